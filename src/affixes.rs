@@ -0,0 +1,320 @@
+// Elite affixes: extra traits rolled onto the elite of an elite wave.
+//
+// Elite waves (see create_elite_army and the depth map) mark their first
+// enemy with EliteAffixes. When that component shows up, apply_affixes_system
+// turns each affix into ordinary components:
+//   - Armored:    Armor — flat damage reduction, armor_system below
+//   - Swift:      a faster Speed
+//   - Vampiric:   Lifesteal — heals on hit, lifesteal_system below
+//   - Splitting:  SplitsOnDeath — breaks into two small slimes (splitting.rs)
+//   - Frost Aura: FrostAura — chills nearby player units, which slows them
+//                 through their Footing (footing_system, hazards.rs)
+//   - Enraged:    Enraged — attack damage rises as HP falls
+//
+// Armor and Lifesteal hook into the damage pipeline (damage.rs): armor in
+// its Calculate stage, lifesteal in PostDamage.
+//
+// Each affix raises the elite's GoopValue and gets a coloured badge above
+// its head. Stronger affixes only appear deeper (min_depth), and deep elites
+// roll a second affix.
+
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::combat::{Armor, KnownAttacks, Lifesteal};
+use crate::damage::{DamageQueue, DamageReason, DamageStage};
+use crate::health::{Dying, Health, MaxHealth};
+use crate::movement::Speed;
+use crate::pick_target::Team;
+use crate::spawn_slimes::GoopValue;
+use crate::splitting::SplitsOnDeath;
+use crate::{GameFont, GameState};
+
+pub struct AffixesPlugin;
+
+impl Plugin for AffixesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (apply_affixes_system, enraged_system, frost_aura_system)
+                .run_if(in_state(GameState::Combat)),
+        )
+        .add_systems(
+            Update,
+            (
+                armor_system.in_set(DamageStage::Calculate),
+                lifesteal_system.in_set(DamageStage::PostDamage),
+            ),
+        );
+    }
+}
+
+/// Elites at or below this depth get one affix; deeper ones get two.
+const SINGLE_AFFIX_MAX_DEPTH: u32 = 9;
+
+pub const ARMOR: i32 = 1;
+const SWIFT_SPEED_MULTIPLIER: f32 = 1.6;
+const LIFESTEAL: i32 = 1;
+/// Splitting elites break into two slimes with 30% of their max HP each.
+const ELITE_SPLIT: SplitsOnDeath = SplitsOnDeath {
+    count: 2,
+    hp_fraction: 0.3,
+};
+const FROST_AURA_RADIUS: f32 = 150.0;
+/// Player units inside a frost aura move at this fraction of their speed.
+const FROST_AURA_SLOW: f32 = 0.6;
+/// Extra damage at (almost) zero HP. Scales linearly with HP lost.
+pub const ENRAGED_MAX_BONUS: i32 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Affix {
+    Armored,
+    Swift,
+    Vampiric,
+    Splitting,
+    FrostAura,
+    Enraged,
+}
+
+pub struct AffixDef {
+    pub affix: Affix,
+    pub name: &'static str,
+    /// Short text shown in the badge above the elite.
+    pub badge: &'static str,
+    pub color: Color,
+    /// Added to the elite's GoopValue.
+    pub goop_bonus: u32,
+    /// Shallowest depth this affix can roll at.
+    pub min_depth: u32,
+}
+
+pub const AFFIXES: &[AffixDef] = &[
+    AffixDef {
+        affix: Affix::Armored,
+        name: "Armored",
+        badge: "ARM",
+        color: Color::srgb(0.7, 0.7, 0.75),
+        goop_bonus: 2,
+        min_depth: 4,
+    },
+    AffixDef {
+        affix: Affix::Swift,
+        name: "Swift",
+        badge: "SWF",
+        color: Color::srgb(0.4, 0.9, 0.5),
+        goop_bonus: 1,
+        min_depth: 4,
+    },
+    AffixDef {
+        affix: Affix::Vampiric,
+        name: "Vampiric",
+        badge: "VMP",
+        color: Color::srgb(0.85, 0.15, 0.3),
+        goop_bonus: 2,
+        min_depth: 6,
+    },
+    AffixDef {
+        affix: Affix::Enraged,
+        name: "Enraged",
+        badge: "RGE",
+        color: Color::srgb(1.0, 0.45, 0.1),
+        goop_bonus: 2,
+        min_depth: 6,
+    },
+    AffixDef {
+        affix: Affix::Splitting,
+        name: "Splitting",
+        badge: "SPL",
+        color: Color::srgb(0.8, 0.8, 0.2),
+        goop_bonus: 3,
+        min_depth: 8,
+    },
+    AffixDef {
+        affix: Affix::FrostAura,
+        name: "Frost Aura",
+        badge: "FRS",
+        color: Color::srgb(0.5, 0.8, 1.0),
+        goop_bonus: 3,
+        min_depth: 10,
+    },
+];
+
+impl Affix {
+    pub fn def(self) -> &'static AffixDef {
+        AFFIXES
+            .iter()
+            .find(|d| d.affix == self)
+            .expect("every affix has an AFFIXES entry")
+    }
+}
+
+/// Rolls the affixes for an elite at `depth`, respecting min_depth.
+pub fn roll_affixes(depth: u32, rng: &mut impl Rng) -> Vec<Affix> {
+    let allowed: Vec<Affix> = AFFIXES
+        .iter()
+        .filter(|d| depth >= d.min_depth)
+        .map(|d| d.affix)
+        .collect();
+    let count = if depth <= SINGLE_AFFIX_MAX_DEPTH {
+        1
+    } else {
+        2
+    };
+    allowed.choose_multiple(rng, count).copied().collect()
+}
+
+/// The elite's affixes. Inserted by the spawn system on the first enemy of
+/// an elite wave.
+#[derive(Component)]
+pub struct EliteAffixes(pub Vec<Affix>);
+
+/// Slows opposing units within the radius.
+#[derive(Component)]
+pub struct FrostAura(pub f32);
+
+/// Damage currently added to this unit's attacks by Enraged.
+#[derive(Component, Default)]
+pub struct Enraged(i32);
+
+/// A unit standing in a frost aura. footing_system scales its top speed by
+/// this.
+#[derive(Component)]
+pub struct Chilled(pub f32);
+
+fn apply_affixes_system(
+    mut commands: Commands,
+    mut elites: Query<
+        (
+            Entity,
+            &EliteAffixes,
+            Option<&mut Speed>,
+            Option<&mut GoopValue>,
+        ),
+        Added<EliteAffixes>,
+    >,
+    game_font: Res<GameFont>,
+) {
+    for (entity, affixes, mut speed, mut goop_value) in &mut elites {
+        for affix in &affixes.0 {
+            if let Some(goop_value) = goop_value.as_mut() {
+                goop_value.0 += affix.def().goop_bonus;
+            }
+            match affix {
+                Affix::Armored => {
+                    commands.entity(entity).insert(Armor(ARMOR));
+                }
+                Affix::Swift => {
+                    if let Some(speed) = speed.as_mut() {
+                        speed.0 *= SWIFT_SPEED_MULTIPLIER;
+                    }
+                }
+                Affix::Vampiric => {
+                    commands.entity(entity).insert(Lifesteal(LIFESTEAL));
+                }
+                Affix::Splitting => {
+                    commands.entity(entity).insert(ELITE_SPLIT);
+                }
+                Affix::FrostAura => {
+                    commands.entity(entity).insert(FrostAura(FROST_AURA_RADIUS));
+                }
+                Affix::Enraged => {
+                    commands.entity(entity).insert(Enraged::default());
+                }
+            }
+        }
+
+        // One badge per affix, stacked above the slime's head.
+        commands.entity(entity).with_children(|parent| {
+            for (i, affix) in affixes.0.iter().enumerate() {
+                let def = affix.def();
+                parent.spawn((
+                    Text2d::new(def.badge),
+                    TextFont {
+                        font: game_font.0.clone(),
+                        font_size: 14.0,
+                        ..default()
+                    },
+                    TextColor(def.color),
+                    Transform::from_xyz(0.0, 28.0 + i as f32 * 14.0, 1.0),
+                ));
+            }
+        });
+    }
+}
+
+/// Keeps each Enraged unit's attack bonus in step with its missing HP.
+fn enraged_system(
+    mut query: Query<
+        (&mut Enraged, &Health, &MaxHealth, &mut KnownAttacks),
+        (Changed<Health>, Without<Dying>),
+    >,
+) {
+    for (mut enraged, health, max_health, mut attacks) in &mut query {
+        let missing = 1.0 - (health.0.max(0) as f32 / max_health.0 as f32);
+        let bonus = (missing * ENRAGED_MAX_BONUS as f32).floor() as i32;
+        if bonus == enraged.0 {
+            continue;
+        }
+        for attack in &mut attacks.0 {
+            attack.on_hit_effect.damage += bonus - enraged.0;
+        }
+        enraged.0 = bonus;
+    }
+}
+
+/// Takes Armor off every hit on an armored target. A hit always keeps at
+/// least 1 damage.
+fn armor_system(mut queue: ResMut<DamageQueue>, armored: Query<&Armor>) {
+    for hit in queue.hits.iter_mut().filter(|hit| hit.lands()) {
+        let Ok(armor) = armored.get(hit.target) else {
+            continue;
+        };
+        if hit.amount > 0 {
+            hit.reduce(DamageReason::Armor, (hit.amount - armor.0).max(1));
+        }
+    }
+}
+
+/// Heals attackers with Lifesteal for every hit of theirs that dealt damage.
+fn lifesteal_system(
+    queue: Res<DamageQueue>,
+    mut attackers: Query<(&Lifesteal, &mut Health, &MaxHealth)>,
+) {
+    for hit in queue.hits.iter().filter(|hit| hit.dealt > 0) {
+        let Some(attacker) = hit.attacker else {
+            continue;
+        };
+        if let Ok((lifesteal, mut health, max_health)) = attackers.get_mut(attacker) {
+            if health.0 > 0 {
+                health.0 = (health.0 + lifesteal.0).min(max_health.0);
+            }
+        }
+    }
+}
+
+/// Chills opposing units standing in any frost aura, and lets them warm up
+/// once they leave (or the aura's owner dies). Speed itself is never
+/// touched, so the slow can't stack with Swift or outlive the aura.
+fn frost_aura_system(
+    mut commands: Commands,
+    auras: Query<(&FrostAura, &Team, &GlobalTransform), Without<Dying>>,
+    units: Query<(Entity, &Team, &GlobalTransform, Has<Chilled>), With<Speed>>,
+) {
+    for (entity, team, transform, chilled) in &units {
+        let pos = transform.translation();
+        let in_aura = auras.iter().any(|(aura, aura_team, aura_pos)| {
+            aura_team != team && aura_pos.translation().distance(pos) <= aura.0
+        });
+
+        match (in_aura, chilled) {
+            (true, false) => {
+                commands.entity(entity).insert(Chilled(FROST_AURA_SLOW));
+            }
+            (false, true) => {
+                commands.entity(entity).remove::<Chilled>();
+            }
+            _ => {}
+        }
+    }
+}
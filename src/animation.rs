@@ -57,6 +57,13 @@ pub enum AnimationType {
     EnemyMageCast,
     // Ice trap spawn — one-shot VFX at the AoE impact point.
     IceTrapSpawn,
+    // Hazard zones (hazards.rs). Icy paths spread, sit, then melt away;
+    // frozen rain gathers (the telegraph) and then pours.
+    IcyPathSpawn,
+    IcyPathIdle,
+    IcyPathDespawn,
+    FrozenRainSpawn,
+    FrozenRainFall,
 }
 
 fn default_animated_sprite() -> Sprite {
@@ -123,6 +130,20 @@ pub struct SpriteSheets {
     // Ice trap spawn — 416x32 sheet (13 frames of 32x32)
     pub ice_trap_spawn: Handle<Image>,
     pub ice_trap_spawn_layout: Handle<TextureAtlasLayout>,
+
+    // Icy path — spawn 192x32 (6 frames), idle 32x32 (1), despawn 288x32 (9)
+    pub icy_path_spawn: Handle<Image>,
+    pub icy_path_spawn_layout: Handle<TextureAtlasLayout>,
+    pub icy_path_idle: Handle<Image>,
+    pub icy_path_idle_layout: Handle<TextureAtlasLayout>,
+    pub icy_path_despawn: Handle<Image>,
+    pub icy_path_despawn_layout: Handle<TextureAtlasLayout>,
+
+    // Frozen rain — spawn 128x32 (4 frames), fall 192x32 (6 frames)
+    pub frozen_rain_spawn: Handle<Image>,
+    pub frozen_rain_spawn_layout: Handle<TextureAtlasLayout>,
+    pub frozen_rain_fall: Handle<Image>,
+    pub frozen_rain_fall_layout: Handle<TextureAtlasLayout>,
 }
 
 impl AnimationState {
@@ -426,6 +447,66 @@ pub fn switch_animation_system(
                     false,
                 );
             }
+            AnimationType::IcyPathSpawn => {
+                sprite.image = sprite_sheets.icy_path_spawn.clone();
+                wip_texture_atlas.layout = sprite_sheets.icy_path_spawn_layout.clone();
+                *anim_state = AnimationState::new(
+                    0.08,
+                    assets
+                        .get(&sprite_sheets.icy_path_spawn_layout)
+                        .unwrap()
+                        .len(),
+                    false,
+                );
+            }
+            AnimationType::IcyPathIdle => {
+                sprite.image = sprite_sheets.icy_path_idle.clone();
+                wip_texture_atlas.layout = sprite_sheets.icy_path_idle_layout.clone();
+                *anim_state = AnimationState::new(
+                    0.1,
+                    assets
+                        .get(&sprite_sheets.icy_path_idle_layout)
+                        .unwrap()
+                        .len(),
+                    true,
+                );
+            }
+            AnimationType::IcyPathDespawn => {
+                sprite.image = sprite_sheets.icy_path_despawn.clone();
+                wip_texture_atlas.layout = sprite_sheets.icy_path_despawn_layout.clone();
+                *anim_state = AnimationState::new(
+                    0.06,
+                    assets
+                        .get(&sprite_sheets.icy_path_despawn_layout)
+                        .unwrap()
+                        .len(),
+                    false,
+                );
+            }
+            AnimationType::FrozenRainSpawn => {
+                sprite.image = sprite_sheets.frozen_rain_spawn.clone();
+                wip_texture_atlas.layout = sprite_sheets.frozen_rain_spawn_layout.clone();
+                *anim_state = AnimationState::new(
+                    0.1,
+                    assets
+                        .get(&sprite_sheets.frozen_rain_spawn_layout)
+                        .unwrap()
+                        .len(),
+                    false,
+                );
+            }
+            AnimationType::FrozenRainFall => {
+                sprite.image = sprite_sheets.frozen_rain_fall.clone();
+                wip_texture_atlas.layout = sprite_sheets.frozen_rain_fall_layout.clone();
+                *anim_state = AnimationState::new(
+                    0.1,
+                    assets
+                        .get(&sprite_sheets.frozen_rain_fall_layout)
+                        .unwrap()
+                        .len(),
+                    true,
+                );
+            }
         }
         sprite.texture_atlas = Some(wip_texture_atlas);
     }
@@ -583,6 +664,48 @@ pub fn load_sprite_sheets(
             None,
             None,
         )),
+
+        // Hazard zones — all 32x32 frames
+        icy_path_spawn: asset_server.load("sprites/TinySpells_BigWander/IcyPath/IcyPath_Spawn_FrozenTome_BigWander.png"),
+        icy_path_spawn_layout: texture_atlas_layouts.add(TextureAtlasLayout::from_grid(
+            UVec2::new(32, 32),
+            6,
+            1,
+            None,
+            None,
+        )),
+        icy_path_idle: asset_server.load("sprites/TinySpells_BigWander/IcyPath/IcyPath_Idle_FrozenTome_BigWander.png"),
+        icy_path_idle_layout: texture_atlas_layouts.add(TextureAtlasLayout::from_grid(
+            UVec2::new(32, 32),
+            1,
+            1,
+            None,
+            None,
+        )),
+        icy_path_despawn: asset_server.load("sprites/TinySpells_BigWander/IcyPath/IcyPath_Despawn_FrozenTome_BigWander.png"),
+        icy_path_despawn_layout: texture_atlas_layouts.add(TextureAtlasLayout::from_grid(
+            UVec2::new(32, 32),
+            9,
+            1,
+            None,
+            None,
+        )),
+        frozen_rain_spawn: asset_server.load("sprites/TinySpells_BigWander/FrozenRain/FrozenRain_LvL1_Spawn_FrozenTome_BigWander.png"),
+        frozen_rain_spawn_layout: texture_atlas_layouts.add(TextureAtlasLayout::from_grid(
+            UVec2::new(32, 32),
+            4,
+            1,
+            None,
+            None,
+        )),
+        frozen_rain_fall: asset_server.load("sprites/TinySpells_BigWander/FrozenRain/FrozenRain_LvL1_Fall_FrozenTome_BigWander.png"),
+        frozen_rain_fall_layout: texture_atlas_layouts.add(TextureAtlasLayout::from_grid(
+            UVec2::new(32, 32),
+            6,
+            1,
+            None,
+            None,
+        )),
     });
 }
 
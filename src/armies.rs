@@ -1,18 +1,77 @@
 use bevy::prelude::*;
+use rand::seq::SliceRandom;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
+use crate::affixes::{roll_affixes, Affix};
+use crate::bosses::{boss_for_depth, is_boss_depth, BossKind};
+use crate::hazards::HazardKind;
+use crate::pick_target::PickTargetStrategy;
+
 pub struct ArmiesPlugin;
 
 impl Plugin for ArmiesPlugin {
     fn build(&self, _app: &mut App) {}
 }
 
+/// Which kind of unit an entity is. Lives on every spawned slime (except
+/// merged ones, which are their own thing) so systems like casualty tracking
+/// can tell units apart without inspecting their attacks or children.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SlimeType {
+    Normal,
+    Tank,
+    Wizard,
+}
+
+impl SlimeType {
+    pub const ALL: [SlimeType; 3] = [SlimeType::Normal, SlimeType::Tank, SlimeType::Wizard];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SlimeType::Normal => "Normal",
+            SlimeType::Tank => "Tank",
+            SlimeType::Wizard => "Wizard",
+        }
+    }
+
+    /// Goop price of the first unit of this type in the Home shop. Each
+    /// additional unit costs one more multiple of this.
+    pub fn base_cost(self) -> u32 {
+        match self {
+            SlimeType::Normal => 1,
+            SlimeType::Tank | SlimeType::Wizard => 10,
+        }
+    }
+
+    /// Targeting this type uses unless the army overrides it.
+    pub fn default_target_strategy(self) -> PickTargetStrategy {
+        match self {
+            SlimeType::Normal => PickTargetStrategy::Close,
+            SlimeType::Tank => PickTargetStrategy::HighestThreat,
+            SlimeType::Wizard => PickTargetStrategy::Backline,
+        }
+    }
+
+    /// How hard this type is to knock around (see steering.rs Mass).
+    pub fn mass(self) -> f32 {
+        match self {
+            SlimeType::Normal => 1.0,
+            SlimeType::Tank => 2.5,
+            SlimeType::Wizard => 0.8,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Army {
     pub normal: NormalSlime,
     pub tanks: TankSlime,
     pub wizards: WizardSlime,
+    #[serde(default)]
+    pub merging: MergeRules,
+    #[serde(default)]
+    pub targeting: TargetingOverrides,
 }
 
 impl Default for Army {
@@ -21,6 +80,26 @@ impl Default for Army {
             normal: NormalSlime::default(),
             tanks: TankSlime::default(),
             wizards: WizardSlime::default(),
+            merging: MergeRules::default(),
+            targeting: TargetingOverrides::default(),
+        }
+    }
+}
+
+impl Army {
+    pub fn count(&self, slime_type: SlimeType) -> u32 {
+        match slime_type {
+            SlimeType::Normal => self.normal.count,
+            SlimeType::Tank => self.tanks.count,
+            SlimeType::Wizard => self.wizards.count,
+        }
+    }
+
+    pub fn count_mut(&mut self, slime_type: SlimeType) -> &mut u32 {
+        match slime_type {
+            SlimeType::Normal => &mut self.normal.count,
+            SlimeType::Tank => &mut self.tanks.count,
+            SlimeType::Wizard => &mut self.wizards.count,
         }
     }
 }
@@ -77,18 +156,225 @@ impl Default for WizardSlime {
     }
 }
 
+/// When an army's slimes are allowed to merge mid-fight. Chosen on the Home
+/// screen for the player's army; enemy armies always use the default.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Merge at the army's base chance.
+    #[default]
+    Sometimes,
+    Never,
+    /// Merge much more often.
+    Aggressive,
+    /// Only merge while the other side has more slimes.
+    WhenOutnumbered,
+    /// Only merge slimes that are both badly hurt.
+    WoundedOnly,
+}
+
+impl MergePolicy {
+    pub const ALL: [MergePolicy; 5] = [
+        MergePolicy::Sometimes,
+        MergePolicy::Never,
+        MergePolicy::Aggressive,
+        MergePolicy::WhenOutnumbered,
+        MergePolicy::WoundedOnly,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MergePolicy::Sometimes => "Sometimes",
+            MergePolicy::Never => "Never",
+            MergePolicy::Aggressive => "Aggressive",
+            MergePolicy::WhenOutnumbered => "Outnumbered",
+            MergePolicy::WoundedOnly => "Wounded",
+        }
+    }
+
+    /// The policy after this one, for the Home screen's cycle button.
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&p| p == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+/// How an army merges: its policy plus the base odds and reach that
+/// check_merge_system uses. Chance and radius are upgradeable.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct MergeRules {
+    pub policy: MergePolicy,
+    /// Chance per merge check for each eligible pair.
+    pub chance: f32,
+    /// How close two slimes must be to merge.
+    pub radius: f32,
+}
+
+impl Default for MergeRules {
+    fn default() -> Self {
+        Self {
+            policy: MergePolicy::Sometimes,
+            chance: 0.005,
+            radius: 150.0,
+        }
+    }
+}
+
+/// Per-type targeting picked on the Home screen. None means the type's
+/// default_target_strategy.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[serde(default)]
+pub struct TargetingOverrides {
+    pub normal: Option<PickTargetStrategy>,
+    pub tanks: Option<PickTargetStrategy>,
+    pub wizards: Option<PickTargetStrategy>,
+}
+
+impl TargetingOverrides {
+    pub fn get(&self, slime_type: SlimeType) -> Option<PickTargetStrategy> {
+        match slime_type {
+            SlimeType::Normal => self.normal,
+            SlimeType::Tank => self.tanks,
+            SlimeType::Wizard => self.wizards,
+        }
+    }
+
+    pub fn get_mut(&mut self, slime_type: SlimeType) -> &mut Option<PickTargetStrategy> {
+        match slime_type {
+            SlimeType::Normal => &mut self.normal,
+            SlimeType::Tank => &mut self.tanks,
+            SlimeType::Wizard => &mut self.wizards,
+        }
+    }
+
+    /// The strategy units of this type actually spawn with.
+    pub fn strategy(&self, slime_type: SlimeType) -> PickTargetStrategy {
+        self.get(slime_type)
+            .unwrap_or_else(|| slime_type.default_target_strategy())
+    }
+}
+
 /// Describes an enemy wave: normal army units plus any pre-made merged slimes.
 /// Merged slimes are tracked separately because they bypass the normal Army
 /// spawn logic — they use a different spawn function with BigSlime animations.
+///
+/// Elite waves also carry affixes (affixes.rs) for the first enemy spawned,
+/// and boss depths carry the boss (bosses.rs), which spawns last. Deeper
+/// waves can bring environmental hazard zones (hazards.rs) too.
+#[derive(Clone)]
 pub struct EnemyWave {
     pub army: Army,
     pub merged_count: u32,
+    pub elite_affixes: Vec<Affix>,
+    pub hazards: Vec<HazardKind>,
+    pub boss: Option<BossKind>,
+}
+
+impl EnemyWave {
+    /// Goop paid out for killing the whole wave. Matches the GoopValue each
+    /// spawn function in spawn_slimes.rs inserts.
+    pub fn goop_value(&self) -> u32 {
+        self.army.normal.count
+            + self.army.tanks.count * 3
+            + self.army.wizards.count * 2
+            + self.merged_count * 5
+            + self
+                .elite_affixes
+                .iter()
+                .map(|a| a.def().goop_bonus)
+                .sum::<u32>()
+            + self.boss.map_or(0, |b| b.def().goop_value)
+    }
+
+    /// e.g. "2 Normal (5 HP), 1 Merged"
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if self.army.normal.count > 0 {
+            parts.push(format!(
+                "{} Normal ({} HP)",
+                self.army.normal.count, self.army.normal.hp
+            ));
+        }
+        if self.army.tanks.count > 0 {
+            parts.push(format!(
+                "{} Tank ({} HP)",
+                self.army.tanks.count, self.army.tanks.hp
+            ));
+        }
+        if self.army.wizards.count > 0 {
+            parts.push(format!(
+                "{} Wizard ({} HP)",
+                self.army.wizards.count, self.army.wizards.hp
+            ));
+        }
+        if self.merged_count > 0 {
+            parts.push(format!("{} Merged", self.merged_count));
+        }
+        if let Some(boss) = self.boss {
+            let def = boss.def();
+            parts.push(format!("BOSS {} ({} HP)", def.name, def.hp));
+        }
+        if !self.elite_affixes.is_empty() {
+            let names: Vec<&str> = self.elite_affixes.iter().map(|a| a.def().name).collect();
+            parts.push(format!("elite: {}", names.join(" + ")));
+        }
+        if !self.hazards.is_empty() {
+            let names: Vec<&str> = self.hazards.iter().map(|h| h.def().name).collect();
+            parts.push(format!("hazards: {}", names.join(" + ")));
+        }
+        parts.join(", ")
+    }
 }
 
 pub fn create_enemy_army(level: u32) -> EnemyWave {
-    let mut rng = rand::thread_rng();
+    roll_enemy_army(level, &mut rand::thread_rng())
+}
+
+/// Elite waves are the wave from a few depths further down, with an extra
+/// normal slime, tougher normals all round, and affixes for the elite itself.
+pub fn create_elite_army(level: u32, rng: &mut impl Rng) -> EnemyWave {
+    // Borrow from the depth before a boss rather than spawning the boss early.
+    let mut source = level + ELITE_DEPTH_OFFSET;
+    if is_boss_depth(source) {
+        source -= 1;
+    }
+    let mut wave = roll_enemy_army(source, rng);
+    wave.army.normal.count += 1;
+    wave.army.normal.hp += ELITE_EXTRA_HP;
+    wave.elite_affixes = roll_affixes(level, rng);
+    wave
+}
+
+const ELITE_DEPTH_OFFSET: u32 = 3;
+const ELITE_EXTRA_HP: i32 = 2;
 
+/// Same as create_enemy_army, but rolls with the given rng so seeded callers
+/// (the depth map) get the same wave every time.
+pub fn roll_enemy_army(level: u32, rng: &mut impl Rng) -> EnemyWave {
+    let mut wave = roll_enemy_units(level, rng);
+    if level >= HAZARD_MIN_DEPTH && !is_boss_depth(level) && rng.gen_bool(HAZARD_WAVE_CHANCE) {
+        wave.hazards.push(*HazardKind::ALL.choose(rng).unwrap());
+    }
+    wave
+}
+
+/// Environmental hazards start turning up at this depth.
+const HAZARD_MIN_DEPTH: u32 = 4;
+const HAZARD_WAVE_CHANCE: f64 = 0.3;
+
+fn roll_enemy_units(level: u32, rng: &mut impl Rng) -> EnemyWave {
     match level {
+        // Every BOSS_INTERVAL depths, the boss replaces the ordinary wave.
+        level if is_boss_depth(level) => EnemyWave {
+            army: Army {
+                normal: NormalSlime { count: 0, hp: 5 },
+                ..Default::default()
+            },
+            merged_count: 0,
+            elite_affixes: Vec::new(),
+            hazards: Vec::new(),
+            boss: Some(boss_for_depth(level)),
+        },
         1..=5 => EnemyWave {
             army: Army {
                 normal: NormalSlime {
@@ -98,6 +384,9 @@ pub fn create_enemy_army(level: u32) -> EnemyWave {
                 ..Default::default()
             },
             merged_count: 0,
+            elite_affixes: Vec::new(),
+            hazards: Vec::new(),
+            boss: None,
         },
         6..=9 => EnemyWave {
             army: Army {
@@ -108,13 +397,9 @@ pub fn create_enemy_army(level: u32) -> EnemyWave {
                 ..Default::default()
             },
             merged_count: 0,
-        },
-        10 => EnemyWave {
-            army: Army {
-                normal: NormalSlime { count: 0, hp: 5 },
-                ..Default::default()
-            },
-            merged_count: 1,
+            elite_affixes: Vec::new(),
+            hazards: Vec::new(),
+            boss: None,
         },
         _ => EnemyWave {
             army: Army {
@@ -122,6 +407,9 @@ pub fn create_enemy_army(level: u32) -> EnemyWave {
                 ..Default::default()
             },
             merged_count: 0,
+            elite_affixes: Vec::new(),
+            hazards: Vec::new(),
+            boss: None,
         },
     }
 }
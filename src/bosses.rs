@@ -0,0 +1,514 @@
+// Bosses: scripted encounters every BOSS_INTERVAL depths.
+//
+// A boss takes the whole wave slot at its depth (see roll_enemy_army) and is
+// built on top of spawn_merged_slime with its own HP, attack, and scale.
+// Each boss has a list of phases, entered once its HP drops to the phase's
+// threshold. Entering a phase runs the phase's actions in order:
+//   - Summon: spawn a few normal slimes next to the boss, ready to fight
+//   - SwitchAttack: replace the boss's KnownAttacks
+//   - Invulnerable: ignore all hits for a few seconds
+//   - Hazards: drop hazard zones (hazards.rs) on top of player units
+//
+// While a boss is alive, a large HP bar at the bottom of the screen shows its
+// name, phase, and health. Killing a boss pays a goop reward on top of its
+// GoopValue and grants a free relic for the rest of the run.
+
+use bevy::prelude::*;
+use rand::seq::{IteratorRandom, SliceRandom};
+use rand::Rng;
+
+use crate::animation::AnimationType;
+use crate::combat::{Attack, AttackEffect, FloatingText, Invulnerable, KnownAttacks};
+use crate::end_round::GoopEarned;
+use crate::hazards::{spawn_hazard, Hazard, HazardKind};
+use crate::health::{Dying, Health, MaxHealth};
+use crate::hitboxes::big_slime_hitbox;
+use crate::merge_recipes::MergeKind;
+use crate::pick_target::Team;
+use crate::relics::RunRelics;
+use crate::setup_round::Inert;
+use crate::spawn_slimes::{spawn_merged_slime, spawn_normal_slime, GoopValue};
+use crate::splitting::SplitsOnDeath;
+use crate::sprite_modifications::SpriteModification;
+use crate::steering::Mass;
+use crate::{CombatState, GameFont, GameState};
+
+pub struct BossesPlugin;
+
+impl Plugin for BossesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_boss_hud_system,
+                update_boss_hud_system,
+                boss_death_system,
+            )
+                .run_if(in_state(GameState::Combat)),
+        )
+        .add_systems(
+            Update,
+            boss_phase_system.run_if(in_state(CombatState::DuringCombat)),
+        );
+    }
+}
+
+/// A boss shows up at every multiple of this depth.
+pub const BOSS_INTERVAL: u32 = 10;
+const BOSS_SCALE: f32 = 2.0;
+/// Bosses barely budge when hit, and scatter whatever is flung into them.
+const BOSS_MASS: f32 = 6.0;
+
+const HUD_BAR_WIDTH: f32 = 600.0;
+/// The HUD sits bottom-centre, since the side bets panel (side_bets.rs)
+/// takes the top of the screen, lifted clear of the relic row (relics.rs).
+const HUD_BOTTOM: f32 = 72.0;
+const HUD_BAR_COLOR: Color = Color::srgb(0.75, 0.15, 0.15);
+const HUD_INVULNERABLE_COLOR: Color = Color::srgb(0.6, 0.6, 0.7);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BossKind {
+    SlimeKing,
+    FrostTyrant,
+}
+
+/// The boss's attack, built into a full Attack with big slime animations.
+#[derive(Clone, Copy)]
+pub struct BossAttack {
+    pub damage: i32,
+    pub range: f32,
+    pub knockback: f32,
+    pub aoe_distance: Option<f32>,
+}
+
+pub enum PhaseAction {
+    Summon {
+        count: u32,
+        hp: i32,
+    },
+    SwitchAttack(BossAttack),
+    /// Seconds of invulnerability.
+    Invulnerable(f32),
+    /// Zones of `kind` dropped on random player units, with the boss's own
+    /// radius, damage per tick and active duration.
+    Hazards {
+        kind: HazardKind,
+        count: usize,
+        radius: f32,
+        damage: i32,
+        duration: f32,
+    },
+}
+
+pub struct BossPhase {
+    pub name: &'static str,
+    /// Entered once HP / MaxHP drops to this or below.
+    pub hp_threshold: f32,
+    pub actions: &'static [PhaseAction],
+}
+
+pub struct BossDef {
+    pub kind: BossKind,
+    pub name: &'static str,
+    pub hp: i32,
+    pub attack: BossAttack,
+    /// GoopValue of the boss entity, like any other enemy.
+    pub goop_value: u32,
+    /// Extra goop paid when the boss dies.
+    pub reward_goop: u32,
+    /// In order of decreasing hp_threshold.
+    pub phases: &'static [BossPhase],
+}
+
+pub const BOSSES: &[BossDef] = &[
+    BossDef {
+        kind: BossKind::SlimeKing,
+        name: "Slime King",
+        hp: 120,
+        attack: BossAttack {
+            damage: 6,
+            range: 100.0,
+            knockback: 80.0,
+            aoe_distance: None,
+        },
+        goop_value: 10,
+        reward_goop: 25,
+        phases: &[
+            BossPhase {
+                name: "Royal Guard",
+                hp_threshold: 0.66,
+                actions: &[
+                    PhaseAction::Invulnerable(3.0),
+                    PhaseAction::Summon { count: 3, hp: 5 },
+                ],
+            },
+            BossPhase {
+                name: "Tantrum",
+                hp_threshold: 0.33,
+                actions: &[
+                    PhaseAction::SwitchAttack(BossAttack {
+                        damage: 9,
+                        range: 120.0,
+                        knockback: 150.0,
+                        aoe_distance: Some(90.0),
+                    }),
+                    PhaseAction::Hazards {
+                        kind: HazardKind::FrozenRain,
+                        count: 2,
+                        radius: 70.0,
+                        damage: 1,
+                        duration: 6.0,
+                    },
+                ],
+            },
+        ],
+    },
+    BossDef {
+        kind: BossKind::FrostTyrant,
+        name: "Frost Tyrant",
+        hp: 160,
+        attack: BossAttack {
+            damage: 5,
+            range: 110.0,
+            knockback: 0.0,
+            aoe_distance: Some(80.0),
+        },
+        goop_value: 15,
+        reward_goop: 40,
+        phases: &[
+            BossPhase {
+                name: "Blizzard",
+                hp_threshold: 0.75,
+                actions: &[
+                    PhaseAction::Hazards {
+                        kind: HazardKind::FrozenRain,
+                        count: 3,
+                        radius: 80.0,
+                        damage: 1,
+                        duration: 8.0,
+                    },
+                    PhaseAction::Hazards {
+                        kind: HazardKind::IcyPath,
+                        count: 2,
+                        radius: 100.0,
+                        damage: 0,
+                        duration: 8.0,
+                    },
+                ],
+            },
+            BossPhase {
+                name: "Ice Wall",
+                hp_threshold: 0.5,
+                actions: &[
+                    PhaseAction::Invulnerable(4.0),
+                    PhaseAction::Summon { count: 2, hp: 8 },
+                    PhaseAction::SwitchAttack(BossAttack {
+                        damage: 4,
+                        range: 300.0,
+                        knockback: 0.0,
+                        aoe_distance: Some(120.0),
+                    }),
+                ],
+            },
+            BossPhase {
+                name: "Shatter",
+                hp_threshold: 0.2,
+                actions: &[
+                    PhaseAction::Summon { count: 4, hp: 5 },
+                    PhaseAction::Hazards {
+                        kind: HazardKind::FrozenRain,
+                        count: 4,
+                        radius: 80.0,
+                        damage: 2,
+                        duration: 8.0,
+                    },
+                ],
+            },
+        ],
+    },
+];
+
+impl BossKind {
+    pub fn def(self) -> &'static BossDef {
+        BOSSES
+            .iter()
+            .find(|d| d.kind == self)
+            .expect("every boss has a BOSSES entry")
+    }
+}
+
+impl BossAttack {
+    fn to_attack(self) -> Attack {
+        Attack {
+            animation: AnimationType::EnemyBigSlimeAttack,
+            hit_frame: 3,
+            on_hit_effect: AttackEffect {
+                damage: self.damage,
+                knockback: self.knockback,
+                aoe_distance: self.aoe_distance,
+                ..default()
+            },
+            range: self.range,
+            hitbox: big_slime_hitbox(self.range),
+        }
+    }
+}
+
+pub fn is_boss_depth(depth: u32) -> bool {
+    depth > 0 && depth % BOSS_INTERVAL == 0
+}
+
+/// Bosses take turns: the first boss depth gets BOSSES[0], the next BOSSES[1], ...
+pub fn boss_for_depth(depth: u32) -> BossKind {
+    let index = (depth / BOSS_INTERVAL).saturating_sub(1) as usize % BOSSES.len();
+    BOSSES[index].kind
+}
+
+#[derive(Component)]
+pub struct Boss {
+    pub kind: BossKind,
+    /// How many phases have been entered so far.
+    pub phase: usize,
+}
+
+#[derive(Component)]
+struct BossHud(Entity);
+
+#[derive(Component)]
+struct BossHpFill(Entity);
+
+#[derive(Component)]
+struct BossHudText(Entity);
+
+/// Spawns a boss as an enemy. Called by spawn_slimes_system when the wave
+/// has a boss; it spawns after the rest of the wave.
+pub fn spawn_boss(commands: &mut Commands, kind: BossKind) -> Entity {
+    let def = kind.def();
+    let pos = Vec3::new(300.0, rand::thread_rng().gen_range(-150.0..150.0), 0.0);
+    let entity = spawn_merged_slime(commands, Team::Enemy, Some(pos));
+    // The boss keeps its size instead of playing the spawn-scale animation,
+    // which would settle it back to scale 1. It doesn't split on death, and
+    // without a MergeKind it never merges either.
+    commands
+        .entity(entity)
+        .remove::<(SpriteModification, SplitsOnDeath, MergeKind)>()
+        .insert((
+            Boss { kind, phase: 0 },
+            Health(def.hp),
+            MaxHealth(def.hp),
+            KnownAttacks(vec![def.attack.to_attack()]),
+            GoopValue(def.goop_value),
+            Transform::from_translation(pos).with_scale(Vec3::splat(BOSS_SCALE)),
+            Mass(BOSS_MASS),
+        ));
+    entity
+}
+
+fn spawn_boss_hud_system(
+    mut commands: Commands,
+    bosses: Query<(Entity, &Boss), Added<Boss>>,
+    game_font: Res<GameFont>,
+) {
+    for (entity, boss) in &bosses {
+        commands
+            .spawn((
+                BossHud(entity),
+                DespawnOnExit(GameState::Combat),
+                Node {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(HUD_BOTTOM),
+                    width: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(6.0),
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ))
+            .with_children(|hud| {
+                hud.spawn((
+                    BossHudText(entity),
+                    Text::new(boss.kind.def().name),
+                    TextFont {
+                        font: game_font.0.clone(),
+                        font_size: 28.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ));
+                hud.spawn((
+                    Node {
+                        width: Val::Px(HUD_BAR_WIDTH),
+                        height: Val::Px(20.0),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.1, 0.1, 0.1)),
+                    BorderColor::all(Color::WHITE),
+                ))
+                .with_child((
+                    BossHpFill(entity),
+                    Node {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(HUD_BAR_COLOR),
+                ));
+            });
+    }
+}
+
+fn update_boss_hud_system(
+    bosses: Query<(&Boss, &Health, &MaxHealth, Has<Invulnerable>)>,
+    mut fills: Query<(&BossHpFill, &mut Node, &mut BackgroundColor)>,
+    mut texts: Query<(&BossHudText, &mut Text)>,
+) {
+    for (fill, mut node, mut bg) in &mut fills {
+        let Ok((_, health, max_health, invulnerable)) = bosses.get(fill.0) else {
+            continue;
+        };
+        let ratio = (health.0.max(0) as f32 / max_health.0 as f32).clamp(0.0, 1.0);
+        node.width = Val::Percent(ratio * 100.0);
+        *bg = if invulnerable {
+            HUD_INVULNERABLE_COLOR.into()
+        } else {
+            HUD_BAR_COLOR.into()
+        };
+    }
+    for (hud_text, mut text) in &mut texts {
+        let Ok((boss, _, _, invulnerable)) = bosses.get(hud_text.0) else {
+            continue;
+        };
+        let def = boss.kind.def();
+        let mut label = def.name.to_string();
+        if let Some(phase) = boss.phase.checked_sub(1).and_then(|i| def.phases.get(i)) {
+            label.push_str(&format!(" - {}", phase.name));
+        }
+        if invulnerable {
+            label.push_str(" (INVULNERABLE)");
+        }
+        if **text != label {
+            **text = label;
+        }
+    }
+}
+
+/// Enters every phase whose threshold the boss's HP has dropped past. A big
+/// hit can skip straight through several phases; each one still fires.
+fn boss_phase_system(
+    mut commands: Commands,
+    mut bosses: Query<(Entity, &mut Boss, &Health, &MaxHealth, &Transform), Changed<Health>>,
+    players: Query<(&Team, &GlobalTransform), (With<Health>, Without<Dying>)>,
+) {
+    let mut rng = rand::thread_rng();
+    for (entity, mut boss, health, max_health, transform) in &mut bosses {
+        if health.0 <= 0 {
+            continue;
+        }
+        let def = boss.kind.def();
+        let ratio = health.0 as f32 / max_health.0 as f32;
+
+        while let Some(phase) = def.phases.get(boss.phase) {
+            if ratio > phase.hp_threshold {
+                break;
+            }
+            boss.phase += 1;
+            info!("{} enters phase {}", def.name, phase.name);
+
+            for action in phase.actions {
+                match *action {
+                    PhaseAction::Summon { count, hp } => {
+                        for i in 0..count {
+                            let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+                            let pos = transform.translation
+                                + Vec3::new(angle.cos(), angle.sin(), 0.0) * 80.0;
+                            let add = spawn_normal_slime(&mut commands, Team::Enemy, hp);
+                            commands
+                                .entity(add)
+                                .insert(Transform::from_translation(pos))
+                                .remove::<(Inert, SpriteModification)>();
+                        }
+                    }
+                    PhaseAction::SwitchAttack(attack) => {
+                        commands
+                            .entity(entity)
+                            .insert(KnownAttacks(vec![attack.to_attack()]));
+                    }
+                    PhaseAction::Invulnerable(seconds) => {
+                        commands
+                            .entity(entity)
+                            .insert(Invulnerable(Timer::from_seconds(seconds, TimerMode::Once)));
+                    }
+                    PhaseAction::Hazards {
+                        kind,
+                        count,
+                        radius,
+                        damage,
+                        duration,
+                    } => {
+                        let spots: Vec<Vec3> = players
+                            .iter()
+                            .filter(|(team, _)| **team == Team::Player)
+                            .map(|(_, t)| t.translation())
+                            .choose_multiple(&mut rng, count);
+                        for spot in spots {
+                            let hazard = Hazard {
+                                radius,
+                                damage,
+                                lifetime: Timer::from_seconds(duration, TimerMode::Once),
+                                ..Hazard::new(kind, Some(Team::Enemy))
+                            };
+                            spawn_hazard(&mut commands, hazard, spot.truncate());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Pays the boss's reward the moment it starts dying: bonus goop for the
+/// run and one relic the run doesn't have yet.
+fn boss_death_system(
+    mut commands: Commands,
+    bosses: Query<(Entity, &Boss, &Transform), Added<Dying>>,
+    huds: Query<(Entity, &BossHud)>,
+    mut goop_earned: ResMut<GoopEarned>,
+    relics: Option<ResMut<RunRelics>>,
+    game_font: Res<GameFont>,
+) {
+    let mut relics = relics;
+    for (entity, boss, transform) in &bosses {
+        let def = boss.kind.def();
+        goop_earned.0 += def.reward_goop;
+
+        let mut reward = format!("{} defeated! +{} goop", def.name, def.reward_goop);
+        if let Some(relics) = relics.as_mut() {
+            if let Some(relic) = relics.unowned().choose(&mut rand::thread_rng()).copied() {
+                relics.0.push(relic);
+                reward.push_str(&format!(", relic: {}", relic.def().name));
+            }
+        }
+        info!("{reward}");
+
+        let pos = transform.translation;
+        commands.spawn((
+            FloatingText(Timer::from_seconds(3.0, TimerMode::Once)),
+            Text2d::new(reward),
+            TextFont {
+                font: game_font.0.clone(),
+                font_size: 24.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Transform::from_xyz(pos.x, pos.y + 60.0, 10.0),
+        ));
+
+        for (hud_entity, hud) in &huds {
+            if hud.0 == entity {
+                commands.entity(hud_entity).despawn();
+            }
+        }
+    }
+}
@@ -0,0 +1,676 @@
+// Camp: the stop between depths.
+//
+// "Risk deeper" no longer drops you straight into the next fight. Survivors
+// keep whatever HP they had, so the camp screen lists each one's
+// Health/MaxHealth and offers three ways to deal with the damage:
+//   - Heal: pay goop from this run to restore a unit to full HP
+//   - Rest: heal everyone a percentage for free, but skip this depth's goop multiplier
+//   - Dismiss: send a badly wounded unit home for a partial goop refund
+//
+// Camp is also where insurance for the next fight is bought (see risk.rs).
+//
+// Dismissed slimes walk home alive — they don't count toward casualties.
+
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+
+use crate::armies::SlimeType;
+use crate::end_round::{CombatLevel, GoopEarned};
+use crate::health::{Dying, Health, MaxHealth};
+use crate::merge_recipes::MergeKind;
+use crate::pick_target::Team;
+use crate::risk::{venture_quote, RunRisk, INSURANCE_TIERS};
+use crate::splitting::SplitSpawn;
+use crate::{CombatState, GameFont};
+
+pub struct CampPlugin;
+
+impl Plugin for CampPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(CombatState::Camp), setup_camp)
+            .add_systems(OnExit(CombatState::Camp), cleanup_camp)
+            .add_systems(
+                Update,
+                (
+                    heal_button_system,
+                    heal_all_button_system,
+                    dismiss_button_system,
+                    rest_button_system,
+                    continue_button_system,
+                    insurance_button_system,
+                    update_camp_cards_system,
+                    update_camp_goop_text,
+                    camp_hint_system,
+                    camp_scroll_system,
+                    button_hover_system,
+                )
+                    .run_if(in_state(CombatState::Camp)),
+            );
+    }
+}
+
+/// Goop per missing HP when paying to heal.
+const HEAL_COST_PER_HP: u32 = 1;
+/// Fraction of max HP restored to every survivor by resting.
+pub const REST_HEAL_FRACTION: f32 = 0.5;
+/// Units at or below this HP ratio count as badly wounded and can be dismissed.
+const DISMISS_THRESHOLD: f32 = 0.35;
+/// Fraction of the unit's shop price refunded on dismissal.
+const DISMISS_REFUND_FRACTION: f32 = 0.5;
+
+const BUTTON_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const BUTTON_HOVER_COLOR: Color = Color::srgb(0.35, 0.35, 0.35);
+const BUTTON_PRESSED_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
+const CARD_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.9);
+
+/// Whether the player rested at this camp. Resting is once per camp and
+/// forfeits the goop multiplier when continuing.
+#[derive(Resource, Default)]
+struct CampRest(bool);
+
+/// One card per surviving player slime. The Entity is the slime it describes.
+#[derive(Component)]
+struct CampCard(Entity);
+
+#[derive(Component)]
+struct CampCardText(Entity);
+
+#[derive(Component)]
+struct HealButton(Entity);
+
+#[derive(Component)]
+struct DismissButton(Entity);
+
+#[derive(Component)]
+struct HealAllButton;
+
+#[derive(Component)]
+struct RestButton;
+
+#[derive(Component)]
+struct ContinueButton;
+
+/// Buys the INSURANCE_TIERS entry at this index.
+#[derive(Component)]
+struct InsuranceButton(usize);
+
+#[derive(Component)]
+struct CampGoopText;
+
+#[derive(Component)]
+struct CampHintText;
+
+#[derive(Component)]
+struct CampScrollArea;
+
+fn heal_cost(health: &Health, max_health: &MaxHealth) -> u32 {
+    (max_health.0 - health.0).max(0) as u32 * HEAL_COST_PER_HP
+}
+
+fn is_badly_wounded(health: &Health, max_health: &MaxHealth) -> bool {
+    health.0 as f32 / max_health.0 as f32 <= DISMISS_THRESHOLD
+}
+
+/// Merged slimes have no SlimeType — they can be healed but not dismissed,
+/// since there's no shop price to refund. Nor can slimes split off another
+/// (SplitSpawn): they carry a SlimeType but were never bought.
+fn dismiss_refund(slime_type: SlimeType) -> u32 {
+    (slime_type.base_cost() as f32 * DISMISS_REFUND_FRACTION).ceil() as u32
+}
+
+fn unit_label(slime_type: Option<&SlimeType>, merge_kind: Option<&MergeKind>) -> &'static str {
+    match (slime_type, merge_kind) {
+        (Some(t), _) => t.label(),
+        (None, Some(kind)) => kind.def().name,
+        (None, None) => "Merged",
+    }
+}
+
+fn setup_camp(
+    mut commands: Commands,
+    game_font: Res<GameFont>,
+    goop_earned: Res<GoopEarned>,
+    survivors: Query<
+        (
+            Entity,
+            &Team,
+            Option<&SlimeType>,
+            Option<&MergeKind>,
+            Has<SplitSpawn>,
+        ),
+        (With<Health>, Without<Dying>),
+    >,
+) {
+    commands.insert_resource(CampRest::default());
+    let font = game_font.0.clone();
+
+    commands
+        .spawn((
+            DespawnOnExit(CombatState::Camp),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(16.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        ))
+        .with_children(|root| {
+            root.spawn((
+                Text::new("CAMP"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 80.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            root.spawn((
+                CampGoopText,
+                Text::new(format!("Goop this run: {}", goop_earned.0)),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 30.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.4, 0.9, 0.2)),
+            ));
+
+            // Survivor cards. With a big army this can be dozens of cards,
+            // so the grid wraps and scrolls vertically with the mouse wheel.
+            root.spawn((
+                CampScrollArea,
+                Node {
+                    width: Val::Px(1000.0),
+                    max_height: Val::Px(420.0),
+                    flex_wrap: FlexWrap::Wrap,
+                    justify_content: JustifyContent::Center,
+                    column_gap: Val::Px(8.0),
+                    row_gap: Val::Px(8.0),
+                    overflow: Overflow::scroll_y(),
+                    ..default()
+                },
+                ScrollPosition::default(),
+            ))
+            .with_children(|grid| {
+                for (entity, team, slime_type, merge_kind, split) in &survivors {
+                    if *team != Team::Player {
+                        continue;
+                    }
+                    let refundable = slime_type.is_some() && !split;
+                    spawn_camp_card(grid, &font, entity, slime_type, merge_kind, refundable);
+                }
+            });
+
+            root.spawn(Node {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(20.0),
+                ..default()
+            })
+            .with_children(|row| {
+                spawn_camp_button(row, &font, "Heal all", 200.0, HealAllButton);
+                spawn_camp_button(row, &font, "Rest", 200.0, RestButton);
+                spawn_camp_button(row, &font, "Continue", 200.0, ContinueButton);
+            });
+
+            root.spawn(Node {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(20.0),
+                ..default()
+            })
+            .with_children(|row| {
+                for (index, tier) in INSURANCE_TIERS.iter().enumerate() {
+                    let label = format!("Insure {}%", (tier.keep_fraction * 100.0) as u32);
+                    spawn_camp_button(row, &font, &label, 200.0, InsuranceButton(index));
+                }
+            });
+
+            root.spawn((
+                CampHintText,
+                Text::new(" "),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+fn spawn_camp_card(
+    parent: &mut ChildSpawnerCommands,
+    font: &Handle<Font>,
+    unit: Entity,
+    slime_type: Option<&SlimeType>,
+    merge_kind: Option<&MergeKind>,
+    refundable: bool,
+) {
+    parent
+        .spawn((
+            CampCard(unit),
+            Node {
+                width: Val::Px(150.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(CARD_COLOR),
+        ))
+        .with_children(|card| {
+            card.spawn((
+                CampCardText(unit),
+                Text::new(unit_label(slime_type, merge_kind)),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            card.spawn(Node {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(4.0),
+                ..default()
+            })
+            .with_children(|row| {
+                spawn_camp_button(row, font, "Heal", 65.0, HealButton(unit));
+                if refundable {
+                    spawn_camp_button(row, font, "Drop", 65.0, DismissButton(unit));
+                }
+            });
+        });
+}
+
+fn spawn_camp_button(
+    parent: &mut ChildSpawnerCommands,
+    font: &Handle<Font>,
+    label: &str,
+    width: f32,
+    marker: impl Component,
+) {
+    let (height, font_size) = if width < 100.0 {
+        (28.0, 16.0)
+    } else {
+        (55.0, 32.0)
+    };
+
+    parent
+        .spawn((
+            marker,
+            Button,
+            Node {
+                width: Val::Px(width),
+                height: Val::Px(height),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+        ))
+        .with_children(|btn| {
+            btn.spawn((
+                Text::new(label),
+                TextFont {
+                    font: font.clone(),
+                    font_size,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+fn heal_button_system(
+    query: Query<(&Interaction, &HealButton), Changed<Interaction>>,
+    mut units: Query<(&mut Health, &MaxHealth), Without<Dying>>,
+    mut goop_earned: ResMut<GoopEarned>,
+) {
+    for (interaction, heal_btn) in &query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Ok((mut health, max_health)) = units.get_mut(heal_btn.0) else {
+            continue;
+        };
+
+        let cost = heal_cost(&health, max_health);
+        if cost == 0 || goop_earned.0 < cost {
+            continue;
+        }
+        goop_earned.0 -= cost;
+        health.0 = max_health.0;
+    }
+}
+
+/// Heals as many units as the run's goop can pay for, most wounded first.
+fn heal_all_button_system(
+    query: Query<&Interaction, (Changed<Interaction>, With<HealAllButton>)>,
+    mut units: Query<(&Team, &mut Health, &MaxHealth), Without<Dying>>,
+    mut goop_earned: ResMut<GoopEarned>,
+) {
+    if !query.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+
+    let mut wounded: Vec<_> = units
+        .iter_mut()
+        .filter(|(team, health, max)| **team == Team::Player && health.0 < max.0)
+        .map(|(_, health, max)| (health, max))
+        .collect();
+    wounded.sort_by(|(a, a_max), (b, b_max)| {
+        let ratio_a = a.0 as f32 / a_max.0 as f32;
+        let ratio_b = b.0 as f32 / b_max.0 as f32;
+        ratio_a.total_cmp(&ratio_b)
+    });
+
+    for (health, max_health) in wounded.iter_mut() {
+        let cost = heal_cost(health, max_health);
+        if goop_earned.0 < cost {
+            continue;
+        }
+        goop_earned.0 -= cost;
+        health.0 = max_health.0;
+    }
+}
+
+fn dismiss_button_system(
+    mut commands: Commands,
+    query: Query<(&Interaction, &DismissButton), Changed<Interaction>>,
+    units: Query<(&Team, &Health, &MaxHealth, &SlimeType), (Without<Dying>, Without<SplitSpawn>)>,
+    player_units: Query<&Team, (With<Health>, Without<Dying>)>,
+    mut goop_earned: ResMut<GoopEarned>,
+) {
+    for (interaction, dismiss_btn) in &query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Ok((team, health, max_health, slime_type)) = units.get(dismiss_btn.0) else {
+            continue;
+        };
+        if *team != Team::Player || !is_badly_wounded(health, max_health) {
+            continue;
+        }
+
+        // Never dismiss the last survivor — continuing with nobody would
+        // just be an instant defeat.
+        let remaining = player_units.iter().filter(|t| **t == Team::Player).count();
+        if remaining <= 1 {
+            continue;
+        }
+
+        goop_earned.0 += dismiss_refund(*slime_type);
+        commands.entity(dismiss_btn.0).despawn();
+    }
+}
+
+fn rest_button_system(
+    query: Query<&Interaction, (Changed<Interaction>, With<RestButton>)>,
+    mut units: Query<(&Team, &mut Health, &MaxHealth), Without<Dying>>,
+    mut rest: ResMut<CampRest>,
+) {
+    if rest.0 || !query.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+    rest.0 = true;
+
+    for (team, mut health, max_health) in &mut units {
+        if *team != Team::Player {
+            continue;
+        }
+        let heal = (max_health.0 as f32 * REST_HEAL_FRACTION).ceil() as i32;
+        health.0 = (health.0 + heal).min(max_health.0);
+    }
+}
+
+/// Leaves camp: applies the goop multiplier (unless the player rested) and
+/// opens the depth map, where the player picks the next depth's node.
+/// Resting also breaks the venture streak.
+fn continue_button_system(
+    query: Query<&Interaction, (Changed<Interaction>, With<ContinueButton>)>,
+    rest: Res<CampRest>,
+    mut risk: ResMut<RunRisk>,
+    mut goop_earned: ResMut<GoopEarned>,
+    combat_level: Res<CombatLevel>,
+    mut next_state: ResMut<NextState<CombatState>>,
+) {
+    if !query.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+
+    if rest.0 {
+        risk.streak = 0;
+    } else {
+        let quote = venture_quote(combat_level.0 + 1, risk.streak);
+        goop_earned.0 = quote.apply(goop_earned.0);
+        risk.streak += 1;
+    }
+
+    next_state.set(CombatState::Map);
+}
+
+/// One policy per fight, paid from run goop.
+fn insurance_button_system(
+    query: Query<(&Interaction, &InsuranceButton), Changed<Interaction>>,
+    mut risk: ResMut<RunRisk>,
+    mut goop_earned: ResMut<GoopEarned>,
+) {
+    for (interaction, insurance_btn) in &query {
+        if *interaction != Interaction::Pressed || risk.insurance.is_some() {
+            continue;
+        }
+        let premium = INSURANCE_TIERS[insurance_btn.0].premium(goop_earned.0);
+        if goop_earned.0 < premium {
+            continue;
+        }
+        goop_earned.0 -= premium;
+        risk.insurance = Some(insurance_btn.0);
+    }
+}
+
+/// Keeps each card's HP readout current and removes cards whose slime is gone.
+/// Heal/Drop buttons are hidden when they'd do nothing.
+fn update_camp_cards_system(
+    mut commands: Commands,
+    cards: Query<(Entity, &CampCard)>,
+    mut texts: Query<(&CampCardText, &mut Text, &mut TextColor)>,
+    mut heal_buttons: Query<(&HealButton, &mut Visibility), Without<DismissButton>>,
+    mut dismiss_buttons: Query<(&DismissButton, &mut Visibility), Without<HealButton>>,
+    units: Query<(&Health, &MaxHealth, Option<&SlimeType>, Option<&MergeKind>), Without<Dying>>,
+) {
+    for (card_entity, card) in &cards {
+        if units.get(card.0).is_err() {
+            commands.entity(card_entity).despawn();
+        }
+    }
+
+    for (card_text, mut text, mut color) in &mut texts {
+        let Ok((health, max_health, slime_type, merge_kind)) = units.get(card_text.0) else {
+            continue;
+        };
+        let cost = heal_cost(health, max_health);
+        **text = if cost > 0 {
+            format!(
+                "{} {}/{} ({}g)",
+                unit_label(slime_type, merge_kind),
+                health.0,
+                max_health.0,
+                cost
+            )
+        } else {
+            format!(
+                "{} {}/{}",
+                unit_label(slime_type, merge_kind),
+                health.0,
+                max_health.0
+            )
+        };
+
+        // Same thresholds as the in-world health bars.
+        let ratio = health.0 as f32 / max_health.0 as f32;
+        color.0 = if ratio >= 0.5 {
+            Color::WHITE
+        } else if ratio >= 0.1 {
+            Color::srgb(0.9, 0.9, 0.0)
+        } else {
+            Color::srgb(0.9, 0.3, 0.3)
+        };
+    }
+
+    for (heal_btn, mut visibility) in &mut heal_buttons {
+        let needs_heal = units
+            .get(heal_btn.0)
+            .map(|(h, max, _, _)| h.0 < max.0)
+            .unwrap_or(false);
+        *visibility = if needs_heal {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+
+    for (dismiss_btn, mut visibility) in &mut dismiss_buttons {
+        let wounded = units
+            .get(dismiss_btn.0)
+            .map(|(h, max, slime_type, _)| slime_type.is_some() && is_badly_wounded(h, max))
+            .unwrap_or(false);
+        *visibility = if wounded {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn update_camp_goop_text(
+    goop_earned: Res<GoopEarned>,
+    mut query: Query<&mut Text, With<CampGoopText>>,
+) {
+    if !goop_earned.is_changed() {
+        return;
+    }
+    for mut text in &mut query {
+        **text = format!("Goop this run: {}", goop_earned.0);
+    }
+}
+
+/// Explains what each bottom-row button will do, with exact numbers.
+fn camp_hint_system(
+    heal_all_query: Query<&Interaction, With<HealAllButton>>,
+    rest_query: Query<&Interaction, With<RestButton>>,
+    continue_query: Query<&Interaction, With<ContinueButton>>,
+    insurance_query: Query<(&Interaction, &InsuranceButton)>,
+    units: Query<(&Team, &Health, &MaxHealth), Without<Dying>>,
+    goop_earned: Res<GoopEarned>,
+    combat_level: Res<CombatLevel>,
+    risk: Res<RunRisk>,
+    rest: Res<CampRest>,
+    mut hint_query: Query<&mut Text, With<CampHintText>>,
+) {
+    let next_depth = combat_level.0 + 1;
+    let quote = venture_quote(next_depth, risk.streak);
+    let hovered_insurance = insurance_query
+        .iter()
+        .find(|(i, _)| **i == Interaction::Hovered || **i == Interaction::Pressed)
+        .map(|(_, btn)| btn.0);
+
+    let hint = if let Some(index) = hovered_insurance {
+        let tier = &INSURANCE_TIERS[index];
+        match risk.insurance {
+            Some(active) if active == index => "insured for the next fight".to_string(),
+            Some(_) => "already insured for the next fight".to_string(),
+            None => {
+                // The payout is a share of run goop at the time of the loss;
+                // show what that would be right now, after the premium.
+                let after_premium = goop_earned.0 - tier.premium(goop_earned.0).min(goop_earned.0);
+                format!(
+                    "pay {} goop: lose the next fight and still bank {}% (now {})",
+                    tier.premium(goop_earned.0),
+                    (tier.keep_fraction * 100.0) as u32,
+                    tier.payout(after_premium)
+                )
+            }
+        }
+    } else if is_hovered(&heal_all_query) {
+        let total: u32 = units
+            .iter()
+            .filter(|(team, _, _)| **team == Team::Player)
+            .map(|(_, h, max)| heal_cost(h, max))
+            .sum();
+        format!("heal everyone to full: -{total} goop")
+    } else if is_hovered(&rest_query) {
+        if rest.0 {
+            "already rested".to_string()
+        } else {
+            format!(
+                "heal {}% of max HP for free, but skip the x{:.2} goop bonus and reset the streak",
+                (REST_HEAL_FRACTION * 100.0) as u32,
+                quote.multiplier()
+            )
+        }
+    } else if is_hovered(&continue_query) {
+        if rest.0 {
+            format!("rested: goop stays at {}", goop_earned.0)
+        } else {
+            quote.describe(next_depth, goop_earned.0)
+        }
+    } else {
+        format!(
+            "units at {}% HP or less can be dropped for a refund",
+            (DISMISS_THRESHOLD * 100.0) as u32
+        )
+    };
+
+    for mut text in &mut hint_query {
+        if **text != hint {
+            **text = hint.clone();
+        }
+    }
+}
+
+/// Scrolls the survivor grid with the mouse wheel. Bevy lays out overflowing
+/// content but doesn't scroll it by itself — we feed wheel input into
+/// ScrollPosition, and the layout clamps it to the content size.
+fn camp_scroll_system(
+    mut wheel: MessageReader<MouseWheel>,
+    mut query: Query<&mut ScrollPosition, With<CampScrollArea>>,
+) {
+    for event in wheel.read() {
+        let dy = match event.unit {
+            MouseScrollUnit::Line => event.y * 30.0,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        for mut scroll in &mut query {
+            scroll.y = (scroll.y - dy).max(0.0);
+        }
+    }
+}
+
+fn is_hovered<T: Component>(query: &Query<&Interaction, With<T>>) -> bool {
+    query
+        .iter()
+        .any(|i| *i == Interaction::Hovered || *i == Interaction::Pressed)
+}
+
+fn button_hover_system(
+    mut query: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<Button>)>,
+) {
+    for (interaction, mut bg) in &mut query {
+        *bg = match interaction {
+            Interaction::Pressed => BUTTON_PRESSED_COLOR.into(),
+            Interaction::Hovered => BUTTON_HOVER_COLOR.into(),
+            Interaction::None => BUTTON_COLOR.into(),
+        };
+    }
+}
+
+fn cleanup_camp(mut commands: Commands) {
+    commands.remove_resource::<CampRest>();
+}
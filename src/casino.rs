@@ -0,0 +1,792 @@
+// Casino: goop mini-games on the Home screen.
+//
+// Three games, all paid from the bank (SaveData.goop):
+//   - Slots: three reels, pay table below
+//   - Coin flip: double-or-nothing, keep flipping or cash out
+//   - Slime race: pick one of RACE_LANES slimes and watch a short auto-run
+//
+// Each game's house edge and stake live in CasinoConfig. Payouts are scaled
+// so the expected return is exactly (1 - house_edge) per goop wagered.
+//
+// Every game draws from one CasinoRng. Launch with `--casino-seed <n>` (or
+// `--casino-seed=<n>`) and the same sequence of plays gives the same results,
+// which makes payout bugs reproducible.
+//
+// Finished games are logged to SaveData.stats.casino.
+
+use bevy::ecs::hierarchy::ChildOf;
+use bevy::ecs::relationship::RelatedSpawnerCommands;
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::save_load::{SaveData, SaveGame};
+use crate::{GameFont, GameState};
+
+pub struct CasinoPlugin;
+
+impl Plugin for CasinoPlugin {
+    fn build(&self, app: &mut App) {
+        // Same rule as ActiveSaveStorage: a pre-inserted RNG or config wins.
+        if !app.world().contains_resource::<CasinoRng>() {
+            app.insert_resource(CasinoRng::from_args(std::env::args()));
+        }
+        app.init_resource::<CasinoConfig>()
+            .init_resource::<CasinoState>()
+            .add_systems(OnExit(GameState::Home), cleanup_casino.before(SaveGame))
+            .add_systems(
+                Update,
+                (
+                    open_casino_system,
+                    casino_button_system,
+                    race_animation_system,
+                    update_casino_text_system,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Home)),
+            );
+    }
+}
+
+// Matches home.rs — home's button_hover_system recolors these while hovered.
+const BUTTON_COLOR: Color = Color::srgb(0.15, 0.4, 0.15);
+const PANEL_COLOR: Color = Color::srgba(0.03, 0.08, 0.03, 0.95);
+const SECTION_COLOR: Color = Color::srgb(0.08, 0.18, 0.08);
+const LANE_COLOR: Color = Color::srgb(0.05, 0.1, 0.05);
+const RACE_BAR_COLOR: Color = Color::srgb(0.4, 0.9, 0.2);
+const WIN_COLOR: Color = Color::srgb(0.4, 0.9, 0.2);
+const LOSE_COLOR: Color = Color::srgb(0.9, 0.3, 0.3);
+
+/// Slimes in the race.
+pub const RACE_LANES: usize = 4;
+/// Each lane's run is split into this many stretches with their own speed.
+const RACE_SEGMENTS: usize = 8;
+/// Seconds the results stay on the track before the race is paid out.
+const RACE_FINISH_PAUSE: f32 = 0.75;
+/// How many finished games the save keeps in its log.
+const MAX_LOG_ENTRIES: usize = 20;
+
+// =============================================================================
+// Config, RNG, and stats
+// =============================================================================
+
+#[derive(Clone, Copy, Debug)]
+pub struct GameConfig {
+    /// Fraction of each wager the house keeps on average (0.05 = 5%).
+    pub house_edge: f32,
+    /// Goop wagered per play.
+    pub stake: u32,
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct CasinoConfig {
+    pub slots: GameConfig,
+    pub coin_flip: GameConfig,
+    pub race: GameConfig,
+}
+
+impl Default for CasinoConfig {
+    fn default() -> Self {
+        Self {
+            slots: GameConfig {
+                house_edge: 0.08,
+                stake: 10,
+            },
+            coin_flip: GameConfig {
+                house_edge: 0.04,
+                stake: 10,
+            },
+            race: GameConfig {
+                house_edge: 0.06,
+                stake: 10,
+            },
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct CasinoRng(pub StdRng);
+
+impl CasinoRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+
+    /// Seeds from `--casino-seed <n>` / `--casino-seed=<n>` if given,
+    /// otherwise from OS entropy.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let value = match arg.strip_prefix("--casino-seed") {
+                Some("") => args.next(),
+                Some(rest) => rest.strip_prefix('=').map(str::to_string),
+                None => continue,
+            };
+            match value.as_deref().map(str::parse::<u64>) {
+                Some(Ok(seed)) => return Self::from_seed(seed),
+                _ => warn!("Ignoring --casino-seed without a number"),
+            }
+        }
+        Self(StdRng::from_entropy())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CasinoGame {
+    Slots,
+    CoinFlip,
+    SlimeRace,
+}
+
+impl CasinoGame {
+    pub fn label(self) -> &'static str {
+        match self {
+            CasinoGame::Slots => "Slots",
+            CasinoGame::CoinFlip => "Coin flip",
+            CasinoGame::SlimeRace => "Slime race",
+        }
+    }
+}
+
+/// One finished game.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CasinoRecord {
+    pub game: CasinoGame,
+    pub wagered: u32,
+    pub returned: u32,
+    /// Short description of what happened, e.g. "GOOP GOOP SKULL".
+    pub detail: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct CasinoStats {
+    pub games_played: u32,
+    pub total_wagered: u64,
+    pub total_returned: u64,
+    pub biggest_win: u32,
+    /// Most recent games, newest last. Capped at MAX_LOG_ENTRIES.
+    pub log: Vec<CasinoRecord>,
+}
+
+impl CasinoStats {
+    pub fn record(&mut self, record: CasinoRecord) {
+        self.games_played += 1;
+        self.total_wagered += record.wagered as u64;
+        self.total_returned += record.returned as u64;
+        self.biggest_win = self.biggest_win.max(record.returned);
+
+        self.log.push(record);
+        if self.log.len() > MAX_LOG_ENTRIES {
+            let excess = self.log.len() - MAX_LOG_ENTRIES;
+            self.log.drain(..excess);
+        }
+    }
+}
+
+// =============================================================================
+// Game rules
+// =============================================================================
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlotSymbol {
+    Slime,
+    Tank,
+    Wizard,
+    Goop,
+    Skull,
+}
+
+impl SlotSymbol {
+    const ALL: [SlotSymbol; 5] = [
+        SlotSymbol::Slime,
+        SlotSymbol::Tank,
+        SlotSymbol::Wizard,
+        SlotSymbol::Goop,
+        SlotSymbol::Skull,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            SlotSymbol::Slime => "SLIME",
+            SlotSymbol::Tank => "TANK",
+            SlotSymbol::Wizard => "WIZ",
+            SlotSymbol::Goop => "GOOP",
+            SlotSymbol::Skull => "SKULL",
+        }
+    }
+
+    /// Multiplier for three of a kind, before house-edge scaling.
+    fn triple_multiplier(self) -> f32 {
+        match self {
+            SlotSymbol::Goop => 25.0,
+            SlotSymbol::Wizard | SlotSymbol::Tank => 12.0,
+            SlotSymbol::Slime => 6.0,
+            SlotSymbol::Skull => 0.0,
+        }
+    }
+}
+
+/// Raw pay table: three of a kind pays its symbol's multiplier, any pair
+/// of GOOP pays 2, any other pair pays 1 (stake back), skulls never pay.
+fn slots_base_multiplier(reels: [SlotSymbol; 3]) -> f32 {
+    let [a, b, c] = reels;
+    if a == b && b == c {
+        return a.triple_multiplier();
+    }
+    let pair = if a == b || a == c {
+        Some(a)
+    } else if b == c {
+        Some(b)
+    } else {
+        None
+    };
+    match pair {
+        Some(SlotSymbol::Goop) => 2.0,
+        Some(SlotSymbol::Skull) | None => 0.0,
+        Some(_) => 1.0,
+    }
+}
+
+/// Expected return of the raw pay table, by enumerating every reel result.
+fn slots_base_return() -> f32 {
+    let mut total = 0.0;
+    for a in SlotSymbol::ALL {
+        for b in SlotSymbol::ALL {
+            for c in SlotSymbol::ALL {
+                total += slots_base_multiplier([a, b, c]);
+            }
+        }
+    }
+    total / SlotSymbol::ALL.len().pow(3) as f32
+}
+
+fn spin_slots(rng: &mut impl Rng, config: GameConfig) -> ([SlotSymbol; 3], u32) {
+    let reels = [(); 3].map(|_| SlotSymbol::ALL[rng.gen_range(0..SlotSymbol::ALL.len())]);
+    // Scale the whole table so its expected return is exactly 1 - edge.
+    let scale = (1.0 - config.house_edge) / slots_base_return();
+    let payout = (config.stake as f32 * slots_base_multiplier(reels) * scale).floor() as u32;
+    (reels, payout)
+}
+
+/// Each double-or-nothing flip wins slightly less than half the time, so
+/// every flip returns (1 - edge) on average.
+fn flip_coin(rng: &mut impl Rng, config: GameConfig) -> bool {
+    rng.gen::<f32>() < (1.0 - config.house_edge) / 2.0
+}
+
+/// A race decided up front: every lane gets RACE_SEGMENTS random stretch
+/// times, and the lane with the lowest total wins. The animation just plays
+/// these times back, so what you see is exactly what was rolled.
+#[derive(Clone, Debug)]
+pub struct Race {
+    pub pick: usize,
+    pub stake: u32,
+    pub segment_times: [[f32; RACE_SEGMENTS]; RACE_LANES],
+    pub elapsed: f32,
+}
+
+impl Race {
+    fn roll(rng: &mut impl Rng, pick: usize, stake: u32) -> Self {
+        let segment_times =
+            [(); RACE_LANES].map(|_| [(); RACE_SEGMENTS].map(|_| rng.gen_range(0.2..0.6)));
+        Self {
+            pick,
+            stake,
+            segment_times,
+            elapsed: 0.0,
+        }
+    }
+
+    fn finish_time(&self, lane: usize) -> f32 {
+        self.segment_times[lane].iter().sum()
+    }
+
+    fn winner(&self) -> usize {
+        (0..RACE_LANES)
+            .min_by(|a, b| self.finish_time(*a).total_cmp(&self.finish_time(*b)))
+            .unwrap()
+    }
+
+    /// How far along the track a lane is, 0..=1, at the current time.
+    fn progress(&self, lane: usize) -> f32 {
+        let mut remaining = self.elapsed;
+        for (i, segment) in self.segment_times[lane].iter().enumerate() {
+            if remaining < *segment {
+                return (i as f32 + remaining / segment) / RACE_SEGMENTS as f32;
+            }
+            remaining -= segment;
+        }
+        1.0
+    }
+
+    fn is_over(&self) -> bool {
+        self.elapsed >= self.finish_time(self.winner()) + RACE_FINISH_PAUSE
+    }
+
+    /// Fair odds are RACE_LANES to 1; the house keeps its edge.
+    fn payout(&self, config: GameConfig) -> u32 {
+        if self.winner() != self.pick {
+            return 0;
+        }
+        (self.stake as f32 * RACE_LANES as f32 * (1.0 - config.house_edge)).floor() as u32
+    }
+}
+
+// =============================================================================
+// UI
+// =============================================================================
+
+/// In-progress games. Lives across panel open/close so a pot or race isn't
+/// lost by closing the panel — it's settled when leaving Home instead.
+#[derive(Resource, Default)]
+pub struct CasinoState {
+    /// Coin flip: the original stake and the current pot, if a run is live.
+    pub coin_flip: Option<(u32, u32)>,
+    pub race: Option<Race>,
+    /// Last result line shown in the panel.
+    pub message: Option<(String, bool)>,
+}
+
+#[derive(Component)]
+pub struct CasinoButton;
+
+#[derive(Component)]
+struct CasinoPanel;
+
+#[derive(Component, Clone, Copy)]
+enum CasinoAction {
+    Spin,
+    Flip,
+    CashOut,
+    BetRace(usize),
+    Close,
+}
+
+#[derive(Component)]
+struct SlotsReelsText;
+
+#[derive(Component)]
+struct CoinPotText;
+
+#[derive(Component)]
+struct RaceBar(usize);
+
+#[derive(Component)]
+struct CasinoMessageText;
+
+#[derive(Component)]
+struct CasinoStatsText;
+
+/// The button on the Home screen that opens the casino panel.
+pub fn spawn_casino_button(parent: &mut RelatedSpawnerCommands<ChildOf>, font: &Handle<Font>) {
+    parent
+        .spawn((
+            CasinoButton,
+            Button,
+            Node {
+                width: Val::Px(260.0),
+                height: Val::Px(45.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+        ))
+        .with_children(|btn| {
+            btn.spawn((
+                Text::new("Casino"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+fn open_casino_system(
+    mut commands: Commands,
+    query: Query<&Interaction, (Changed<Interaction>, With<CasinoButton>)>,
+    panels: Query<(), With<CasinoPanel>>,
+    game_font: Res<GameFont>,
+    config: Res<CasinoConfig>,
+) {
+    if !panels.is_empty() || !query.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+    spawn_casino_panel(&mut commands, &game_font.0, &config);
+}
+
+fn spawn_casino_panel(commands: &mut Commands, font: &Handle<Font>, config: &CasinoConfig) {
+    let text = |content: String, size: f32| {
+        (
+            Text::new(content),
+            TextFont {
+                font: font.clone(),
+                font_size: size,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        )
+    };
+
+    commands
+        .spawn((
+            CasinoPanel,
+            DespawnOnExit(GameState::Home),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(16.0),
+                ..default()
+            },
+            BackgroundColor(PANEL_COLOR),
+            // Draw over the rest of the Home screen.
+            GlobalZIndex(10),
+        ))
+        .with_children(|panel| {
+            panel.spawn(text("Casino".to_string(), 60.0));
+
+            panel
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(16.0),
+                    ..default()
+                })
+                .with_children(|games| {
+                    // Slots
+                    spawn_section(games).with_children(|section| {
+                        section.spawn(text("Slots".to_string(), 36.0));
+                        section.spawn((SlotsReelsText, text("- - -".to_string(), 30.0)));
+                        spawn_action_button(
+                            section,
+                            font,
+                            CasinoAction::Spin,
+                            &format!("Spin ({})", config.slots.stake),
+                        );
+                    });
+
+                    // Coin flip
+                    spawn_section(games).with_children(|section| {
+                        section.spawn(text("Double or nothing".to_string(), 30.0));
+                        section.spawn((CoinPotText, text("Pot: -".to_string(), 30.0)));
+                        spawn_action_button(
+                            section,
+                            font,
+                            CasinoAction::Flip,
+                            &format!("Flip ({})", config.coin_flip.stake),
+                        );
+                        spawn_action_button(section, font, CasinoAction::CashOut, "Cash out");
+                    });
+
+                    // Slime race
+                    spawn_section(games).with_children(|section| {
+                        section.spawn(text(
+                            format!("Slime race ({} per bet)", config.race.stake),
+                            30.0,
+                        ));
+                        for lane in 0..RACE_LANES {
+                            section
+                                .spawn(Node {
+                                    flex_direction: FlexDirection::Row,
+                                    align_items: AlignItems::Center,
+                                    column_gap: Val::Px(8.0),
+                                    ..default()
+                                })
+                                .with_children(|row| {
+                                    row.spawn((
+                                        Node {
+                                            width: Val::Px(180.0),
+                                            height: Val::Px(20.0),
+                                            ..default()
+                                        },
+                                        BackgroundColor(LANE_COLOR),
+                                    ))
+                                    .with_child((
+                                        RaceBar(lane),
+                                        Node {
+                                            width: Val::Percent(0.0),
+                                            height: Val::Percent(100.0),
+                                            ..default()
+                                        },
+                                        BackgroundColor(RACE_BAR_COLOR),
+                                    ));
+                                    spawn_action_button(
+                                        row,
+                                        font,
+                                        CasinoAction::BetRace(lane),
+                                        &format!("Slime {}", lane + 1),
+                                    );
+                                });
+                        }
+                    });
+                });
+
+            panel.spawn((CasinoMessageText, text(" ".to_string(), 28.0)));
+            panel.spawn((CasinoStatsText, text(" ".to_string(), 22.0)));
+            spawn_action_button(panel, font, CasinoAction::Close, "Close");
+        });
+}
+
+fn spawn_section<'a>(parent: &'a mut RelatedSpawnerCommands<ChildOf>) -> EntityCommands<'a> {
+    parent.spawn((
+        Node {
+            width: Val::Px(360.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(12.0)),
+            ..default()
+        },
+        BackgroundColor(SECTION_COLOR),
+    ))
+}
+
+fn spawn_action_button(
+    parent: &mut RelatedSpawnerCommands<ChildOf>,
+    font: &Handle<Font>,
+    action: CasinoAction,
+    label: &str,
+) {
+    parent
+        .spawn((
+            action,
+            Button,
+            Node {
+                width: Val::Px(150.0),
+                height: Val::Px(40.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+        ))
+        .with_children(|btn| {
+            btn.spawn((
+                Text::new(label),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 22.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+fn casino_button_system(
+    mut commands: Commands,
+    query: Query<(&Interaction, &CasinoAction), Changed<Interaction>>,
+    panels: Query<Entity, With<CasinoPanel>>,
+    mut reels_text: Query<&mut Text, With<SlotsReelsText>>,
+    mut state: ResMut<CasinoState>,
+    mut rng: ResMut<CasinoRng>,
+    mut save_data: ResMut<SaveData>,
+    config: Res<CasinoConfig>,
+) {
+    for (interaction, action) in &query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match *action {
+            CasinoAction::Spin => {
+                let stake = config.slots.stake;
+                if save_data.goop < stake {
+                    state.message = Some(("Not enough goop".to_string(), false));
+                    continue;
+                }
+                save_data.goop -= stake;
+
+                let (reels, payout) = spin_slots(&mut rng.0, config.slots);
+                let detail = reels.map(SlotSymbol::label).join(" ");
+                for mut text in &mut reels_text {
+                    **text = detail.clone();
+                }
+                save_data.goop += payout;
+                state.message = Some(result_message(CasinoGame::Slots, stake, payout));
+                save_data.stats.casino.record(CasinoRecord {
+                    game: CasinoGame::Slots,
+                    wagered: stake,
+                    returned: payout,
+                    detail,
+                });
+            }
+            CasinoAction::Flip => {
+                // Start a new run, or risk the current pot again.
+                let (stake, pot) = match state.coin_flip {
+                    Some(run) => run,
+                    None => {
+                        let stake = config.coin_flip.stake;
+                        if save_data.goop < stake {
+                            state.message = Some(("Not enough goop".to_string(), false));
+                            continue;
+                        }
+                        save_data.goop -= stake;
+                        (stake, stake)
+                    }
+                };
+
+                if flip_coin(&mut rng.0, config.coin_flip) {
+                    state.coin_flip = Some((stake, pot * 2));
+                    state.message = Some((format!("Heads! Pot is now {}", pot * 2), true));
+                } else {
+                    state.coin_flip = None;
+                    state.message = Some(result_message(CasinoGame::CoinFlip, stake, 0));
+                    save_data.stats.casino.record(CasinoRecord {
+                        game: CasinoGame::CoinFlip,
+                        wagered: stake,
+                        returned: 0,
+                        detail: format!("lost a pot of {pot}"),
+                    });
+                }
+            }
+            CasinoAction::CashOut => {
+                if let Some(message) = cash_out_coin_flip(&mut state, &mut save_data) {
+                    state.message = Some(message);
+                }
+            }
+            CasinoAction::BetRace(lane) => {
+                if state.race.is_some() {
+                    continue;
+                }
+                let stake = config.race.stake;
+                if save_data.goop < stake {
+                    state.message = Some(("Not enough goop".to_string(), false));
+                    continue;
+                }
+                save_data.goop -= stake;
+                state.race = Some(Race::roll(&mut rng.0, lane, stake));
+                state.message = Some((format!("Go, Slime {}!", lane + 1), true));
+            }
+            CasinoAction::Close => {
+                for panel in &panels {
+                    commands.entity(panel).despawn();
+                }
+            }
+        }
+    }
+}
+
+/// Banks the coin flip pot, if there is one.
+fn cash_out_coin_flip(state: &mut CasinoState, save_data: &mut SaveData) -> Option<(String, bool)> {
+    let (stake, pot) = state.coin_flip.take()?;
+    save_data.goop += pot;
+    save_data.stats.casino.record(CasinoRecord {
+        game: CasinoGame::CoinFlip,
+        wagered: stake,
+        returned: pot,
+        detail: format!("cashed out {pot}"),
+    });
+    Some(result_message(CasinoGame::CoinFlip, stake, pot))
+}
+
+fn finish_race(race: &Race, config: GameConfig, save_data: &mut SaveData) -> (String, bool) {
+    let payout = race.payout(config);
+    save_data.goop += payout;
+    save_data.stats.casino.record(CasinoRecord {
+        game: CasinoGame::SlimeRace,
+        wagered: race.stake,
+        returned: payout,
+        detail: format!("bet on {}, {} won", race.pick + 1, race.winner() + 1),
+    });
+    result_message(CasinoGame::SlimeRace, race.stake, payout)
+}
+
+fn result_message(game: CasinoGame, wagered: u32, returned: u32) -> (String, bool) {
+    if returned > wagered {
+        (format!("{}: won {returned}!", game.label()), true)
+    } else if returned == wagered {
+        (format!("{}: stake back", game.label()), true)
+    } else if returned > 0 {
+        (format!("{}: got {returned} back", game.label()), false)
+    } else {
+        (format!("{}: lost {wagered}", game.label()), false)
+    }
+}
+
+fn race_animation_system(
+    time: Res<Time>,
+    mut state: ResMut<CasinoState>,
+    mut bars: Query<(&RaceBar, &mut Node)>,
+    mut save_data: ResMut<SaveData>,
+    config: Res<CasinoConfig>,
+) {
+    let Some(race) = state.race.as_mut() else {
+        return;
+    };
+    race.elapsed += time.delta_secs();
+
+    for (bar, mut node) in &mut bars {
+        node.width = Val::Percent(race.progress(bar.0) * 100.0);
+    }
+
+    if race.is_over() {
+        let race = state.race.take().unwrap();
+        state.message = Some(finish_race(&race, config.race, &mut save_data));
+    }
+}
+
+fn update_casino_text_system(
+    state: Res<CasinoState>,
+    save_data: Res<SaveData>,
+    mut message_text: Query<
+        (&mut Text, &mut TextColor),
+        (
+            With<CasinoMessageText>,
+            Without<CoinPotText>,
+            Without<CasinoStatsText>,
+        ),
+    >,
+    mut pot_text: Query<&mut Text, (With<CoinPotText>, Without<CasinoStatsText>)>,
+    mut stats_text: Query<&mut Text, With<CasinoStatsText>>,
+    added_panels: Query<(), Added<CasinoPanel>>,
+) {
+    if !state.is_changed() && !save_data.is_changed() && added_panels.is_empty() {
+        return;
+    }
+
+    if let Some((message, good)) = &state.message {
+        for (mut text, mut color) in &mut message_text {
+            **text = message.clone();
+            *color = TextColor(if *good { WIN_COLOR } else { LOSE_COLOR });
+        }
+    }
+
+    for mut text in &mut pot_text {
+        **text = match state.coin_flip {
+            Some((_, pot)) => format!("Pot: {pot}"),
+            None => "Pot: -".to_string(),
+        };
+    }
+
+    let stats = &save_data.stats.casino;
+    for mut text in &mut stats_text {
+        **text = format!(
+            "Played {} | wagered {} | returned {} | best win {}",
+            stats.games_played, stats.total_wagered, stats.total_returned, stats.biggest_win
+        );
+    }
+}
+
+/// Settles anything still running so no goop is lost by leaving Home
+/// mid-game: the coin flip pot is cashed out and a running race finishes.
+/// Runs before SaveGame so the settled goop is saved on the way out.
+fn cleanup_casino(
+    mut state: ResMut<CasinoState>,
+    mut save_data: ResMut<SaveData>,
+    config: Res<CasinoConfig>,
+) {
+    cash_out_coin_flip(&mut state, &mut save_data);
+    if let Some(race) = state.race.take() {
+        finish_race(&race, config.race, &mut save_data);
+    }
+    state.message = None;
+}
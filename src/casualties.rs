@@ -0,0 +1,138 @@
+// Casualties rule (opt-in via Settings::casualties).
+//
+// Normally a run can't cost you units — the army in SaveData comes back
+// intact no matter how many slimes died. With casualties on, every player
+// slime that dies during the run is tallied by type, and so is every slime
+// consumed by a merge (two go in, one merged blob comes out). When the run
+// ends the tally is subtracted from the saved army.
+
+use bevy::prelude::*;
+
+use crate::armies::{Army, SlimeType};
+use crate::health::Dying;
+use crate::pick_target::Team;
+use crate::save_load::SaveData;
+use crate::special_abilities::SlimesMergedEvent;
+use crate::splitting::SplitSpawn;
+use crate::GameState;
+
+pub struct CasualtiesPlugin;
+
+impl Plugin for CasualtiesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(on_slimes_merged)
+            .add_systems(OnEnter(GameState::Combat), init_casualties)
+            .add_systems(OnExit(GameState::Combat), apply_casualties)
+            .add_systems(
+                Update,
+                record_deaths_system
+                    .run_if(in_state(GameState::Combat))
+                    .run_if(resource_exists::<Casualties>),
+            );
+    }
+}
+
+/// Player slimes lost so far this run, per unit type.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct Casualties {
+    pub normal: u32,
+    pub tanks: u32,
+    pub wizards: u32,
+}
+
+impl Casualties {
+    pub fn record(&mut self, slime_type: SlimeType) {
+        *self.count_mut(slime_type) += 1;
+    }
+
+    pub fn count(&self, slime_type: SlimeType) -> u32 {
+        match slime_type {
+            SlimeType::Normal => self.normal,
+            SlimeType::Tank => self.tanks,
+            SlimeType::Wizard => self.wizards,
+        }
+    }
+
+    fn count_mut(&mut self, slime_type: SlimeType) -> &mut u32 {
+        match slime_type {
+            SlimeType::Normal => &mut self.normal,
+            SlimeType::Tank => &mut self.tanks,
+            SlimeType::Wizard => &mut self.wizards,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.normal + self.tanks + self.wizards
+    }
+
+    /// Removes the lost slimes from an army. Counts never go below zero.
+    pub fn subtract_from(&self, army: &mut Army) {
+        for slime_type in SlimeType::ALL {
+            let count = army.count_mut(slime_type);
+            *count = count.saturating_sub(self.count(slime_type));
+        }
+    }
+
+    /// Human-readable loss list, e.g. "3 Normal, 1 Tank". Empty types are skipped.
+    pub fn summary(&self) -> String {
+        if self.total() == 0 {
+            return "No losses".to_string();
+        }
+        SlimeType::ALL
+            .iter()
+            .filter(|t| self.count(**t) > 0)
+            .map(|t| format!("{} {}", self.count(*t), t.label()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn init_casualties(mut commands: Commands) {
+    commands.insert_resource(Casualties::default());
+}
+
+/// Counts player slimes the moment they start dying. Added<Dying> fires
+/// exactly once per death, and merged slimes have no SlimeType so they're
+/// skipped — their two ingredients were already counted when they merged.
+fn record_deaths_system(
+    query: Query<(&Team, &SlimeType), (Added<Dying>, Without<SplitSpawn>)>,
+    mut casualties: ResMut<Casualties>,
+) {
+    for (team, slime_type) in &query {
+        if *team == Team::Player {
+            casualties.record(*slime_type);
+        }
+    }
+}
+
+fn on_slimes_merged(trigger: On<SlimesMergedEvent>, casualties: Option<ResMut<Casualties>>) {
+    let Some(mut casualties) = casualties else {
+        return;
+    };
+    if trigger.team != Team::Player {
+        return;
+    }
+    for slime_type in trigger.consumed.into_iter().flatten() {
+        casualties.record(slime_type);
+    }
+}
+
+/// Runs when leaving Combat (cash-out or defeat). Only touches the save if
+/// the player opted into the casualties rule; the Home screen's OnEnter save
+/// then persists the smaller army.
+fn apply_casualties(
+    mut commands: Commands,
+    casualties: Option<Res<Casualties>>,
+    mut save_data: ResMut<SaveData>,
+) {
+    let Some(casualties) = casualties else {
+        return;
+    };
+
+    if save_data.settings.casualties && casualties.total() > 0 {
+        info!("Casualties this run: {}", casualties.summary());
+        casualties.subtract_from(&mut save_data.army);
+    }
+
+    commands.remove_resource::<Casualties>();
+}
@@ -4,12 +4,16 @@ use rand::{seq::IteratorRandom, Rng};
 use crate::{
     animation::{AnimationState, AnimationType, IdleAnimation},
     audio::GameAudio,
-    health::{DamagedEvent, Dying, Health},
-    movement::{Knockback, TargetEntity},
+    damage::{DamageQueue, DamageReason, DamageStage, PendingHit},
+    health::{Dying, Health},
+    hitboxes::{hitbox, hurtbox, Hitbox},
+    movement::{knockback_speed, Knockback, TargetEntity},
+    obstacles::{line_of_sight, Obstacle, RANGED_ATTACK_RANGE},
     pick_target::Team,
     setup_round::{Inert, StunTimer},
     shaders_lite::Flash,
     status::{CanAttack, CanBeTargeted},
+    steering::{Blocker, Mass, Velocity},
     CombatState, GameFont, GameState,
 };
 
@@ -26,7 +30,9 @@ impl Plugin for CombatPlugin {
         //   pick_attack → attack → hit_frame_check → cleanup
         //
         // on_hit_observer is NOT in this chain — it runs immediately when
-        // hit_frame_check_system calls commands.trigger(OnHitEvent { ... }).
+        // hit_frame_check_system calls commands.trigger(OnHitEvent { ... }),
+        // and queues the hit for the damage pipeline (damage.rs), which
+        // runs right after.
         //
         // "Chaining" means each system runs after the previous one finishes,
         // which guarantees that e.g. ActiveAttack exists before attack_system tries to read it.
@@ -35,9 +41,22 @@ impl Plugin for CombatPlugin {
             Update,
             (pick_attack_system, attack_system, hit_frame_check_system)
                 .chain()
+                .before(DamageStage::Collect)
                 .run_if(in_state(CombatState::DuringCombat)),
         );
 
+        // Combat's own damage modifiers. Invulnerability goes first so an
+        // invulnerable target never shows a block.
+        app.add_systems(
+            Update,
+            (
+                (invulnerable_hit_system, block_hit_system)
+                    .chain()
+                    .in_set(DamageStage::PreHit),
+                hit_effects_system.in_set(DamageStage::PostDamage),
+            ),
+        );
+
         // Cleanup and visual-finish systems run across all combat phases so
         // in-progress effects (ice traps, shield punches, attack animations)
         // can complete even after transitioning to PostCombat.
@@ -49,6 +68,7 @@ impl Plugin for CombatPlugin {
                 ice_vfx_cleanup_system,
                 shield_scale_punch_system,
                 floating_text_system,
+                invulnerable_system,
             )
                 .run_if(in_state(GameState::Combat)),
         );
@@ -68,7 +88,8 @@ impl Plugin for CombatPlugin {
 pub struct KnownAttacks(pub Vec<Attack>);
 
 /// Describes a single attack: which animation to play, when damage lands,
-/// what happens on hit, and how close the target needs to be.
+/// what happens on hit, how close the target needs to be to start it, and
+/// what the hit has to touch to land (hitboxes.rs).
 ///
 /// Clone is derived instead of Component because Attack is stored inside
 /// KnownAttacks (a Vec<Attack>), not placed on entities directly.
@@ -79,6 +100,14 @@ pub struct Attack {
     pub hit_frame: usize, // 0-indexed frame when damage should be applied
     pub on_hit_effect: AttackEffect,
     pub range: f32,
+    pub hitbox: Hitbox,
+}
+
+impl Attack {
+    /// Ranged attacks need line of sight past arena obstacles (obstacles.rs).
+    pub fn is_ranged(&self) -> bool {
+        self.range > RANGED_ATTACK_RANGE
+    }
 }
 
 /// The effect that happens when an attack connects.
@@ -128,6 +157,9 @@ pub struct AttackCooldown(pub Timer);
 pub struct ActiveAttack {
     pub attack: Attack,
     pub target: Entity,
+    /// Centre of the target's hurtbox when the attack started. The hitbox is
+    /// thrown at this point, not wherever the target has got to since.
+    pub aim: Vec2,
     /// Prevents the hit from firing multiple frames in a row.
     /// Once we send OnHitEvent, we set this to true so we don't
     /// send it again on subsequent frames while still on the hit_frame.
@@ -174,13 +206,31 @@ pub struct IceTrapVfx;
 
 /// Chance (0.0–1.0) that an incoming attack is completely blocked.
 /// When a block succeeds, the attack deals no damage, no knockback, no stun —
-/// the entire OnHitEvent is cancelled via early return.
+/// block_hit_system cancels the whole hit in the damage pipeline's PreHit.
 ///
 /// This is a component on the *defender*, not the attacker. It's the defender's
 /// passive ability: "I have a shield that might block your hit."
 #[derive(Component)]
 pub struct BlockChance(pub f32);
 
+/// Flat damage subtracted from every hit this entity takes. A hit that gets
+/// through always deals at least 1, so armor never makes a unit unkillable.
+/// Like BlockChance, this is a defender component. Applied in the damage
+/// pipeline's Calculate stage (affixes.rs).
+#[derive(Component)]
+pub struct Armor(pub i32);
+
+/// While present, hits on this entity are ignored entirely — no damage,
+/// knockback, or stun. Removed by invulnerable_system when the timer runs out.
+#[derive(Component)]
+pub struct Invulnerable(pub Timer);
+
+/// HP the *attacker* regains each time one of its hits deals damage,
+/// capped at its MaxHealth. Applied in the damage pipeline's PostDamage
+/// stage (affixes.rs).
+#[derive(Component)]
+pub struct Lifesteal(pub i32);
+
 /// Marker component for the shield child entity (e.g., the iceberg sprite).
 /// The on_block_attack_observer uses this to find the shield and flash it white.
 #[derive(Component)]
@@ -216,13 +266,19 @@ fn pick_attack_system(
     // GlobalTransform gives world-space position. This is critical for child entities
     // (like the frozen spear) whose local Transform is relative to their parent.
     // For top-level entities, GlobalTransform == Transform, so nothing changes for them.
-    targets: Query<&GlobalTransform>,
+    targets: Query<(&GlobalTransform, Option<&AnimationType>, Option<&Sprite>)>,
+    obstacles: Query<(&GlobalTransform, &Obstacle)>,
     mut commands: Commands,
 ) {
     let mut rng = rand::thread_rng();
+    let blockers: Vec<Blocker> = obstacles
+        .iter()
+        .map(|(transform, obstacle)| obstacle.blocker(transform.translation()))
+        .collect();
 
     for (entity, known_attacks, attacker_transform, target_entity) in attackers.iter() {
-        let Ok(target_transform) = targets.get(target_entity.0) else {
+        let Ok((target_transform, target_animation, target_sprite)) = targets.get(target_entity.0)
+        else {
             continue;
         };
 
@@ -231,18 +287,26 @@ fn pick_attack_system(
             .translation()
             .distance(target_transform.translation());
 
-        // Filter to only attacks whose range covers the current distance,
-        // then randomly choose one. choose() returns None if no attacks are in range.
+        let in_sight = line_of_sight(
+            attacker_transform.translation().truncate(),
+            target_transform.translation().truncate(),
+            &blockers,
+        );
+
+        // Filter to only attacks whose range covers the current distance (and,
+        // for ranged attacks, that can see the target), then randomly choose
+        // one. choose() returns None if no attacks are usable.
         let chosen_attack = known_attacks
             .0
             .iter()
-            .filter(|attack| distance <= attack.range)
+            .filter(|attack| distance <= attack.range && (in_sight || !attack.is_ranged()))
             .choose(&mut rng);
 
         if let Some(attack) = chosen_attack {
             commands.entity(entity).insert(ActiveAttack {
                 attack: attack.clone(),
                 target: target_entity.0,
+                aim: hurtbox(target_transform, target_animation, target_sprite).center,
                 hit_triggered: false,
             });
         }
@@ -257,42 +321,71 @@ fn attack_system(mut query: Query<(&ActiveAttack, &mut AnimationType), Added<Act
 }
 
 /// Checks if the current animation frame has reached the attack's "hit frame."
-/// If the target has moved beyond attack range + 10 by the hit frame,
-/// the attack misses — no damage, just a "MISS!" text and whoosh sound.
+/// If the attack's hitbox doesn't overlap the target's hurtbox by then
+/// (hitboxes.rs), the attack misses — no damage, just a "MISS!" text and
+/// whoosh sound. A ranged attack whose target has gone behind an obstacle
+/// is "BLOCKED!" the same way.
 fn hit_frame_check_system(
     mut query: Query<
-        (Entity, &mut ActiveAttack, &AnimationState, &GlobalTransform),
+        (
+            Entity,
+            &mut ActiveAttack,
+            &AnimationState,
+            &GlobalTransform,
+            Option<&AnimationType>,
+            Option<&Sprite>,
+        ),
         Without<Dying>,
     >,
-    targets: Query<&GlobalTransform>,
+    targets: Query<(&GlobalTransform, Option<&AnimationType>, Option<&Sprite>)>,
+    obstacles: Query<(&GlobalTransform, &Obstacle)>,
     game_font: Res<GameFont>,
     audio: Res<GameAudio>,
     mut commands: Commands,
 ) {
-    for (entity, mut active_attack, anim_state, attacker_transform) in query.iter_mut() {
+    for (entity, mut active_attack, anim_state, attacker_transform, animation, sprite) in
+        query.iter_mut()
+    {
         if anim_state.frame_index >= active_attack.attack.hit_frame && !active_attack.hit_triggered
         {
             active_attack.hit_triggered = true;
 
-            // Check if target is still in range. If it moved away mid-attack, miss.
-            let missed = targets
-                .get(active_attack.target)
-                .map(|target_transform| {
-                    let distance = attacker_transform
-                        .translation()
-                        .distance(target_transform.translation());
-                    distance > active_attack.attack.range + 15.0
-                })
-                .unwrap_or(false);
+            // Throw the hitbox at the aim and see if it touches the target
+            // where it is now.
+            let target = targets.get(active_attack.target).ok();
+            let missed =
+                target.is_some_and(|(target_transform, target_animation, target_sprite)| {
+                    let body = hurtbox(attacker_transform, animation, sprite);
+                    let reach = hitbox(
+                        &active_attack.attack.hitbox,
+                        attacker_transform,
+                        &body,
+                        active_attack.aim,
+                    );
+                    !reach.overlaps(&hurtbox(target_transform, target_animation, target_sprite))
+                });
+
+            // A ranged shot that would pass through an obstacle is stopped by it.
+            let blocked = active_attack.attack.is_ranged()
+                && target.is_some_and(|(target_transform, ..)| {
+                    let blockers: Vec<Blocker> = obstacles
+                        .iter()
+                        .map(|(t, obstacle)| obstacle.blocker(t.translation()))
+                        .collect();
+                    !line_of_sight(
+                        attacker_transform.translation().truncate(),
+                        target_transform.translation().truncate(),
+                        &blockers,
+                    )
+                });
 
-            if missed {
-                let pos = targets
-                    .get(active_attack.target)
-                    .map(|t| t.translation())
+            if missed || blocked {
+                let pos = target
+                    .map(|(t, ..)| t.translation())
                     .unwrap_or(attacker_transform.translation());
                 commands.spawn((
                     FloatingText(Timer::from_seconds(0.8, TimerMode::Once)),
-                    Text2d::new("MISS!"),
+                    Text2d::new(if blocked { "BLOCKED!" } else { "MISS!" }),
                     TextFont {
                         font: game_font.0.clone(),
                         font_size: 18.0,
@@ -316,7 +409,8 @@ fn hit_frame_check_system(
     }
 }
 
-/// Observer that reacts to OnHitEvent and applies damage + knockback + stun.
+/// Observer that reacts to OnHitEvent by queueing the hit for the damage
+/// pipeline (damage.rs), which decides what it actually does.
 ///
 /// Observers are functions registered with app.add_observer(). They run immediately
 /// when their event type is triggered via commands.trigger(). Unlike regular systems,
@@ -325,120 +419,130 @@ fn hit_frame_check_system(
 /// The first parameter is On<OnHitEvent>, which wraps the event data.
 /// You access the event fields by dereferencing: trigger.attacker, trigger.target, etc.
 /// Additional parameters work just like regular system parameters (queries, commands, etc.).
+fn on_hit_observer(trigger: On<OnHitEvent>, mut queue: ResMut<DamageQueue>) {
+    queue.push(PendingHit::new(
+        Some(trigger.attacker),
+        trigger.target,
+        trigger.effect.clone(),
+    ));
+}
+
+/// PreHit: invulnerable targets ignore hits entirely.
+fn invulnerable_hit_system(
+    mut queue: ResMut<DamageQueue>,
+    invulnerable: Query<(), With<Invulnerable>>,
+) {
+    for hit in &mut queue.hits {
+        if invulnerable.contains(hit.target) {
+            hit.cancel(DamageReason::Invulnerable);
+        }
+    }
+}
+
+/// PreHit: rolls each target's BlockChance. Blocked attacks cancel
+/// everything — including AoE splash. Only attacks can be blocked: hits
+/// with no attacker (hazards, wall slams) always go through.
+fn block_hit_system(
+    mut queue: ResMut<DamageQueue>,
+    blockers: Query<&BlockChance>,
+    mut commands: Commands,
+) {
+    let mut rng = rand::thread_rng();
+    for hit in queue
+        .hits
+        .iter_mut()
+        .filter(|hit| hit.lands() && hit.attacker.is_some())
+    {
+        let Ok(block_chance) = blockers.get(hit.target) else {
+            continue;
+        };
+        if rng.gen::<f32>() < block_chance.0 {
+            hit.cancel(DamageReason::Blocked);
+            commands.trigger(BlockedAttackEvent {
+                defender: hit.target,
+            });
+        }
+    }
+}
+
+/// PostDamage: the rest of the AttackEffect — knockback, stun, and AoE
+/// splash — for every hit that landed.
 ///
-/// ParamSet has three queries because they overlap on components:
-///   p0 — read attacker + target world positions (GlobalTransform)
-///   p1 — mutate target's Health/Transform/AnimationState
-///   p2 — scan all entities with Health for AoE splash (reads GlobalTransform + Team)
-///
-/// Option<&BlockChance> in p1 lets us read BlockChance when it exists without
-/// excluding entities that don't have it.
-fn on_hit_observer(
-    trigger: On<OnHitEvent>,
+/// ParamSet has two queries because they overlap on GlobalTransform:
+///   p0 — the target's Transform/AnimationState/Velocity, and the attacker's position
+///   p1 — scan all targetable entities for AoE splash (reads GlobalTransform + Team)
+fn hit_effects_system(
+    queue: Res<DamageQueue>,
     mut params: ParamSet<(
-        Query<&GlobalTransform>,
         Query<(
-            &mut Health,
-            &mut Transform,
-            &mut AnimationState,
-            Option<&BlockChance>,
+            &GlobalTransform,
+            Option<&Health>,
+            Option<&mut AnimationState>,
+            Option<(&mut Velocity, &Mass)>,
         )>,
         Query<(Entity, &GlobalTransform, &Team), With<CanBeTargeted>>,
     )>,
     audio: Res<GameAudio>,
     mut commands: Commands,
 ) {
-    // ── Phase 1: read positions from p0 ──
-    // Scope the p0 borrow so it's released before we touch p1/p2.
-    let (attacker_pos, target_pos) = {
-        let p0 = params.p0();
-        let Some(attacker_pos) = p0.get(trigger.attacker).ok().map(|t| t.translation()) else {
-            return;
-        };
-        let Some(target_pos) = p0.get(trigger.target).ok().map(|t| t.translation()) else {
-            return;
+    for hit in queue.hits.iter().filter(|hit| hit.lands()) {
+        let mut bodies = params.p0();
+        let attacker_pos = hit
+            .attacker
+            .and_then(|attacker| bodies.get(attacker).ok())
+            .map(|(t, ..)| t.translation());
+        let Ok((target_transform, health, anim_state, body)) = bodies.get_mut(hit.target) else {
+            continue;
         };
-        (attacker_pos, target_pos)
-    };
-
-    // ── Phase 2: apply primary hit via p1 ──
-    if let Ok((mut health, transform, mut anim_state, block_chance)) =
-        params.p1().get_mut(trigger.target)
-    {
-        // Blocked attacks cancel everything — including AoE splash.
-        if let Some(block_chance) = block_chance {
-            let mut rng = rand::thread_rng();
-            if rng.gen::<f32>() < block_chance.0 {
-                commands.trigger(BlockedAttackEvent {
-                    defender: trigger.target,
-                });
-                return;
+        let target_pos = target_transform.translation();
+
+        // Knockback: an impulse away from the attacker, smaller for heavier
+        // targets. Knockback makes the target coast on it. Hits with no
+        // attacker have nothing to push away from.
+        if let Some(attacker_pos) = attacker_pos.filter(|_| hit.effect.knockback > 0.0) {
+            let diff = (target_pos - attacker_pos).truncate();
+            if let (Some((mut velocity, mass)), Some(direction)) = (body, diff.try_normalize()) {
+                velocity.0 += direction * knockback_speed(hit.effect.knockback) / mass.0;
+                commands.entity(hit.target).insert(Knockback);
             }
         }
 
-        // Apply damage
-        if health.0 > 0 {
-            health.0 -= trigger.effect.damage;
-            commands.trigger(DamagedEvent {
-                entity: trigger.target,
-            });
-        }
-
-        // Apply knockback: insert a Knockback component
-        if trigger.effect.knockback > 0.0 {
-            let diff = transform.translation - attacker_pos;
-            if diff.length() > 0.01 {
-                let direction = diff.normalize();
-                let target_pos = transform.translation
-                    + Vec3::new(
-                        direction.x * trigger.effect.knockback,
-                        direction.y * trigger.effect.knockback,
-                        0.0,
-                    );
-                commands.entity(trigger.target).insert(Knockback {
-                    start_position: transform.translation,
-                    target_position: target_pos,
-                    timer: Timer::from_seconds(1.0, TimerMode::Once),
-                });
-            }
-        }
-
-        if trigger.effect.stun_chance > 0.0 && health.0 > 0 {
+        if hit.effect.stun_chance > 0.0 && health.is_some_and(|h| h.0 > 0) {
             let mut rng = rand::thread_rng();
-            if rng.gen::<f32>() < trigger.effect.stun_chance {
-                if let Ok(mut target_commands) = commands.get_entity(trigger.target) {
+            if rng.gen::<f32>() < hit.effect.stun_chance {
+                if let Ok(mut target_commands) = commands.get_entity(hit.target) {
                     target_commands.insert((
                         Inert,
                         StunTimer(Timer::from_seconds(
-                            trigger.effect.stun_duration,
+                            hit.effect.stun_duration,
                             TimerMode::Once,
                         )),
                     ));
                     target_commands.remove::<ActiveAttack>();
                 }
-                anim_state.finished = true;
-                commands.trigger(StunnedEvent {
-                    entity: trigger.target,
-                });
+                if let Some(mut anim_state) = anim_state {
+                    anim_state.finished = true;
+                }
+                commands.trigger(StunnedEvent { entity: hit.target });
             }
         }
-    }
 
-    // ── Phase 3: AoE splash via p2 ──
-    // Only fires when the primary hit's effect has aoe_distance set.
-    // Secondary OnHitEvents have aoe_distance = None, so this block is
-    // skipped for them — preventing infinite recursion.
-    if let Some(aoe_dist) = trigger.effect.aoe_distance {
-        // Scope the p2 borrow — collect results into a Vec, then release p2
-        // before triggering events (which need &mut commands).
+        // AoE splash: only fires when the primary hit's effect has
+        // aoe_distance set. Secondary OnHitEvents have aoe_distance = None,
+        // so this block is skipped for them — preventing infinite recursion.
+        // They go through the pipeline on the next pass.
+        let Some(aoe_dist) = hit.effect.aoe_distance else {
+            continue;
+        };
         let splash_targets: Vec<Entity> = {
-            let p2 = params.p2();
-            let target_team = p2.get(trigger.target).ok().map(|(_, _, t)| *t);
+            let targetable = params.p1();
+            let target_team = targetable.get(hit.target).ok().map(|(_, _, t)| *t);
 
             if let Some(team) = target_team {
-                p2.iter()
+                targetable
+                    .iter()
                     .filter(|(e, pos, t)| {
-                        *e != trigger.target
+                        *e != hit.target
                             && **t == team
                             && pos.translation().distance(target_pos) <= aoe_dist
                     })
@@ -449,13 +553,12 @@ fn on_hit_observer(
             }
         };
 
-        if !splash_targets.is_empty() {
-            let mut splash_effect = trigger.effect.clone();
-            splash_effect.aoe_distance = None;
-
+        let mut splash_effect = hit.effect.clone();
+        splash_effect.aoe_distance = None;
+        if let Some(attacker) = hit.attacker {
             for splash_target in splash_targets {
                 commands.trigger(OnHitEvent {
-                    attacker: trigger.attacker,
+                    attacker,
                     target: splash_target,
                     effect: splash_effect.clone(),
                 });
@@ -556,9 +659,9 @@ fn attack_cooldown_system(
 
 /// Observer that reacts to StunnedEvent by spawning ice VFX and playing a sound.
 ///
-/// This is separate from on_hit_observer for the same reason DamagedEvent is
+/// This is separate from hit_effects_system for the same reason DamagedEvent is
 /// separate from OnHitEvent: decoupling "what happened" from "how to show it."
-/// The hit observer handles game logic (damage, stun state), while this observer
+/// The damage pipeline handles game logic (damage, stun state), while this observer
 /// handles presentation (VFX, audio). This makes it easy to add/change visual
 /// feedback without touching combat logic.
 fn on_stunned_observer(trigger: On<StunnedEvent>, audio: Res<GameAudio>, mut commands: Commands) {
@@ -591,7 +694,7 @@ fn on_stunned_observer(trigger: On<StunnedEvent>, audio: Res<GameAudio>, mut com
 
 /// Observer that reacts to BlockedAttackEvent by flashing the shield white
 /// and playing a block sound. This is purely presentation — the game logic
-/// (cancelling the attack) already happened in on_hit_observer's early return.
+/// (cancelling the attack) already happened in block_hit_system.
 ///
 /// To find the shield, we iterate the defender's Children and check which one
 /// has the Shield marker component. This is a standard Bevy pattern for
@@ -716,3 +819,17 @@ fn floating_text_system(
         }
     }
 }
+
+/// Ticks Invulnerable and removes it once it runs out.
+fn invulnerable_system(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Invulnerable)>,
+    time: Res<Time>,
+) {
+    for (entity, mut invulnerable) in query.iter_mut() {
+        invulnerable.0.tick(time.delta());
+        if invulnerable.0.is_finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
+}
@@ -0,0 +1,237 @@
+// Damage pipeline: everything between "the attack connected" and "HP went
+// down", in ordered stages that other plugins hook into.
+//
+// on_hit_observer (combat.rs) turns each OnHitEvent into a PendingHit on the
+// DamageQueue. Damage with no attacker behind it (hazard zones, wall slams)
+// is pushed onto the queue directly. Once a frame, Collect takes everything
+// queued so far (so whatever queues hits should run before it), and the hits
+// go through these stages in order:
+//   1. PreHit:     decide whether the hit lands at all. A modifier here
+//                  calls cancel() (block, invulnerability, a dodge...).
+//   2. Calculate:  adjust `amount` with reduce() or by hand (armor, crits,
+//                  resistances, multipliers).
+//   3. Apply:      apply_damage_system takes `amount` off the target's
+//                  Health, fires DamagedEvent, and fires DamageDealt for
+//                  every hit, cancelled or not.
+//   4. PostDamage: react to what landed (knockback, stun, splash, lifesteal,
+//                  thorns, on-kill triggers). `dealt` says how much did.
+//
+// To add a modifier, write a system that works on DamageQueue::hits and add
+// it with .in_set(DamageStage::Calculate) (or whichever stage). Order within
+// a stage with .before()/.after() if it matters.
+//
+// Hits queued while the pipeline runs (splash hits, say) wait for the next
+// frame's pass, so they go through every stage like any other hit.
+
+use bevy::prelude::*;
+
+use crate::combat::AttackEffect;
+use crate::health::{DamagedEvent, Health};
+use crate::GameState;
+
+pub struct DamagePlugin;
+
+impl Plugin for DamagePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DamageQueue>()
+            .configure_sets(
+                Update,
+                (
+                    DamageStage::Collect,
+                    DamageStage::PreHit,
+                    DamageStage::Calculate,
+                    DamageStage::Apply,
+                    DamageStage::PostDamage,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Combat)),
+            )
+            .add_observer(on_damage_dealt)
+            .add_systems(
+                Update,
+                (
+                    start_hits_system.in_set(DamageStage::Collect),
+                    apply_damage_system.in_set(DamageStage::Apply),
+                )
+                    .run_if(in_state(GameState::Combat)),
+            )
+            .add_systems(OnExit(GameState::Combat), clear_damage_queue);
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DamageStage {
+    Collect,
+    PreHit,
+    Calculate,
+    Apply,
+    PostDamage,
+}
+
+/// Why a hit was stopped or did less than its raw damage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageReason {
+    Blocked,
+    Invulnerable,
+    Armor,
+}
+
+/// One reduction made to a hit, and how much damage it took off. A
+/// cancelled hit has a single Reduction for all of its raw damage.
+#[derive(Clone, Copy, Debug)]
+pub struct Reduction {
+    pub reason: DamageReason,
+    pub amount: i32,
+}
+
+/// One hit working its way through the pipeline.
+pub struct PendingHit {
+    /// Whoever threw the hit, if anyone (hazards and walls have no source).
+    pub attacker: Option<Entity>,
+    pub target: Entity,
+    pub effect: AttackEffect,
+    /// The attack's damage before any pipeline modifier. Bonuses that live
+    /// on the attacker's KnownAttacks (relics like Sharp Goop, the Enraged
+    /// affix) are already in it; only what happens in the stages below is
+    /// tracked in `reductions`.
+    pub raw: i32,
+    /// Damage after the modifiers so far. Apply takes this off Health.
+    pub amount: i32,
+    /// Set in PreHit to stop the hit outright: no damage, knockback, stun,
+    /// or splash.
+    pub cancelled: Option<DamageReason>,
+    /// Every reduction made to `amount`, in order.
+    pub reductions: Vec<Reduction>,
+    /// Damage that actually came off the target's Health. Set by Apply;
+    /// zero if the hit was cancelled or the target was already dead.
+    pub dealt: i32,
+}
+
+impl PendingHit {
+    pub fn new(attacker: Option<Entity>, target: Entity, effect: AttackEffect) -> Self {
+        Self {
+            attacker,
+            target,
+            raw: effect.damage,
+            amount: effect.damage,
+            effect,
+            cancelled: None,
+            reductions: Vec::new(),
+            dealt: 0,
+        }
+    }
+
+    /// Whether the hit is still going to land.
+    pub fn lands(&self) -> bool {
+        self.cancelled.is_none()
+    }
+
+    /// Stops the hit. The first cancel wins.
+    pub fn cancel(&mut self, reason: DamageReason) {
+        if self.lands() {
+            self.cancelled = Some(reason);
+            self.reductions.push(Reduction {
+                reason,
+                amount: self.amount,
+            });
+            self.amount = 0;
+        }
+    }
+
+    /// Lowers `amount` to `to`, recording why. Does nothing if `to` isn't
+    /// lower.
+    pub fn reduce(&mut self, reason: DamageReason, to: i32) {
+        if to < self.amount {
+            self.reductions.push(Reduction {
+                reason,
+                amount: self.amount - to,
+            });
+            self.amount = to;
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct DamageQueue {
+    /// Hits waiting for the next pass through the pipeline.
+    incoming: Vec<PendingHit>,
+    /// Hits going through the pipeline this frame. Stage systems work on
+    /// these.
+    pub hits: Vec<PendingHit>,
+}
+
+impl DamageQueue {
+    pub fn push(&mut self, hit: PendingHit) {
+        self.incoming.push(hit);
+    }
+}
+
+/// Fired by apply_damage_system for every hit that went through the
+/// pipeline, including cancelled ones.
+#[derive(Event, Debug)]
+pub struct DamageDealt {
+    pub attacker: Option<Entity>,
+    pub target: Entity,
+    /// PendingHit::raw: after the attacker's own bonuses, before the
+    /// pipeline.
+    pub raw_amount: i32,
+    pub final_amount: i32,
+    pub reductions: Vec<Reduction>,
+}
+
+/// Moves the hits queued since last frame into the pipeline.
+fn start_hits_system(mut queue: ResMut<DamageQueue>) {
+    queue.hits = std::mem::take(&mut queue.incoming);
+}
+
+fn apply_damage_system(
+    mut queue: ResMut<DamageQueue>,
+    mut targets: Query<&mut Health>,
+    mut commands: Commands,
+) {
+    for hit in &mut queue.hits {
+        if hit.lands() {
+            if let Ok(mut health) = targets.get_mut(hit.target) {
+                if health.0 > 0 {
+                    health.0 -= hit.amount;
+                    hit.dealt = hit.amount;
+                    commands.trigger(DamagedEvent {
+                        entity: hit.target,
+                        source: hit.attacker,
+                        amount: hit.amount,
+                    });
+                }
+            }
+        }
+
+        commands.trigger(DamageDealt {
+            attacker: hit.attacker,
+            target: hit.target,
+            raw_amount: hit.raw,
+            final_amount: hit.dealt,
+            reductions: hit.reductions.clone(),
+        });
+    }
+}
+
+/// Logs every resolved hit. Run with RUST_LOG=debug to see them.
+fn on_damage_dealt(trigger: On<DamageDealt>) {
+    let reductions: Vec<String> = trigger
+        .reductions
+        .iter()
+        .map(|r| format!("{:?} -{}", r.reason, r.amount))
+        .collect();
+    debug!(
+        "{:?} hit {:?} for {} of {} [{}]",
+        trigger.attacker,
+        trigger.target,
+        trigger.final_amount,
+        trigger.raw_amount,
+        reductions.join(", ")
+    );
+}
+
+fn clear_damage_queue(mut queue: ResMut<DamageQueue>) {
+    queue.incoming.clear();
+    queue.hits.clear();
+}
@@ -0,0 +1,792 @@
+// Depth map: a branching path between depths.
+//
+// Instead of "Risk deeper" always meaning "the next wave", each depth is a
+// row of nodes and the player walks a path through them. Leaving camp opens
+// the map; the player picks one of the nodes connected to where they are:
+//   - Fight: an ordinary wave
+//   - Elite: a tougher wave that pays a goop bonus on victory
+//   - Treasure: free run goop, no fight
+//   - Merchant: sells one relic for run goop
+//   - Rest: heal every survivor, no fight
+//   - Event: something unpredictable happens
+//   - Boss: every path funnels into the boss on boss depths (bosses.rs)
+//
+// The whole map comes from the run seed. Rows are generated lazily, one depth
+// at a time, each from its own rng seeded by (run seed, depth) — so the same
+// seed always produces the same map, waves, prices, and event outcomes, no
+// matter how far ahead the UI has looked. Pass `--run-seed <n>` to replay one.
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::armies::{create_elite_army, roll_enemy_army, EnemyWave};
+use crate::bosses::is_boss_depth;
+use crate::camp::REST_HEAL_FRACTION;
+use crate::end_round::{CombatLevel, GoopEarned};
+use crate::health::{Dying, Health, MaxHealth};
+use crate::pick_target::Team;
+use crate::relics::{Relic, RunRelics, RELICS};
+use crate::spawn_slimes::setup_slime_spawn;
+use crate::{CombatState, GameFont, GameState};
+
+pub struct DepthMapPlugin;
+
+impl Plugin for DepthMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Combat), init_depth_map)
+            .add_systems(OnExit(GameState::Combat), cleanup_depth_map)
+            .add_systems(OnEnter(CombatState::PostCombat), pay_elite_bonus)
+            .add_systems(OnEnter(CombatState::Map), mark_map_dirty)
+            .add_systems(
+                Update,
+                (
+                    refresh_map_ui,
+                    map_node_button_system,
+                    merchant_button_system,
+                    map_preview_system,
+                    button_hover_system,
+                )
+                    .chain()
+                    .run_if(in_state(CombatState::Map)),
+            );
+    }
+}
+
+/// Depth columns visible on the map at once, starting with the current one.
+const MAP_COLUMNS: u32 = 5;
+const MIN_ROW_WIDTH: usize = 2;
+const MAX_ROW_WIDTH: usize = 4;
+/// Elites don't show up until the player has a few fights behind them.
+const ELITE_MIN_DEPTH: u32 = 4;
+
+const NODE_WEIGHTS: &[(NodeKind, u32)] = &[
+    (NodeKind::Fight, 45),
+    (NodeKind::Elite, 15),
+    (NodeKind::Treasure, 10),
+    (NodeKind::Merchant, 10),
+    (NodeKind::Rest, 10),
+    (NodeKind::Event, 10),
+];
+
+// Map panel layout, in UI pixels.
+const PANEL_WIDTH: f32 = 1040.0;
+const PANEL_HEIGHT: f32 = 420.0;
+const COLUMN_SPACING: f32 = 210.0;
+const NODE_WIDTH: f32 = 120.0;
+const NODE_HEIGHT: f32 = 56.0;
+
+const BUTTON_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const BUTTON_HOVER_COLOR: Color = Color::srgb(0.35, 0.35, 0.35);
+const BUTTON_PRESSED_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
+const REACHABLE_BORDER: Color = Color::srgb(1.0, 0.85, 0.2);
+const EDGE_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.25);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    Fight,
+    Elite,
+    Treasure,
+    Merchant,
+    Rest,
+    Event,
+    Boss,
+}
+
+impl NodeKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            NodeKind::Fight => "Fight",
+            NodeKind::Elite => "Elite",
+            NodeKind::Treasure => "Treasure",
+            NodeKind::Merchant => "Merchant",
+            NodeKind::Rest => "Rest",
+            NodeKind::Event => "Event",
+            NodeKind::Boss => "BOSS",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            NodeKind::Fight => Color::srgb(0.45, 0.25, 0.25),
+            NodeKind::Elite => Color::srgb(0.65, 0.15, 0.15),
+            NodeKind::Treasure => Color::srgb(0.6, 0.5, 0.1),
+            NodeKind::Merchant => Color::srgb(0.2, 0.45, 0.3),
+            NodeKind::Rest => Color::srgb(0.2, 0.35, 0.55),
+            NodeKind::Event => Color::srgb(0.4, 0.25, 0.5),
+            NodeKind::Boss => Color::srgb(0.3, 0.05, 0.05),
+        }
+    }
+}
+
+/// What an Event node does. Rolled with the map, but hidden until visited.
+#[derive(Clone, Copy, Debug)]
+pub enum MapEvent {
+    /// Free run goop.
+    GoopGeyser(u32),
+    /// Every survivor loses this much HP, but never drops below 1.
+    CursedPuddle(i32),
+    /// Double or halve the run's goop.
+    GamblersAltar { won: bool },
+}
+
+#[derive(Clone)]
+pub struct MapNode {
+    pub kind: NodeKind,
+    /// The enemies for Fight and Elite nodes.
+    pub wave: Option<EnemyWave>,
+    /// Goop amount: elite bonus, treasure payout, or merchant price.
+    pub reward: u32,
+    /// What the merchant sells.
+    pub relic: Option<Relic>,
+    pub event: Option<MapEvent>,
+    /// Indices of the connected nodes in the next row.
+    pub next: Vec<usize>,
+}
+
+impl MapNode {
+    fn roll(depth: u32, rng: &mut StdRng) -> Self {
+        let kind = NODE_WEIGHTS
+            .iter()
+            .filter(|(kind, _)| *kind != NodeKind::Elite || depth >= ELITE_MIN_DEPTH)
+            .collect::<Vec<_>>()
+            .choose_weighted(rng, |(_, weight)| *weight)
+            .map(|(kind, _)| *kind)
+            .unwrap_or(NodeKind::Fight);
+
+        let mut node = MapNode {
+            kind,
+            wave: None,
+            reward: 0,
+            relic: None,
+            event: None,
+            next: Vec::new(),
+        };
+        match kind {
+            NodeKind::Fight | NodeKind::Boss => node.wave = Some(roll_enemy_army(depth, rng)),
+            NodeKind::Elite => {
+                node.wave = Some(create_elite_army(depth, rng));
+                node.reward = 5 + depth;
+            }
+            NodeKind::Treasure => node.reward = rng.gen_range(4..=8) + depth * 2,
+            NodeKind::Merchant => {
+                node.relic = RELICS.choose(rng).map(|d| d.relic);
+                node.reward = 10 + depth * 2;
+            }
+            NodeKind::Rest => {}
+            NodeKind::Event => {
+                node.event = Some(match rng.gen_range(0..3) {
+                    0 => MapEvent::GoopGeyser(rng.gen_range(5..=10) + depth),
+                    1 => MapEvent::CursedPuddle(2),
+                    _ => MapEvent::GamblersAltar {
+                        won: rng.gen_bool(0.5),
+                    },
+                })
+            }
+        }
+        node
+    }
+
+    /// One-line preview shown when hovering the node. Events stay a mystery.
+    pub fn preview(&self) -> String {
+        match self.kind {
+            NodeKind::Fight | NodeKind::Elite | NodeKind::Boss => {
+                let Some(wave) = &self.wave else {
+                    return "The fight that started the run".to_string();
+                };
+                let mut text = format!(
+                    "{}: {} | reward {} goop",
+                    self.kind.label(),
+                    wave.describe(),
+                    wave.goop_value()
+                );
+                if self.reward > 0 {
+                    text.push_str(&format!(" +{} bonus", self.reward));
+                }
+                if let Some(boss) = wave.boss {
+                    text.push_str(&format!(" +{} and a relic", boss.def().reward_goop));
+                }
+                text
+            }
+            NodeKind::Treasure => format!("Treasure: +{} goop, no fight", self.reward),
+            NodeKind::Merchant => match self.relic {
+                Some(relic) => format!(
+                    "Merchant: sells {} for {} goop",
+                    relic.def().name,
+                    self.reward
+                ),
+                None => "Merchant: nothing for sale".to_string(),
+            },
+            NodeKind::Rest => format!(
+                "Rest: heal everyone {:.0}% of max HP, no fight",
+                REST_HEAL_FRACTION * 100.0
+            ),
+            NodeKind::Event => "Event: ???".to_string(),
+        }
+    }
+}
+
+/// Seed for everything the run generates. Inserted when a run starts.
+#[derive(Resource)]
+pub struct RunSeed(pub u64);
+
+impl RunSeed {
+    /// Reads `--run-seed <n>` / `--run-seed=<n>` if given, otherwise picks
+    /// a random seed.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let value = match arg.strip_prefix("--run-seed") {
+                Some("") => args.next(),
+                Some(rest) => rest.strip_prefix('=').map(str::to_string),
+                None => continue,
+            };
+            match value.as_deref().map(str::parse::<u64>) {
+                Some(Ok(seed)) => return Self(seed),
+                _ => warn!("Ignoring --run-seed without a number"),
+            }
+        }
+        Self(rand::thread_rng().r#gen())
+    }
+}
+
+/// The run's map and where the player is on it. rows[0] is depth 1.
+#[derive(Resource)]
+pub struct DepthMap {
+    seed: u64,
+    rows: Vec<Vec<MapNode>>,
+    /// Index of the current node within the current depth's row.
+    pub position: usize,
+    /// Whether the merchant at the current node already sold its relic.
+    pub merchant_used: bool,
+    /// Bonus goop owed if the current fight is won (elite nodes).
+    pub pending_bonus: u32,
+    /// What happened at the last non-fight node, shown on the map.
+    pub message: Option<String>,
+}
+
+impl DepthMap {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rows: Vec::new(),
+            position: 0,
+            merchant_used: false,
+            pending_bonus: 0,
+            message: None,
+        }
+    }
+
+    /// Makes sure rows exist up to and including `depth`.
+    pub fn ensure_depth(&mut self, depth: u32) {
+        while (self.rows.len() as u32) < depth {
+            self.generate_row();
+        }
+    }
+
+    pub fn row(&self, depth: u32) -> &[MapNode] {
+        &self.rows[(depth - 1) as usize]
+    }
+
+    fn generate_row(&mut self) {
+        let depth = self.rows.len() as u32 + 1;
+        let mut rng =
+            StdRng::seed_from_u64(self.seed ^ (depth as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+
+        // Depth 1 is the opening fight, already rolled by spawn_slimes.
+        if depth == 1 {
+            self.rows.push(vec![MapNode {
+                kind: NodeKind::Fight,
+                wave: None,
+                reward: 0,
+                relic: None,
+                event: None,
+                next: Vec::new(),
+            }]);
+            return;
+        }
+
+        let row: Vec<MapNode> = if is_boss_depth(depth) {
+            vec![MapNode {
+                kind: NodeKind::Boss,
+                wave: Some(roll_enemy_army(depth, &mut rng)),
+                reward: 0,
+                relic: None,
+                event: None,
+                next: Vec::new(),
+            }]
+        } else {
+            let width = rng.gen_range(MIN_ROW_WIDTH..=MAX_ROW_WIDTH);
+            (0..width).map(|_| MapNode::roll(depth, &mut rng)).collect()
+        };
+        let width = row.len();
+
+        // Connect the previous row: every node gets the node at the nearest
+        // height below it (plus sometimes a neighbour), then any node left
+        // without an incoming edge gets one from the nearest node above.
+        let prev = self.rows.last_mut().expect("depth 1 row always exists");
+        let nearest = |i: usize, from: usize, to: usize| {
+            (((i as f32 + 0.5) / from as f32) * to as f32) as usize
+        };
+        let prev_width = prev.len();
+        for (i, node) in prev.iter_mut().enumerate() {
+            let j = nearest(i, prev_width, width);
+            node.next.push(j);
+            if rng.gen_bool(0.4) {
+                let neighbour = if rng.gen_bool(0.5) {
+                    j + 1
+                } else {
+                    j.wrapping_sub(1)
+                };
+                if neighbour < width {
+                    node.next.push(neighbour);
+                }
+            }
+        }
+        for j in 0..width {
+            if !prev.iter().any(|n| n.next.contains(&j)) {
+                prev[nearest(j, width, prev_width)].next.push(j);
+            }
+        }
+        for node in prev.iter_mut() {
+            node.next.sort_unstable();
+            node.next.dedup();
+        }
+
+        self.rows.push(row);
+    }
+}
+
+#[derive(Component)]
+struct MapRoot;
+
+#[derive(Component)]
+struct MapNodeButton {
+    depth: u32,
+    index: usize,
+    reachable: bool,
+}
+
+#[derive(Component)]
+struct MapPreviewText;
+
+#[derive(Component)]
+struct MerchantButton;
+
+fn init_depth_map(mut commands: Commands) {
+    let seed = RunSeed::from_args(std::env::args());
+    info!("Run seed: {}", seed.0);
+    let mut map = DepthMap::new(seed.0);
+    map.ensure_depth(1);
+    commands.insert_resource(map);
+    commands.insert_resource(seed);
+}
+
+fn cleanup_depth_map(mut commands: Commands) {
+    commands.remove_resource::<DepthMap>();
+    commands.remove_resource::<RunSeed>();
+}
+
+/// Elite bonuses are only paid if the elite fight is won.
+fn pay_elite_bonus(
+    teams: Query<&Team>,
+    mut map: ResMut<DepthMap>,
+    mut goop_earned: ResMut<GoopEarned>,
+) {
+    let bonus = std::mem::take(&mut map.pending_bonus);
+    let has_player = teams.iter().any(|t| *t == Team::Player);
+    let has_enemy = teams.iter().any(|t| *t == Team::Enemy);
+    if has_player && !has_enemy {
+        goop_earned.0 += bonus;
+    }
+}
+
+/// Forces refresh_map_ui to build the map on entering the state.
+fn mark_map_dirty(mut map: ResMut<DepthMap>) {
+    map.set_changed();
+}
+
+/// Rebuilds the map screen whenever the map changes: on entering the state,
+/// after resolving a non-fight node, and after buying from a merchant.
+fn refresh_map_ui(
+    mut commands: Commands,
+    mut map: ResMut<DepthMap>,
+    combat_level: Res<CombatLevel>,
+    roots: Query<Entity, With<MapRoot>>,
+    game_font: Res<GameFont>,
+) {
+    if !map.is_changed() {
+        return;
+    }
+    for entity in &roots {
+        commands.entity(entity).despawn();
+    }
+
+    let current = combat_level.0;
+    // Generating rows mutates the map, which would retrigger this system next
+    // frame. bypass_change_detection keeps it to one rebuild per change.
+    map.bypass_change_detection()
+        .ensure_depth(current + MAP_COLUMNS - 1);
+    let map = &*map;
+    let font = game_font.0.clone();
+
+    let node_center = |column: u32, index: usize, width: usize| {
+        Vec2::new(
+            (PANEL_WIDTH - COLUMN_SPACING * (MAP_COLUMNS - 1) as f32) / 2.0
+                + column as f32 * COLUMN_SPACING,
+            (index as f32 + 0.5) / width as f32 * PANEL_HEIGHT,
+        )
+    };
+
+    let current_node = &map.row(current)[map.position];
+    let at_merchant = current_node.kind == NodeKind::Merchant && !map.merchant_used;
+
+    commands
+        .spawn((
+            MapRoot,
+            DespawnOnExit(CombatState::Map),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(16.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        ))
+        .with_children(|root| {
+            root.spawn((
+                Text::new("Choose your path"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 44.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            if let Some(message) = &map.message {
+                root.spawn((
+                    Text::new(message.clone()),
+                    TextFont {
+                        font: font.clone(),
+                        font_size: 24.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(1.0, 0.85, 0.2)),
+                ));
+            }
+
+            root.spawn(Node {
+                width: Val::Px(PANEL_WIDTH),
+                height: Val::Px(PANEL_HEIGHT),
+                ..default()
+            })
+            .with_children(|panel| {
+                // Edges first so the nodes draw on top of them.
+                for column in 0..MAP_COLUMNS - 1 {
+                    let depth = current + column;
+                    let row = map.row(depth);
+                    let next_width = map.row(depth + 1).len();
+                    for (i, node) in row.iter().enumerate() {
+                        // Only the current node's paths matter in its own column.
+                        if column == 0 && i != map.position {
+                            continue;
+                        }
+                        let from = node_center(column, i, row.len());
+                        for &j in &node.next {
+                            let to = node_center(column + 1, j, next_width);
+                            spawn_edge(panel, from, to);
+                        }
+                    }
+                }
+
+                for column in 0..MAP_COLUMNS {
+                    let depth = current + column;
+                    let row = map.row(depth);
+                    for (index, node) in row.iter().enumerate() {
+                        let is_current = column == 0 && index == map.position;
+                        let reachable = column == 1 && current_node.next.contains(&index);
+                        let center = node_center(column, index, row.len());
+                        spawn_map_node(
+                            panel,
+                            &font,
+                            node,
+                            center,
+                            MapNodeButton {
+                                depth,
+                                index,
+                                reachable,
+                            },
+                            is_current,
+                        );
+                    }
+                }
+            });
+
+            root.spawn((
+                MapPreviewText,
+                Text::new("Hover a node to preview it"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 22.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.85, 0.85, 0.85)),
+            ));
+
+            if at_merchant {
+                if let Some(relic) = current_node.relic {
+                    root.spawn((
+                        MerchantButton,
+                        Button,
+                        Node {
+                            padding: UiRect::axes(Val::Px(20.0), Val::Px(10.0)),
+                            ..default()
+                        },
+                        BackgroundColor(BUTTON_COLOR),
+                    ))
+                    .with_child((
+                        Text::new(format!(
+                            "Buy {} ({} goop)",
+                            relic.def().name,
+                            current_node.reward
+                        )),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                }
+            }
+        });
+}
+
+/// A thin rotated bar from `from` to `to`, in panel pixels.
+fn spawn_edge(panel: &mut ChildSpawnerCommands, from: Vec2, to: Vec2) {
+    let delta = to - from;
+    let length = delta.length();
+    let mid = (from + to) / 2.0;
+    panel.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(mid.x - length / 2.0),
+            top: Val::Px(mid.y - 1.5),
+            width: Val::Px(length),
+            height: Val::Px(3.0),
+            ..default()
+        },
+        // UI y points down, so atan2 on the raw delta is already a clockwise angle.
+        UiTransform {
+            rotation: Rot2::radians(delta.y.atan2(delta.x)),
+            ..UiTransform::IDENTITY
+        },
+        BackgroundColor(EDGE_COLOR),
+        Pickable::IGNORE,
+    ));
+}
+
+fn spawn_map_node(
+    panel: &mut ChildSpawnerCommands,
+    font: &Handle<Font>,
+    node: &MapNode,
+    center: Vec2,
+    button: MapNodeButton,
+    is_current: bool,
+) {
+    let color = if button.reachable || is_current {
+        node.kind.color()
+    } else {
+        node.kind.color().with_alpha(0.45)
+    };
+    let border = if is_current {
+        Color::WHITE
+    } else if button.reachable {
+        REACHABLE_BORDER
+    } else {
+        Color::NONE
+    };
+    panel
+        .spawn((
+            button,
+            Button,
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(center.x - NODE_WIDTH / 2.0),
+                top: Val::Px(center.y - NODE_HEIGHT / 2.0),
+                width: Val::Px(NODE_WIDTH),
+                height: Val::Px(NODE_HEIGHT),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(3.0)),
+                ..default()
+            },
+            BackgroundColor(color),
+            BorderColor::all(border),
+        ))
+        .with_child((
+            Text::new(node.kind.label()),
+            TextFont {
+                font: font.clone(),
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        ));
+}
+
+/// Moves to a clicked node in the next depth. Fights queue their wave and
+/// start the countdown; everything else resolves right here on the map.
+fn map_node_button_system(
+    mut commands: Commands,
+    query: Query<(&Interaction, &MapNodeButton), Changed<Interaction>>,
+    mut map: ResMut<DepthMap>,
+    mut combat_level: ResMut<CombatLevel>,
+    mut goop_earned: ResMut<GoopEarned>,
+    mut units: Query<(&Team, &mut Health, &MaxHealth), Without<Dying>>,
+    mut next_state: ResMut<NextState<CombatState>>,
+) {
+    let Some(button) = query
+        .iter()
+        .find(|(i, b)| **i == Interaction::Pressed && b.reachable)
+        .map(|(_, b)| b)
+    else {
+        return;
+    };
+
+    let node = map.row(button.depth)[button.index].clone();
+    combat_level.0 = button.depth;
+    map.position = button.index;
+    map.merchant_used = false;
+    map.message = None;
+
+    let mut player_units = units
+        .iter_mut()
+        .filter(|(team, _, _)| **team == Team::Player);
+    match node.kind {
+        NodeKind::Fight | NodeKind::Elite | NodeKind::Boss => {
+            map.pending_bonus = node.reward;
+            if let Some(wave) = node.wave {
+                setup_slime_spawn(&mut commands, None, wave);
+            }
+            next_state.set(CombatState::PreCombat);
+            return;
+        }
+        NodeKind::Treasure => {
+            goop_earned.0 += node.reward;
+            map.message = Some(format!("Treasure! +{} goop", node.reward));
+        }
+        NodeKind::Merchant => {
+            map.message = Some("A merchant waves you over".to_string());
+        }
+        NodeKind::Rest => {
+            for (_, mut health, max_health) in player_units.by_ref() {
+                let heal = (max_health.0 as f32 * REST_HEAL_FRACTION).ceil() as i32;
+                health.0 = (health.0 + heal).min(max_health.0);
+            }
+            map.message = Some("Your slimes rest and recover".to_string());
+        }
+        NodeKind::Event => {
+            map.message = Some(match node.event {
+                Some(MapEvent::GoopGeyser(amount)) => {
+                    goop_earned.0 += amount;
+                    format!("A goop geyser erupts! +{amount} goop")
+                }
+                Some(MapEvent::CursedPuddle(damage)) => {
+                    for (_, mut health, _) in player_units.by_ref() {
+                        health.0 = (health.0 - damage).max(1);
+                    }
+                    format!("A cursed puddle! Every slime loses {damage} HP")
+                }
+                Some(MapEvent::GamblersAltar { won: true }) => {
+                    goop_earned.0 *= 2;
+                    "The gambler's altar doubles your goop!".to_string()
+                }
+                Some(MapEvent::GamblersAltar { won: false }) => {
+                    goop_earned.0 /= 2;
+                    "The gambler's altar takes half your goop".to_string()
+                }
+                None => "Nothing happens".to_string(),
+            });
+        }
+    }
+    info!("Depth {}: {}", button.depth, node.kind.label());
+}
+
+fn merchant_button_system(
+    query: Query<&Interaction, (Changed<Interaction>, With<MerchantButton>)>,
+    mut map: ResMut<DepthMap>,
+    combat_level: Res<CombatLevel>,
+    mut relics: ResMut<RunRelics>,
+    mut goop_earned: ResMut<GoopEarned>,
+) {
+    if !query.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+    let node = &map.row(combat_level.0)[map.position];
+    let (Some(relic), price) = (node.relic, node.reward) else {
+        return;
+    };
+
+    map.message = Some(if relics.has(relic) {
+        format!("You already have {}", relic.def().name)
+    } else if goop_earned.0 < price {
+        format!("Not enough goop for {}", relic.def().name)
+    } else {
+        goop_earned.0 -= price;
+        relics.0.push(relic);
+        map.merchant_used = true;
+        format!("Bought {}", relic.def().name)
+    });
+}
+
+fn map_preview_system(
+    query: Query<(&Interaction, &MapNodeButton), Changed<Interaction>>,
+    map: Res<DepthMap>,
+    mut preview: Query<&mut Text, With<MapPreviewText>>,
+) {
+    for (interaction, button) in &query {
+        if *interaction != Interaction::Hovered {
+            continue;
+        }
+        let node = &map.row(button.depth)[button.index];
+        let mut text = format!("Depth {} - {}", button.depth, node.preview());
+        if !button.reachable {
+            text.push_str(" (not reachable yet)");
+        }
+        for mut preview in &mut preview {
+            **preview = text.clone();
+        }
+    }
+}
+
+/// Merchant button colors, plus a border highlight on reachable map nodes.
+fn button_hover_system(
+    mut merchant: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<MerchantButton>),
+    >,
+    mut nodes: Query<(&Interaction, &MapNodeButton, &mut BorderColor), Changed<Interaction>>,
+) {
+    for (interaction, mut bg) in &mut merchant {
+        *bg = match interaction {
+            Interaction::Pressed => BUTTON_PRESSED_COLOR.into(),
+            Interaction::Hovered => BUTTON_HOVER_COLOR.into(),
+            Interaction::None => BUTTON_COLOR.into(),
+        };
+    }
+    for (interaction, button, mut border) in &mut nodes {
+        if !button.reachable {
+            continue;
+        }
+        *border = BorderColor::all(match interaction {
+            Interaction::None => REACHABLE_BORDER,
+            _ => Color::WHITE,
+        });
+    }
+}
@@ -2,17 +2,20 @@ use bevy::prelude::*;
 use rand::Rng;
 
 use crate::animation::{AnimationState, AnimationType, IdleAnimation, VictoryAnimation};
-use crate::armies::create_enemy_army;
+use crate::casualties::Casualties;
 use crate::combat::{ActiveAttack, AttackCooldown};
 use crate::health::Dying;
 use crate::movement::{Knockback, Speed, TargetEntity, TargetTransform};
+use crate::odds::WinOdds;
 use crate::pick_target::Team;
 use crate::render::Background;
+use crate::risk::{venture_quote, RunRisk};
 use crate::save_load::SaveData;
 use crate::screen_fade::{spawn_screen_fade, ScreenFade};
 use crate::setup_round::{Inert, PreGameTimer, StunTimer};
-use crate::spawn_slimes::{setup_slime_spawn, GoopValue, SlimeSpawnTimer, SlimesToSpawn};
+use crate::spawn_slimes::{GoopValue, SlimeSpawnTimer, SlimesToSpawn};
 use crate::special_abilities::{Merging, PreMerging};
+use crate::splitting::PendingSplit;
 use crate::{CombatState, GameFont, GameState};
 
 pub struct EndRoundPlugin;
@@ -30,7 +33,10 @@ impl Plugin for EndRoundPlugin {
                 (check_round_end_system, accumulate_goop_system)
                     .run_if(in_state(CombatState::DuringCombat)),
             )
-            .add_systems(Update, update_goop_text.run_if(in_state(GameState::Combat)))
+            .add_systems(
+                Update,
+                (update_goop_text, update_depth_text).run_if(in_state(GameState::Combat)),
+            )
             .add_systems(
                 Update,
                 (
@@ -77,7 +83,6 @@ struct DepthText;
 const BUTTON_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
 const BUTTON_HOVER_COLOR: Color = Color::srgb(0.35, 0.35, 0.35);
 const BUTTON_PRESSED_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
-const VENTURE_MULTIPLIER: f32 = 1.2;
 
 fn init_goop_earned(mut commands: Commands) {
     commands.insert_resource(GoopEarned(0));
@@ -150,12 +155,29 @@ fn update_goop_text(
     }
 }
 
+fn update_depth_text(
+    combat_level: Res<CombatLevel>,
+    mut depth_query: Query<&mut Text, With<DepthText>>,
+) {
+    if !combat_level.is_changed() {
+        return;
+    }
+    for mut text in &mut depth_query {
+        **text = format!("Depth: {}", combat_level.0);
+    }
+}
+
 /// Checks if one team has been eliminated. If so, transitions to PostCombat.
-fn check_round_end_system(teams: Query<&Team>, mut next_state: ResMut<NextState<CombatState>>) {
+fn check_round_end_system(
+    teams: Query<&Team>,
+    pending_splits: Query<&PendingSplit>,
+    mut next_state: ResMut<NextState<CombatState>>,
+) {
     let mut has_player = false;
     let mut has_enemy = false;
 
-    for team in &teams {
+    // A split that hasn't spawned its children yet still counts as alive.
+    for team in teams.iter().chain(pending_splits.iter().map(|p| &p.team)) {
         match team {
             Team::Player => has_player = true,
             Team::Enemy => has_enemy = true,
@@ -177,6 +199,10 @@ fn enter_post_combat(
     mut survivors: Query<(Entity, &mut AnimationType, &VictoryAnimation, &Team)>,
     frozen_mergers: Query<Entity, Or<(With<PreMerging>, With<Merging>)>>,
     game_font: Res<GameFont>,
+    casualties: Option<Res<Casualties>>,
+    save_data: Res<SaveData>,
+    risk: Option<Res<RunRisk>>,
+    goop_earned: Res<GoopEarned>,
 ) {
     let mut has_player = false;
     let mut has_enemy = false;
@@ -252,6 +278,37 @@ fn enter_post_combat(
                 TextLayout::new_with_justify(Justify::Center),
             ));
 
+            // With the casualties rule on, remind the player what this run
+            // has cost so far — these slimes are gone when the run ends.
+            if let Some(casualties) = casualties.filter(|_| save_data.settings.casualties) {
+                parent.spawn((
+                    Text::new(format!("Losses this run: {}", casualties.summary())),
+                    TextFont {
+                        font: game_font.0.clone(),
+                        font_size: 28.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.9, 0.3, 0.3)),
+                ));
+            }
+
+            // Insurance pays out on a defeat (risk.rs banks the goop).
+            let insurance = risk.as_ref().and_then(|r| r.insurance_tier());
+            if let Some(tier) = insurance.filter(|_| !is_victory) {
+                parent.spawn((
+                    Text::new(format!(
+                        "Insurance banked {} goop",
+                        tier.payout(goop_earned.0)
+                    )),
+                    TextFont {
+                        font: game_font.0.clone(),
+                        font_size: 28.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.4, 0.9, 0.2)),
+                ));
+            }
+
             if is_victory {
                 parent
                     .spawn(Node {
@@ -337,10 +394,10 @@ fn go_home_button_system(
     }
 }
 
-/// When the player clicks "Venture Further", reposition survivors, spawn new
-/// enemies, and transition back to PreCombat. The UI is auto-despawned by
-/// DespawnOnExit(CombatState::PostCombat), and OnEnter(PreCombat) handles
-/// the countdown timer.
+/// When the player clicks "Venture Further", reposition survivors, scroll the
+/// background, and head to the relic choice (relics.rs), then Camp. The UI is
+/// auto-despawned by DespawnOnExit(CombatState::PostCombat). Leaving camp (camp.rs) applies the
+/// goop multiplier and opens the depth map (depth_map.rs).
 fn venture_further_button_system(
     mut commands: Commands,
     query: Query<&Interaction, (Changed<Interaction>, With<VentureFurtherButton>)>,
@@ -353,9 +410,6 @@ fn venture_further_button_system(
     )>,
     backgrounds: Query<(Entity, &Transform), With<Background>>,
     mut next_state: ResMut<NextState<CombatState>>,
-    mut combat_level: ResMut<CombatLevel>,
-    mut goop_earned: ResMut<GoopEarned>,
-    mut depth_query: Query<&mut Text, With<DepthText>>,
 ) {
     let mut clicked = false;
     for interaction in &query {
@@ -398,21 +452,16 @@ fn venture_further_button_system(
             .insert((TargetTransform(target), Speed(60.0)));
     }
 
-    goop_earned.0 = (goop_earned.0 as f32 * VENTURE_MULTIPLIER).ceil() as u32;
-    combat_level.0 += 1;
-    setup_slime_spawn(&mut commands, None, create_enemy_army(combat_level.0));
-
-    for mut text in &mut depth_query {
-        **text = format!("Depth: {}", combat_level.0);
-    }
-
-    next_state.set(CombatState::PreCombat);
+    next_state.set(CombatState::RelicChoice);
 }
 
 fn button_hint_system(
     venture_query: Query<&Interaction, With<VentureFurtherButton>>,
     home_query: Query<&Interaction, With<GoHomeButton>>,
     goop_earned: Res<GoopEarned>,
+    combat_level: Res<CombatLevel>,
+    risk: Option<Res<RunRisk>>,
+    win_odds: Option<Res<WinOdds>>,
     mut hint_query: Query<(&mut Text, &mut TextColor), With<ButtonHintText>>,
     mut bg_query: Query<&mut BackgroundColor, With<ButtonHintBg>>,
 ) {
@@ -420,8 +469,15 @@ fn button_hint_system(
 
     for interaction in &venture_query {
         if *interaction == Interaction::Hovered || *interaction == Interaction::Pressed {
-            let projected = (goop_earned.0 as f32 * VENTURE_MULTIPLIER).ceil() as u32;
-            hint = Some(format!("goop x{} -> {}", VENTURE_MULTIPLIER, projected));
+            // Resting at camp would skip this multiplier and reset the streak.
+            let streak = risk.as_ref().map_or(0, |r| r.streak);
+            let next_depth = combat_level.0 + 1;
+            let mut msg = venture_quote(next_depth, streak).describe(next_depth, goop_earned.0);
+            // WinOdds is absent when the player hid the odds in settings.
+            if let Some(odds) = &win_odds {
+                msg = format!("{msg} | {}", odds.label());
+            }
+            hint = Some(msg);
         }
     }
 
@@ -0,0 +1,476 @@
+// Hazard zones: timed patches of the arena that do something to whoever
+// stands in them.
+//
+// Every zone is a Hazard entity with a circle showing its reach and an
+// animated sprite on top. A zone lives in three stages:
+//   1. Telegraph: the spawn animation plays and nothing happens yet, so units
+//      get a moment to see it coming.
+//   2. Active: for `duration` seconds it applies its effects —
+//        - through movement: footing_system gives units inside a Footing
+//          (steering.rs), which integrate_system uses to slow them down and
+//          cut their grip so they slide;
+//        - through health: every `tick` seconds it deals `damage` to units
+//          inside, through the damage pipeline (damage.rs) like any hit.
+//   3. Gone: the zone despawns, leaving its despawn animation behind if the
+//      kind has one.
+//
+// A zone made by a team (a wizard's spell, a boss phase) only affects the
+// other team. Environmental zones, rolled onto deeper enemy waves, affect
+// everyone and keep appearing at random spots throughout the fight.
+
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::affixes::Chilled;
+use crate::animation::{AnimationState, AnimationType};
+use crate::combat::{AttackEffect, FloatingText, KnownAttacks};
+use crate::damage::{DamageQueue, PendingHit};
+use crate::health::{Dying, Health};
+use crate::movement::TargetEntity;
+use crate::obstacles::{line_of_sight, Obstacle};
+use crate::pick_target::Team;
+use crate::status::CanAttack;
+use crate::steering::Footing;
+use crate::{ArenaBounds, CombatState, GameFont, GameState};
+
+pub struct HazardsPlugin;
+
+impl Plugin for HazardsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WaveHazards>()
+            .add_systems(
+                Update,
+                (wave_hazard_system, hazard_ability_system, hazard_system)
+                    .chain()
+                    .run_if(in_state(CombatState::DuringCombat)),
+            )
+            .add_systems(
+                Update,
+                (
+                    hazard_visuals_system,
+                    footing_system,
+                    hazard_vfx_cleanup_system,
+                )
+                    .run_if(in_state(GameState::Combat)),
+            );
+    }
+}
+
+/// Seconds between environmental zones in a wave that has them.
+const WAVE_HAZARD_INTERVAL: f32 = 6.0;
+/// Environmental zones keep this far inside the arena edge.
+const WAVE_HAZARD_MARGIN: f32 = 120.0;
+/// Hazard sprites are 32x32.
+const HAZARD_SPRITE_SIZE: f32 = 32.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HazardKind {
+    IcyPath,
+    FrozenRain,
+}
+
+pub struct HazardDef {
+    pub kind: HazardKind,
+    pub name: &'static str,
+    pub radius: f32,
+    /// Seconds between the zone appearing and it taking effect.
+    pub telegraph: f32,
+    /// Seconds it stays active after the telegraph.
+    pub duration: f32,
+    /// Damage per tick to each unit inside. 0 for zones that only hinder.
+    pub damage: i32,
+    pub tick: f32,
+    /// Footing inside the zone.
+    pub speed: f32,
+    pub grip: f32,
+    pub color: Color,
+    pub telegraph_animation: AnimationType,
+    pub active_animation: AnimationType,
+    pub despawn_animation: Option<AnimationType>,
+    /// Seconds between casts, for units that have this as an ability.
+    pub cooldown: f32,
+}
+
+pub const HAZARDS: &[HazardDef] = &[
+    HazardDef {
+        kind: HazardKind::IcyPath,
+        name: "Icy Path",
+        radius: 90.0,
+        telegraph: 0.5,
+        duration: 6.0,
+        damage: 0,
+        tick: 1.0,
+        speed: 0.6,
+        grip: 0.15,
+        color: Color::srgba(0.7, 0.9, 1.0, 0.25),
+        telegraph_animation: AnimationType::IcyPathSpawn,
+        active_animation: AnimationType::IcyPathIdle,
+        despawn_animation: Some(AnimationType::IcyPathDespawn),
+        cooldown: 9.0,
+    },
+    HazardDef {
+        kind: HazardKind::FrozenRain,
+        name: "Frozen Rain",
+        radius: 80.0,
+        telegraph: 1.2,
+        duration: 5.0,
+        damage: 1,
+        tick: 1.0,
+        speed: 1.0,
+        grip: 1.0,
+        color: Color::srgba(0.5, 0.8, 1.0, 0.3),
+        telegraph_animation: AnimationType::FrozenRainSpawn,
+        active_animation: AnimationType::FrozenRainFall,
+        despawn_animation: None,
+        cooldown: 7.0,
+    },
+];
+
+impl HazardKind {
+    pub const ALL: [HazardKind; 2] = [HazardKind::IcyPath, HazardKind::FrozenRain];
+
+    pub fn def(self) -> &'static HazardDef {
+        HAZARDS
+            .iter()
+            .find(|d| d.kind == self)
+            .expect("every hazard has a HAZARDS entry")
+    }
+}
+
+/// A zone on the arena floor. Spawn with spawn_hazard.
+#[derive(Component)]
+pub struct Hazard {
+    pub kind: HazardKind,
+    /// The team that made it; it only affects the other side. None for
+    /// environmental zones, which affect everyone.
+    pub team: Option<Team>,
+    pub radius: f32,
+    pub damage: i32,
+    pub telegraph: Timer,
+    pub lifetime: Timer,
+    pub tick: Timer,
+}
+
+impl Hazard {
+    /// A zone with its kind's default stats.
+    pub fn new(kind: HazardKind, team: Option<Team>) -> Self {
+        let def = kind.def();
+        Self {
+            kind,
+            team,
+            radius: def.radius,
+            damage: def.damage,
+            telegraph: Timer::from_seconds(def.telegraph, TimerMode::Once),
+            lifetime: Timer::from_seconds(def.duration, TimerMode::Once),
+            tick: Timer::from_seconds(def.tick, TimerMode::Repeating),
+        }
+    }
+
+    fn active(&self) -> bool {
+        self.telegraph.is_finished()
+    }
+
+    fn affects(&self, team: Team) -> bool {
+        self.team != Some(team)
+    }
+}
+
+/// Lets a unit drop a zone on its target every `cooldown` seconds. Wizards
+/// cast Frozen Rain; the Shielded Caster lays Icy Paths.
+#[derive(Component)]
+pub struct HazardAbility {
+    pub kind: HazardKind,
+    pub cooldown: Timer,
+}
+
+impl HazardAbility {
+    pub fn new(kind: HazardKind) -> Self {
+        Self {
+            kind,
+            cooldown: Timer::from_seconds(kind.def().cooldown, TimerMode::Once),
+        }
+    }
+}
+
+/// Resource — the environmental zones the current wave brings, and when the
+/// next one appears. setup_slime_spawn replaces it with every new wave, so a
+/// wave without hazards clears the last one's.
+#[derive(Resource)]
+pub struct WaveHazards {
+    pub kinds: Vec<HazardKind>,
+    pub timer: Timer,
+}
+
+impl Default for WaveHazards {
+    fn default() -> Self {
+        Self {
+            kinds: Vec::new(),
+            timer: Timer::from_seconds(WAVE_HAZARD_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+/// The zone's sprite, a child of the Hazard.
+#[derive(Component)]
+struct HazardSprite;
+
+/// A despawn animation left behind by a finished zone.
+#[derive(Component)]
+struct HazardVfx;
+
+/// Spawns `hazard` at `position`. Its circle and sprite are added by
+/// hazard_visuals_system.
+pub fn spawn_hazard(commands: &mut Commands, hazard: Hazard, position: Vec2) -> Entity {
+    commands
+        .spawn((
+            hazard,
+            Transform::from_xyz(position.x, position.y, -5.0),
+            Visibility::default(),
+            DespawnOnExit(CombatState::DuringCombat),
+        ))
+        .id()
+}
+
+fn sprite_scale(radius: f32) -> Vec3 {
+    Vec3::splat(radius * 2.0 / HAZARD_SPRITE_SIZE)
+}
+
+/// Gives new zones a circle showing their reach and their telegraph sprite.
+fn hazard_visuals_system(
+    mut commands: Commands,
+    hazards: Query<(Entity, &Hazard), Added<Hazard>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (entity, hazard) in &hazards {
+        let def = hazard.kind.def();
+        commands
+            .entity(entity)
+            .insert((
+                Mesh2d(meshes.add(Circle::new(hazard.radius))),
+                MeshMaterial2d(materials.add(def.color)),
+            ))
+            .with_child((
+                HazardSprite,
+                def.telegraph_animation,
+                Transform::from_scale(sprite_scale(hazard.radius)),
+            ));
+    }
+}
+
+/// Drops one of the wave's environmental zones somewhere on the field every
+/// WAVE_HAZARD_INTERVAL.
+fn wave_hazard_system(
+    mut commands: Commands,
+    mut wave_hazards: ResMut<WaveHazards>,
+    arena: Res<ArenaBounds>,
+    time: Res<Time>,
+) {
+    if wave_hazards.kinds.is_empty() {
+        return;
+    }
+    wave_hazards.timer.tick(time.delta());
+    if !wave_hazards.timer.just_finished() {
+        return;
+    }
+
+    let mut rng = rand::thread_rng();
+    let Some(&kind) = wave_hazards.kinds.choose(&mut rng) else {
+        return;
+    };
+    let half_w = arena.half_width() - WAVE_HAZARD_MARGIN;
+    let half_h = arena.half_height() - WAVE_HAZARD_MARGIN;
+    let position = Vec2::new(
+        rng.gen_range(-half_w..half_w),
+        rng.gen_range(-half_h..half_h),
+    );
+    spawn_hazard(&mut commands, Hazard::new(kind, None), position);
+}
+
+/// Casts each unit's zone on its target once the cooldown is up and the
+/// target is within reach and in sight. Units only cast when they could
+/// also start an attack, so stunned or merging casters hold off.
+fn hazard_ability_system(
+    mut commands: Commands,
+    mut casters: Query<
+        (
+            &mut HazardAbility,
+            &Team,
+            &GlobalTransform,
+            &TargetEntity,
+            &KnownAttacks,
+        ),
+        With<CanAttack>,
+    >,
+    targets: Query<&GlobalTransform, With<Health>>,
+    obstacles: Query<(&GlobalTransform, &Obstacle)>,
+    game_font: Res<GameFont>,
+    time: Res<Time>,
+) {
+    let blockers: Vec<_> = obstacles
+        .iter()
+        .map(|(transform, obstacle)| obstacle.blocker(transform.translation()))
+        .collect();
+
+    for (mut ability, team, transform, target, attacks) in &mut casters {
+        ability.cooldown.tick(time.delta());
+        if !ability.cooldown.is_finished() {
+            continue;
+        }
+        let Ok(target_transform) = targets.get(target.0) else {
+            continue;
+        };
+
+        let from = transform.translation().truncate();
+        let to = target_transform.translation().truncate();
+        let reach = attacks.0.iter().map(|a| a.range).fold(0.0, f32::max);
+        if from.distance(to) > reach || !line_of_sight(from, to, &blockers) {
+            continue;
+        }
+
+        ability.cooldown.reset();
+        spawn_hazard(&mut commands, Hazard::new(ability.kind, Some(*team)), to);
+        commands.spawn((
+            FloatingText(Timer::from_seconds(0.8, TimerMode::Once)),
+            Text2d::new(ability.kind.def().name),
+            TextFont {
+                font: game_font.0.clone(),
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Transform::from_xyz(from.x, from.y + 50.0, 10.0),
+        ));
+    }
+}
+
+/// Runs each zone through its telegraph, active time and expiry, dealing
+/// its damage once per tick while active.
+fn hazard_system(
+    mut commands: Commands,
+    mut hazards: Query<(Entity, &mut Hazard, &Transform, &Children)>,
+    mut sprites: Query<&mut AnimationType, With<HazardSprite>>,
+    units: Query<(Entity, &Team, &GlobalTransform, &Health), Without<Dying>>,
+    mut damage_queue: ResMut<DamageQueue>,
+    time: Res<Time>,
+) {
+    for (hazard_entity, mut hazard, transform, children) in &mut hazards {
+        let def = hazard.kind.def();
+
+        if !hazard.active() {
+            hazard.telegraph.tick(time.delta());
+            if hazard.active() {
+                for child in children.iter() {
+                    if let Ok(mut animation) = sprites.get_mut(child) {
+                        *animation = def.active_animation;
+                    }
+                }
+            }
+            continue;
+        }
+
+        hazard.lifetime.tick(time.delta());
+        if hazard.lifetime.is_finished() {
+            if let Some(animation) = def.despawn_animation {
+                commands.spawn((
+                    HazardVfx,
+                    animation,
+                    Transform::from_translation(transform.translation)
+                        .with_scale(sprite_scale(hazard.radius)),
+                    DespawnOnExit(GameState::Combat),
+                ));
+            }
+            commands.entity(hazard_entity).despawn();
+            continue;
+        }
+
+        if hazard.damage <= 0 {
+            continue;
+        }
+        hazard.tick.tick(time.delta());
+        if !hazard.tick.just_finished() {
+            continue;
+        }
+
+        let center = transform.translation.truncate();
+        for (entity, team, unit_transform, health) in &units {
+            if !hazard.affects(*team) || health.0 <= 0 {
+                continue;
+            }
+            if unit_transform.translation().truncate().distance(center) <= hazard.radius {
+                damage_queue.push(PendingHit::new(
+                    None,
+                    entity,
+                    AttackEffect {
+                        damage: hazard.damage,
+                        ..default()
+                    },
+                ));
+            }
+        }
+    }
+}
+
+/// Gives units standing in active zones the worst footing among them, and
+/// takes it away once they step out (or the zones are gone). A Chilled unit
+/// (a frost aura, affixes.rs) has its top speed scaled on top of that.
+fn footing_system(
+    mut commands: Commands,
+    hazards: Query<(&Hazard, &Transform)>,
+    units: Query<
+        (
+            Entity,
+            &Team,
+            &GlobalTransform,
+            Option<&Footing>,
+            Option<&Chilled>,
+        ),
+        With<Health>,
+    >,
+) {
+    for (entity, team, transform, current, chilled) in &units {
+        let position = transform.translation().truncate();
+        let mut footing = hazards
+            .iter()
+            .filter(|(hazard, _)| hazard.active() && hazard.affects(*team))
+            .filter(|(hazard, t)| t.translation.truncate().distance(position) <= hazard.radius)
+            .map(|(hazard, _)| hazard.kind.def())
+            .filter(|def| def.speed < 1.0 || def.grip < 1.0)
+            .fold(None, |worst: Option<Footing>, def| {
+                Some(Footing {
+                    speed: worst.map_or(def.speed, |w| w.speed.min(def.speed)),
+                    grip: worst.map_or(def.grip, |w| w.grip.min(def.grip)),
+                })
+            });
+        if let Some(chilled) = chilled {
+            footing
+                .get_or_insert(Footing {
+                    speed: 1.0,
+                    grip: 1.0,
+                })
+                .speed *= chilled.0;
+        }
+
+        match (footing, current) {
+            (Some(footing), _) => {
+                commands.entity(entity).insert(footing);
+            }
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<Footing>();
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+fn hazard_vfx_cleanup_system(
+    mut commands: Commands,
+    vfx: Query<(Entity, &AnimationState), With<HazardVfx>>,
+) {
+    for (entity, anim_state) in &vfx {
+        if anim_state.finished {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -7,7 +7,9 @@ use crate::{
     animation::{AnimationState, AnimationType},
     audio::GameAudio,
     movement::{Speed, TargetEntity},
+    pick_target::Team,
     shaders_lite::DamageTint,
+    splitting::{queue_split, SplitsOnDeath},
     GameState,
 };
 
@@ -120,12 +122,30 @@ pub fn when_starts_dying_system(
     }
 }
 
+/// Despawns slimes whose death animation has finished. Slimes with
+/// SplitsOnDeath leave a PendingSplit behind (see splitting.rs).
 pub fn when_finishes_dying_system(
     mut commands: Commands,
-    query: Query<(Entity, &AnimationState), (With<Dying>, With<AnimationState>)>,
+    query: Query<
+        (
+            Entity,
+            &AnimationState,
+            Option<(&SplitsOnDeath, &Team, &Transform, &MaxHealth)>,
+        ),
+        (With<Dying>, With<AnimationState>),
+    >,
 ) {
-    for (entity, animation_state) in query.iter() {
+    for (entity, animation_state, split) in query.iter() {
         if animation_state.finished {
+            if let Some((split, team, transform, max_health)) = split {
+                queue_split(
+                    &mut commands,
+                    *split,
+                    *team,
+                    transform.translation,
+                    max_health.0,
+                );
+            }
             commands.entity(entity).despawn();
         }
     }
@@ -168,6 +188,9 @@ fn update_health_bars(
 #[derive(Event)]
 pub struct DamagedEvent {
     pub entity: Entity,
+    /// Whoever dealt the damage, if anyone (hazards have no source).
+    pub source: Option<Entity>,
+    pub amount: i32,
 }
 
 pub fn on_damaged_event(
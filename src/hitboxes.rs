@@ -0,0 +1,293 @@
+// Hitboxes and hurtboxes: what a swing actually has to touch to land.
+//
+// Every animation a unit can be in has a hurtbox (HURTBOXES), the part of
+// the sprite that can be hit. Every attack carries a hitbox, the area it
+// hits. Both are boxes in sprite pixels, drawn facing right, and both grow
+// with the entity's scale, so a scale-2 enemy slime is twice as easy to hit
+// and reaches twice as far as a scale-1 player slime.
+//
+// When an attack starts, pick_attack_system records its aim: the centre of
+// the target's hurtbox at that moment. On the hit frame,
+// hit_frame_check_system builds the hitbox and lands the hit only if it
+// overlaps the target's current hurtbox:
+//   - Swing hitboxes sit `reach` in front of the attacker's own hurtbox,
+//     turned toward the aim. A target that has stepped out of the arc is
+//     missed.
+//   - AimPoint hitboxes (spells) land on the aim itself, so a slow cast can
+//     be walked out of.
+//
+// Press F4 during a fight to see them: hurtboxes in green, hitboxes of
+// attacks in progress in yellow, turning red once their hit frame has
+// passed.
+
+use bevy::prelude::*;
+
+use crate::animation::AnimationType;
+use crate::combat::ActiveAttack;
+use crate::health::Health;
+use crate::obstacles::RANGED_ATTACK_RANGE;
+use crate::GameState;
+
+pub struct HitboxesPlugin;
+
+impl Plugin for HitboxesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HitboxOverlay>().add_systems(
+            Update,
+            (toggle_hitbox_overlay_system, hitbox_overlay_system)
+                .chain()
+                .run_if(in_state(GameState::Combat)),
+        );
+    }
+}
+
+/// Whether the F4 hitbox overlay is showing.
+#[derive(Resource, Default)]
+pub struct HitboxOverlay(pub bool);
+
+const HURTBOX_COLOR: Color = Color::srgba(0.3, 1.0, 0.4, 0.8);
+const HITBOX_COLOR: Color = Color::srgba(1.0, 0.9, 0.2, 0.8);
+const SPENT_HITBOX_COLOR: Color = Color::srgba(1.0, 0.2, 0.2, 0.8);
+
+/// Where an attack's hitbox is placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HitboxAnchor {
+    /// In front of the attacker, turned toward the aim.
+    Swing,
+    /// Centred on the aim.
+    AimPoint,
+}
+
+/// The area an attack hits, in the attacker's sprite pixels.
+#[derive(Clone, Copy, Debug)]
+pub struct Hitbox {
+    pub anchor: HitboxAnchor,
+    /// How far in front of the attacker's hurtbox centre the box sits.
+    /// Ignored for AimPoint.
+    pub reach: f32,
+    /// Half the box's length along the aim, and half its width across it.
+    pub half_size: Vec2,
+}
+
+/// A small slime's lunge: just past the end of its nose.
+pub const SLIME_BITE: Hitbox = Hitbox {
+    anchor: HitboxAnchor::Swing,
+    reach: 26.0,
+    half_size: Vec2::new(22.0, 18.0),
+};
+
+/// A merged slime's body slam: longer and wider than a bite.
+pub const SLIME_SLAM: Hitbox = Hitbox {
+    anchor: HitboxAnchor::Swing,
+    reach: 30.0,
+    half_size: Vec2::new(30.0, 22.0),
+};
+
+/// The frozen spear's thrust. The spear sheet is 32px square, so this is
+/// small, but the spear is drawn at 4x.
+pub const SPEAR_THRUST: Hitbox = Hitbox {
+    anchor: HitboxAnchor::Swing,
+    reach: 8.0,
+    half_size: Vec2::new(10.0, 4.0),
+};
+
+/// A spell bursting where the target stood when the cast began.
+pub const SPELL_BURST: Hitbox = Hitbox {
+    anchor: HitboxAnchor::AimPoint,
+    reach: 0.0,
+    half_size: Vec2::new(20.0, 20.0),
+};
+
+/// Merged slimes and bosses slam up close and cast from range.
+pub fn big_slime_hitbox(range: f32) -> Hitbox {
+    if range > RANGED_ATTACK_RANGE {
+        SPELL_BURST
+    } else {
+        SLIME_SLAM
+    }
+}
+
+pub struct HurtboxDef {
+    pub animations: &'static [AnimationType],
+    /// Centre of the box relative to the sprite's centre, facing right.
+    pub offset: Vec2,
+    pub half_size: Vec2,
+}
+
+/// The slime sheets are 60x99 with the slime sitting at the bottom, so the
+/// body is well below the sprite's centre. Big and enemy variants share the
+/// same sheets.
+pub const HURTBOXES: &[HurtboxDef] = &[
+    HurtboxDef {
+        animations: &[
+            AnimationType::SlimeJumpIdle,
+            AnimationType::SlimeMoveSmallJump,
+            AnimationType::SlimeHurt,
+            AnimationType::SlimeDeath,
+            AnimationType::BigSlimeJumpIdle,
+            AnimationType::BigSlimeDeath,
+            AnimationType::EnemySlimeJumpIdle,
+            AnimationType::EnemySlimeMoveSmallJump,
+            AnimationType::EnemySlimeHurt,
+            AnimationType::EnemySlimeDeath,
+            AnimationType::EnemyBigSlimeJumpIdle,
+            AnimationType::EnemyBigSlimeDeath,
+        ],
+        offset: Vec2::new(0.0, -30.0),
+        half_size: Vec2::new(20.0, 16.0),
+    },
+    // Mid-lunge the slime stretches forward.
+    HurtboxDef {
+        animations: &[
+            AnimationType::SlimeAttack,
+            AnimationType::BigSlimeAttack,
+            AnimationType::EnemySlimeAttack,
+            AnimationType::EnemyBigSlimeAttack,
+        ],
+        offset: Vec2::new(4.0, -32.0),
+        half_size: Vec2::new(22.0, 14.0),
+    },
+    // Casting, the wizard rears up.
+    HurtboxDef {
+        animations: &[AnimationType::MageCast, AnimationType::EnemyMageCast],
+        offset: Vec2::new(0.0, -26.0),
+        half_size: Vec2::new(18.0, 20.0),
+    },
+];
+
+/// Anything without a HURTBOXES entry: a box around the sprite's centre.
+const FALLBACK_HURTBOX: HurtboxDef = HurtboxDef {
+    animations: &[],
+    offset: Vec2::ZERO,
+    half_size: Vec2::new(12.0, 12.0),
+};
+
+pub fn hurtbox_def(animation: AnimationType) -> &'static HurtboxDef {
+    HURTBOXES
+        .iter()
+        .find(|d| d.animations.contains(&animation))
+        .unwrap_or(&FALLBACK_HURTBOX)
+}
+
+/// A box in world space. `dir` is the unit direction of its length.
+#[derive(Clone, Copy, Debug)]
+pub struct HitShape {
+    pub center: Vec2,
+    pub half_size: Vec2,
+    pub dir: Vec2,
+}
+
+impl HitShape {
+    /// Half the box's extent when projected onto `axis`.
+    fn extent_along(&self, axis: Vec2) -> f32 {
+        self.dir.dot(axis).abs() * self.half_size.x
+            + self.dir.perp().dot(axis).abs() * self.half_size.y
+    }
+
+    /// Separating axis test: two boxes overlap unless one of their four edge
+    /// directions separates them.
+    pub fn overlaps(&self, other: &HitShape) -> bool {
+        let offset = other.center - self.center;
+        [self.dir, self.dir.perp(), other.dir, other.dir.perp()]
+            .iter()
+            .all(|axis| {
+                offset.dot(*axis).abs() <= self.extent_along(*axis) + other.extent_along(*axis)
+            })
+    }
+}
+
+/// An entity's hurtbox in world space, from its current animation, facing,
+/// and scale.
+pub fn hurtbox(
+    transform: &GlobalTransform,
+    animation: Option<&AnimationType>,
+    sprite: Option<&Sprite>,
+) -> HitShape {
+    let def = animation.map_or(&FALLBACK_HURTBOX, |a| hurtbox_def(*a));
+    let scale = transform.scale().truncate().abs();
+    let facing = if sprite.is_some_and(|s| s.flip_x) {
+        -1.0
+    } else {
+        1.0
+    };
+    HitShape {
+        center: transform.translation().truncate()
+            + Vec2::new(def.offset.x * facing, def.offset.y) * scale,
+        half_size: def.half_size * scale,
+        dir: Vec2::X,
+    }
+}
+
+/// An attack's hitbox in world space, thrown by an attacker whose own
+/// hurtbox is `body`, at `aim`.
+pub fn hitbox(
+    hitbox: &Hitbox,
+    transform: &GlobalTransform,
+    body: &HitShape,
+    aim: Vec2,
+) -> HitShape {
+    let scale = transform.scale().x.abs();
+    let dir = (aim - body.center).try_normalize().unwrap_or(Vec2::X);
+    let center = match hitbox.anchor {
+        HitboxAnchor::Swing => body.center + dir * hitbox.reach * scale,
+        HitboxAnchor::AimPoint => aim,
+    };
+    HitShape {
+        center,
+        half_size: hitbox.half_size * scale,
+        dir,
+    }
+}
+
+fn toggle_hitbox_overlay_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut overlay: ResMut<HitboxOverlay>,
+) {
+    if keyboard.just_pressed(KeyCode::F4) {
+        overlay.0 = !overlay.0;
+    }
+}
+
+fn hitbox_overlay_system(
+    mut gizmos: Gizmos,
+    overlay: Res<HitboxOverlay>,
+    bodies: Query<(&GlobalTransform, Option<&AnimationType>, Option<&Sprite>), With<Health>>,
+    attackers: Query<(
+        &GlobalTransform,
+        &ActiveAttack,
+        Option<&AnimationType>,
+        Option<&Sprite>,
+    )>,
+) {
+    if !overlay.0 {
+        return;
+    }
+
+    let mut draw = |shape: HitShape, color: Color| {
+        gizmos.rect_2d(
+            Isometry2d::new(shape.center, Rot2::from_sin_cos(shape.dir.y, shape.dir.x)),
+            shape.half_size * 2.0,
+            color,
+        );
+    };
+
+    for (transform, animation, sprite) in &bodies {
+        draw(hurtbox(transform, animation, sprite), HURTBOX_COLOR);
+    }
+
+    for (transform, active_attack, animation, sprite) in &attackers {
+        let body = hurtbox(transform, animation, sprite);
+        let shape = hitbox(
+            &active_attack.attack.hitbox,
+            transform,
+            &body,
+            active_attack.aim,
+        );
+        let color = if active_attack.hit_triggered {
+            SPENT_HITBOX_COLOR
+        } else {
+            HITBOX_COLOR
+        };
+        draw(shape, color);
+    }
+}
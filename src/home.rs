@@ -1,9 +1,16 @@
+use std::path::Path;
+
 use bevy::ecs::hierarchy::ChildOf;
 use bevy::ecs::relationship::RelatedSpawnerCommands;
 use bevy::prelude::*;
 
-use crate::save_load::SaveData;
+use crate::armies::{MergePolicy, SlimeType};
+use crate::casino;
+use crate::pick_target::PickTargetStrategy;
+use crate::save_load::{ActiveSaveStorage, SaveData, Settings};
 use crate::screen_fade::{spawn_screen_fade, ScreenFade};
+use crate::share_code::{self, ShareCode};
+use crate::upgrades::{UpgradeStat, UPGRADES};
 use crate::{GameFont, GameState};
 
 pub struct HomePlugin;
@@ -16,9 +23,15 @@ impl Plugin for HomePlugin {
                 (
                     battle_button_system,
                     army_button_system,
+                    upgrade_button_system,
+                    update_upgrade_text_system,
                     update_count_text_system,
                     update_goop_text_system,
                     update_cost_tooltip_system,
+                    share_button_system,
+                    setting_toggle_system,
+                    merge_policy_button_system,
+                    targeting_button_system,
                     button_hover_system,
                 )
                     .run_if(in_state(GameState::Home)),
@@ -29,28 +42,8 @@ impl Plugin for HomePlugin {
 #[derive(Component)]
 struct BattleButton;
 
-#[derive(Clone, Copy)]
-enum SlimeType {
-    Normal,
-    Tank,
-    Wizard,
-}
-
-impl SlimeType {
-    fn base_cost(self) -> u32 {
-        match self {
-            SlimeType::Normal => 1,
-            SlimeType::Tank | SlimeType::Wizard => 10,
-        }
-    }
-}
-
 fn get_count(slime_type: SlimeType, save_data: &SaveData) -> u32 {
-    match slime_type {
-        SlimeType::Normal => save_data.army.normal.count,
-        SlimeType::Tank => save_data.army.tanks.count,
-        SlimeType::Wizard => save_data.army.wizards.count,
-    }
+    save_data.army.count(slime_type)
 }
 
 #[derive(Component)]
@@ -68,6 +61,84 @@ struct ArmyButton {
     delta: i32,
 }
 
+/// Buys one level of a stat upgrade. Lives to the right of its slime row.
+#[derive(Component)]
+struct UpgradeButton(UpgradeStat);
+
+#[derive(Component)]
+struct UpgradeButtonText(UpgradeStat);
+
+/// Cycles a unit type's targeting override. Lives in its slime row.
+#[derive(Component)]
+struct TargetingButton(SlimeType);
+
+#[derive(Component)]
+struct TargetingButtonText(SlimeType);
+
+/// Text colour for a type still on its default targeting.
+const DEFAULT_TARGETING_COLOR: Color = Color::srgb(0.6, 0.7, 0.6);
+
+/// Cycles the army's merge policy. Lives at the start of the merging row.
+#[derive(Component)]
+struct MergePolicyButton;
+
+#[derive(Component)]
+struct MergePolicyText;
+
+/// A boolean option from `Settings` that can be flipped from the Home screen.
+#[derive(Clone, Copy)]
+enum SettingToggle {
+    Casualties,
+    HideOdds,
+}
+
+impl SettingToggle {
+    fn value(self, settings: &Settings) -> bool {
+        match self {
+            SettingToggle::Casualties => settings.casualties,
+            SettingToggle::HideOdds => settings.hide_odds,
+        }
+    }
+
+    fn value_mut(self, settings: &mut Settings) -> &mut bool {
+        match self {
+            SettingToggle::Casualties => &mut settings.casualties,
+            SettingToggle::HideOdds => &mut settings.hide_odds,
+        }
+    }
+
+    fn label(self, settings: &Settings) -> String {
+        let name = match self {
+            SettingToggle::Casualties => "Casualties",
+            SettingToggle::HideOdds => "Hide odds",
+        };
+        let state = if self.value(settings) { "On" } else { "Off" };
+        format!("{name}: {state}")
+    }
+}
+
+#[derive(Component)]
+struct SettingButton(SettingToggle);
+
+#[derive(Component)]
+struct SettingButtonText(SettingToggle);
+
+#[derive(Clone, Copy)]
+enum ShareAction {
+    CopyArmy,
+    CopySave,
+    PasteClipboard,
+    LoadFile,
+}
+
+#[derive(Component)]
+struct ShareButton(ShareAction);
+
+/// One-line feedback under the share buttons ("Army code copied!", or why
+/// an import was rejected).
+#[derive(Component)]
+struct ShareStatusText;
+
 const BG_COLOR: Color = Color::srgb(0.08, 0.18, 0.08);
 const BUTTON_COLOR: Color = Color::srgb(0.15, 0.4, 0.15);
 const BUTTON_HOVER_COLOR: Color = Color::srgb(0.2, 0.55, 0.2);
@@ -137,9 +208,23 @@ fn setup_home(mut commands: Commands, game_font: Res<GameFont>, save_data: Res<S
             ];
 
             for (slime_type, label, count) in rows {
-                spawn_slime_row(root, &font, slime_type, label, count);
+                spawn_slime_row(root, &font, slime_type, label, count, &save_data);
             }
 
+            spawn_merge_row(root, &font, &save_data);
+
+            // Rule toggles
+            root.spawn(Node {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(10.0),
+                ..default()
+            })
+            .with_children(|row| {
+                spawn_setting_button(row, &font, SettingToggle::Casualties, &save_data.settings);
+                spawn_setting_button(row, &font, SettingToggle::HideOdds, &save_data.settings);
+                casino::spawn_casino_button(row, &font);
+            });
+
             // Battle button
             root.spawn((
                 BattleButton,
@@ -165,6 +250,31 @@ fn setup_home(mut commands: Commands, game_font: Res<GameFont>, save_data: Res<S
                     TextColor(Color::WHITE),
                 ));
             });
+
+            // Share codes: trade builds with friends
+            root.spawn(Node {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(10.0),
+                margin: UiRect::top(Val::Px(10.0)),
+                ..default()
+            })
+            .with_children(|row| {
+                spawn_share_button(row, &font, ShareAction::CopyArmy, "Copy army");
+                spawn_share_button(row, &font, ShareAction::CopySave, "Copy save");
+                spawn_share_button(row, &font, ShareAction::PasteClipboard, "Paste code");
+                spawn_share_button(row, &font, ShareAction::LoadFile, "Load file");
+            });
+
+            root.spawn((
+                ShareStatusText,
+                Text::new(""),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 22.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
         });
 }
 
@@ -174,6 +284,7 @@ fn spawn_slime_row(
     slime_type: SlimeType,
     label: &str,
     count: u32,
+    save_data: &SaveData,
 ) {
     parent
         .spawn(Node {
@@ -204,6 +315,158 @@ fn spawn_slime_row(
 
             // [+] button
             spawn_army_button(row, font, slime_type, 1, "+");
+
+            // Targeting override
+            let (label, color) = targeting_label(slime_type, save_data);
+            row.spawn((
+                TargetingButton(slime_type),
+                Button,
+                Node {
+                    width: Val::Px(170.0),
+                    height: Val::Px(50.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(BUTTON_COLOR),
+            ))
+            .with_children(|btn| {
+                btn.spawn((
+                    TargetingButtonText(slime_type),
+                    Text::new(label),
+                    TextFont {
+                        font: font.clone(),
+                        font_size: 22.0,
+                        ..default()
+                    },
+                    TextColor(color),
+                ));
+            });
+
+            // Stat upgrades for this unit type
+            for def in UPGRADES
+                .iter()
+                .filter(|def| def.slime_type == Some(slime_type))
+            {
+                spawn_upgrade_button(row, font, def.stat, save_data);
+            }
+        });
+}
+
+/// "Aim: Backline" — greyed out while the type is on its default.
+fn targeting_label(slime_type: SlimeType, save_data: &SaveData) -> (String, Color) {
+    let targeting = &save_data.army.targeting;
+    let color = if targeting.get(slime_type).is_some() {
+        Color::WHITE
+    } else {
+        DEFAULT_TARGETING_COLOR
+    };
+    let label = format!("Aim: {}", targeting.strategy(slime_type).label());
+    (label, color)
+}
+
+/// Default → every strategy in turn → back to default.
+fn next_targeting(current: Option<PickTargetStrategy>) -> Option<PickTargetStrategy> {
+    let all = PickTargetStrategy::ALL;
+    match current {
+        None => Some(all[0]),
+        Some(strategy) => {
+            let i = all.iter().position(|&s| s == strategy).unwrap_or(0);
+            all.get(i + 1).copied()
+        }
+    }
+}
+
+fn merge_policy_label(policy: MergePolicy) -> String {
+    format!("Merge: {}", policy.label())
+}
+
+/// The merge policy button plus the army-wide merge upgrades, laid out like
+/// a slime row.
+fn spawn_merge_row(
+    parent: &mut RelatedSpawnerCommands<ChildOf>,
+    font: &Handle<Font>,
+    save_data: &SaveData,
+) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(15.0),
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn((
+                MergePolicyButton,
+                Button,
+                Node {
+                    width: Val::Px(330.0),
+                    height: Val::Px(50.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(BUTTON_COLOR),
+            ))
+            .with_children(|btn| {
+                btn.spawn((
+                    MergePolicyText,
+                    Text::new(merge_policy_label(save_data.army.merging.policy)),
+                    TextFont {
+                        font: font.clone(),
+                        font_size: 26.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ));
+            });
+
+            for def in UPGRADES.iter().filter(|def| def.slime_type.is_none()) {
+                spawn_upgrade_button(row, font, def.stat, save_data);
+            }
+        });
+}
+
+fn upgrade_label(stat: UpgradeStat, save_data: &SaveData) -> String {
+    let def = stat.def();
+    format!(
+        "{} {}/{}",
+        def.label,
+        save_data.upgrades.level(stat),
+        def.max_level
+    )
+}
+
+fn spawn_upgrade_button(
+    parent: &mut RelatedSpawnerCommands<ChildOf>,
+    font: &Handle<Font>,
+    stat: UpgradeStat,
+    save_data: &SaveData,
+) {
+    parent
+        .spawn((
+            UpgradeButton(stat),
+            Button,
+            Node {
+                width: Val::Px(130.0),
+                height: Val::Px(50.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+        ))
+        .with_children(|btn| {
+            btn.spawn((
+                UpgradeButtonText(stat),
+                Text::new(upgrade_label(stat, save_data)),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 22.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
         });
 }
 
@@ -240,6 +503,71 @@ fn spawn_army_button(
         });
 }
 
+fn spawn_setting_button(
+    parent: &mut RelatedSpawnerCommands<ChildOf>,
+    font: &Handle<Font>,
+    toggle: SettingToggle,
+    settings: &Settings,
+) {
+    parent
+        .spawn((
+            SettingButton(toggle),
+            Button,
+            Node {
+                width: Val::Px(260.0),
+                height: Val::Px(45.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+        ))
+        .with_children(|btn| {
+            btn.spawn((
+                SettingButtonText(toggle),
+                Text::new(toggle.label(settings)),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+fn spawn_share_button(
+    parent: &mut RelatedSpawnerCommands<ChildOf>,
+    font: &Handle<Font>,
+    action: ShareAction,
+    label: &str,
+) {
+    parent
+        .spawn((
+            ShareButton(action),
+            Button,
+            Node {
+                width: Val::Px(170.0),
+                height: Val::Px(45.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+        ))
+        .with_children(|btn| {
+            btn.spawn((
+                Text::new(label),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 22.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
 fn army_button_system(
     query: Query<(&Interaction, &ArmyButton), Changed<Interaction>>,
     mut save_data: ResMut<SaveData>,
@@ -265,16 +593,45 @@ fn army_button_system(
             save_data.goop += refund;
         }
 
-        let count = match army_btn.slime_type {
-            SlimeType::Normal => &mut save_data.army.normal.count,
-            SlimeType::Tank => &mut save_data.army.tanks.count,
-            SlimeType::Wizard => &mut save_data.army.wizards.count,
-        };
+        let count = save_data.army.count_mut(army_btn.slime_type);
         let new_val = *count as i32 + army_btn.delta;
         *count = new_val.max(0) as u32;
     }
 }
 
+fn upgrade_button_system(
+    query: Query<(&Interaction, &UpgradeButton), Changed<Interaction>>,
+    mut save_data: ResMut<SaveData>,
+) {
+    for (interaction, upgrade_btn) in &query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        // None means the stat is already at its cap.
+        let Some(cost) = save_data.upgrades.next_cost(upgrade_btn.0) else {
+            continue;
+        };
+        if save_data.goop < cost {
+            continue;
+        }
+        save_data.goop -= cost;
+        *save_data.upgrades.level_mut(upgrade_btn.0) += 1;
+    }
+}
+
+fn update_upgrade_text_system(
+    mut query: Query<(&UpgradeButtonText, &mut Text)>,
+    save_data: Res<SaveData>,
+) {
+    if !save_data.is_changed() {
+        return;
+    }
+    for (upgrade_text, mut text) in &mut query {
+        **text = upgrade_label(upgrade_text.0, &save_data);
+    }
+}
+
 fn update_count_text_system(
     mut query: Query<(&SlimeCountText, &mut Text)>,
     save_data: Res<SaveData>,
@@ -320,11 +677,165 @@ fn battle_button_system(
     }
 }
 
+fn setting_toggle_system(
+    query: Query<(&Interaction, &SettingButton), Changed<Interaction>>,
+    mut text_query: Query<(&SettingButtonText, &mut Text)>,
+    mut save_data: ResMut<SaveData>,
+) {
+    for (interaction, setting_btn) in &query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let value = setting_btn.0.value_mut(&mut save_data.settings);
+        *value = !*value;
+    }
+
+    // Refresh labels whenever settings may have changed — this also covers
+    // importing a whole save from a share code.
+    if !save_data.is_changed() {
+        return;
+    }
+    for (label, mut text) in &mut text_query {
+        **text = label.0.label(&save_data.settings);
+    }
+}
+
+fn merge_policy_button_system(
+    query: Query<&Interaction, (Changed<Interaction>, With<MergePolicyButton>)>,
+    mut text_query: Query<&mut Text, With<MergePolicyText>>,
+    mut save_data: ResMut<SaveData>,
+) {
+    for interaction in &query {
+        if *interaction == Interaction::Pressed {
+            let policy = &mut save_data.army.merging.policy;
+            *policy = policy.next();
+        }
+    }
+
+    // Also covers importing an army from a share code.
+    if !save_data.is_changed() {
+        return;
+    }
+    for mut text in &mut text_query {
+        **text = merge_policy_label(save_data.army.merging.policy);
+    }
+}
+
+fn targeting_button_system(
+    query: Query<(&Interaction, &TargetingButton), Changed<Interaction>>,
+    mut text_query: Query<(&TargetingButtonText, &mut Text, &mut TextColor)>,
+    mut save_data: ResMut<SaveData>,
+) {
+    for (interaction, targeting_btn) in &query {
+        if *interaction == Interaction::Pressed {
+            let current = save_data.army.targeting.get_mut(targeting_btn.0);
+            *current = next_targeting(*current);
+        }
+    }
+
+    if !save_data.is_changed() {
+        return;
+    }
+    for (label, mut text, mut color) in &mut text_query {
+        let (new_text, new_color) = targeting_label(label.0, &save_data);
+        **text = new_text;
+        color.0 = new_color;
+    }
+}
+
+const SHARE_OK_COLOR: Color = Color::srgb(0.4, 0.9, 0.2);
+const SHARE_ERROR_COLOR: Color = Color::srgb(0.9, 0.3, 0.3);
+
+fn share_button_system(
+    query: Query<(&Interaction, &ShareButton), Changed<Interaction>>,
+    mut save_data: ResMut<SaveData>,
+    storage: Res<ActiveSaveStorage>,
+    mut status_query: Query<(&mut Text, &mut TextColor), With<ShareStatusText>>,
+) {
+    let code_file = storage.0.share_code_path();
+    for (interaction, share_btn) in &query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let result = match share_btn.0 {
+            ShareAction::CopyArmy => export_code(
+                &share_code::encode_army(&save_data.army),
+                "Army",
+                code_file.as_deref(),
+            ),
+            ShareAction::CopySave => export_code(
+                &share_code::encode_save(&save_data),
+                "Save",
+                code_file.as_deref(),
+            ),
+            ShareAction::PasteClipboard => share_code::read_clipboard()
+                .map_err(|e| format!("Couldn't read clipboard: {e}"))
+                .and_then(|code| import_code(&code, &mut save_data)),
+            ShareAction::LoadFile => match &code_file {
+                Some(path) => share_code::read_code_file(path)
+                    .map_err(|e| format!("Couldn't read {}: {e}", path.display()))
+                    .and_then(|code| import_code(&code, &mut save_data)),
+                None => Err("No code file while saving is off".to_string()),
+            },
+        };
+
+        for (mut text, mut color) in &mut status_query {
+            match &result {
+                Ok(message) => {
+                    **text = message.clone();
+                    *color = TextColor(SHARE_OK_COLOR);
+                }
+                Err(message) => {
+                    **text = message.clone();
+                    *color = TextColor(SHARE_ERROR_COLOR);
+                }
+            }
+        }
+    }
+}
+
+/// Puts the code on the clipboard. Only if that fails is it written to
+/// `code_file` instead, so it can be sent as a file — and only when the save
+/// backend allows writing one (not under --kiosk or --no-save).
+fn export_code(code: &str, label: &str, code_file: Option<&Path>) -> Result<String, String> {
+    let copy_error = match share_code::copy_to_clipboard(code) {
+        Ok(()) => return Ok(format!("{label} code copied to clipboard")),
+        Err(e) => e,
+    };
+    let Some(path) = code_file else {
+        return Err(format!("Couldn't copy code: {copy_error}"));
+    };
+    share_code::write_code_file(path, code)
+        .map(|()| format!("{label} code saved to {}", path.display()))
+        .map_err(|_| format!("Couldn't export code: {copy_error}"))
+}
+
+/// Decodes and applies a share code. An army code replaces just the army;
+/// a save code replaces the whole save, goop included.
+fn import_code(code: &str, save_data: &mut SaveData) -> Result<String, String> {
+    match share_code::decode(code).map_err(|e| e.to_string())? {
+        ShareCode::Army(army) => {
+            let summary = format!(
+                "Imported army: {} normal, {} tank, {} wizard",
+                army.normal.count, army.tanks.count, army.wizards.count
+            );
+            save_data.army = army;
+            Ok(summary)
+        }
+        ShareCode::Save(imported) => {
+            *save_data = imported;
+            Ok("Imported save".to_string())
+        }
+    }
+}
+
 const COST_COLOR: Color = Color::srgb(0.9, 0.3, 0.3);
 const REFUND_COLOR: Color = Color::srgb(0.3, 0.9, 0.3);
 
 fn update_cost_tooltip_system(
     army_query: Query<(&Interaction, &ArmyButton)>,
+    upgrade_query: Query<(&Interaction, &UpgradeButton)>,
     mut tooltip_query: Query<(&mut TextSpan, &mut TextColor), With<CostTooltip>>,
     save_data: Res<SaveData>,
 ) {
@@ -356,6 +867,28 @@ fn update_cost_tooltip_system(
         break;
     }
 
+    // Upgrades show the exact price and what the next level brings,
+    // e.g. " -23 (+3 HP)", or "MAX" once capped.
+    for (interaction, upgrade_btn) in &upgrade_query {
+        if found_hover || *interaction != Interaction::Hovered {
+            continue;
+        }
+        found_hover = true;
+
+        let stat = upgrade_btn.0;
+        let level = save_data.upgrades.level(stat);
+        match save_data.upgrades.next_cost(stat) {
+            Some(cost) => {
+                **span = format!(" -{cost} ({})", stat.describe_bonus(level + 1));
+                *color = TextColor(COST_COLOR);
+            }
+            None => {
+                **span = " MAX".to_string();
+                *color = TextColor(Color::WHITE);
+            }
+        }
+    }
+
     if !found_hover {
         **span = String::new();
     }
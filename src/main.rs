@@ -20,6 +20,9 @@ pub enum GameState {
 /// - PreCombat: countdown timer, slimes are Inert
 /// - DuringCombat: systems run, slimes fight
 /// - PostCombat: result UI shown, player decides next action
+/// - RelicChoice: after "Risk deeper" — pick one of three relics for the run
+/// - Camp: between depths after "Risk deeper" — heal, rest, or dismiss survivors
+/// - Map: pick the next depth's node on the branching depth map
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, SubStates)]
 #[source(GameState = GameState::Combat)]
 #[states(scoped_entities)]
@@ -28,6 +31,9 @@ pub enum CombatState {
     PreCombat,
     DuringCombat,
     PostCombat,
+    RelicChoice,
+    Camp,
+    Map,
 }
 
 #[derive(Resource)]
@@ -119,6 +125,25 @@ fn main() {
         home::HomePlugin,
         status::StatusPlugin,
         screen_fade::ScreenFadePlugin,
+        casualties::CasualtiesPlugin,
+        camp::CampPlugin,
+        odds::OddsPlugin,
+        side_bets::SideBetsPlugin,
+    ))
+    // Plugin tuples max out at 15 entries, so later plugins go in another group.
+    .add_plugins((
+        casino::CasinoPlugin,
+        risk::RiskPlugin,
+        relics::RelicsPlugin,
+        depth_map::DepthMapPlugin,
+        affixes::AffixesPlugin,
+        bosses::BossesPlugin,
+        splitting::SplittingPlugin,
+        threat::ThreatPlugin,
+        obstacles::ObstaclesPlugin,
+        hazards::HazardsPlugin,
+        hitboxes::HitboxesPlugin,
+        damage::DamagePlugin,
     ))
     // init_state must come AFTER add_plugins(DefaultPlugins) because DefaultPlugins
     // includes StatesPlugin, which sets up the StateTransition schedule that
@@ -179,22 +204,43 @@ fn kill_random_on_spacebar(keyboard: Res<ButtonInput<KeyCode>>, mut query: Query
     }
 }
 
+mod affixes;
 mod animation;
 mod armies;
 mod audio;
+mod bosses;
+mod camp;
+mod casino;
+mod casualties;
 mod combat;
+mod damage;
+mod depth_map;
 mod end_round;
+mod hazards;
 mod health;
+mod hitboxes;
 mod home;
+mod merge_recipes;
 mod movement;
+mod obstacles;
+mod odds;
 mod pick_target;
+mod positioning;
+mod relics;
 mod render;
+mod risk;
 mod save_load;
 mod setup_round;
 mod shaders_lite;
+mod share_code;
+mod side_bets;
 mod spawn_slimes;
 mod special_abilities;
+mod splitting;
 mod sprite_modifications;
 mod screen_fade;
 mod status;
+mod steering;
+mod threat;
+mod upgrades;
 mod utils;
@@ -0,0 +1,316 @@
+// Merge recipes: what two slimes turn into when they merge.
+//
+// check_merge_system only pairs slimes that have a recipe here, and
+// execute_merge_system looks the recipe up again to build the result. The
+// result's stats aren't fixed — they come from the two ingredients:
+//   - HP:     sum of both max HPs × hp_multiplier
+//   - damage: sum of both attack damages × damage_multiplier
+//   - range:  the recipe's range. Caster recipes keep the longer of the two
+//             ingredient ranges if that's further.
+//   - block:  the better of the two block chances + block_bonus
+//   - stun:   the better of the two stun chances
+//
+// The Shielded Caster keeps the wizard's range, so it positions like one,
+// and lays Icy Paths under its targets.
+//
+// Every merge result carries a MergeKind. Tier-2 results can merge again into
+// a tier-3 Giant; Giants are the end of the line.
+
+use bevy::prelude::*;
+
+use crate::animation::AnimationType;
+use crate::armies::SlimeType;
+use crate::combat::{Attack, AttackEffect, BlockChance, KnownAttacks, Shield};
+use crate::hazards::{HazardAbility, HazardKind};
+use crate::health::{Health, MaxHealth};
+use crate::hitboxes::big_slime_hitbox;
+use crate::movement::Speed;
+use crate::pick_target::Team;
+use crate::positioning::Positioning;
+use crate::spawn_slimes::spawn_merged_slime;
+use crate::steering::Mass;
+
+/// Highest tier a merge can produce. Slimes at this tier never merge.
+pub const MAX_MERGE_TIER: u32 = 3;
+
+/// What a merged slime is. Lives on every merge result (and on pre-made
+/// merged slimes in enemy waves, which are Blobs).
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeKind {
+    Blob,
+    Fortress,
+    ShieldedCaster,
+    Giant,
+}
+
+pub struct MergeDef {
+    pub kind: MergeKind,
+    pub name: &'static str,
+    pub tier: u32,
+    pub hp_multiplier: f32,
+    pub damage_multiplier: f32,
+    pub speed: f32,
+    /// Attack range. For Caster recipes it's a minimum, and a longer-ranged
+    /// ingredient raises it; melee results always slam from this range.
+    pub range: f32,
+    pub scale: f32,
+    /// How hard the result is to knock around (see steering.rs Mass).
+    pub mass: f32,
+    /// Added to the best ingredient block chance.
+    pub block_bonus: f32,
+    /// If Some, the result's attack splashes like a wizard's spell.
+    pub splash: Option<f32>,
+    pub positioning: Positioning,
+    /// Hazard zone the result can cast on its target (hazards.rs).
+    pub ability: Option<HazardKind>,
+}
+
+pub const MERGES: &[MergeDef] = &[
+    // Two 5-HP, 1-damage normals give the classic 40-HP, 8-damage blob.
+    MergeDef {
+        kind: MergeKind::Blob,
+        name: "Blob",
+        tier: 2,
+        hp_multiplier: 4.0,
+        damage_multiplier: 4.0,
+        speed: 125.0,
+        range: 100.0,
+        scale: 2.0,
+        mass: 3.0,
+        block_bonus: 0.0,
+        splash: None,
+        positioning: Positioning::Melee,
+        ability: None,
+    },
+    MergeDef {
+        kind: MergeKind::Fortress,
+        name: "Fortress",
+        tier: 2,
+        hp_multiplier: 3.0,
+        damage_multiplier: 2.0,
+        speed: 90.0,
+        range: 100.0,
+        scale: 2.2,
+        mass: 4.0,
+        block_bonus: 0.2,
+        splash: None,
+        positioning: Positioning::Melee,
+        ability: None,
+    },
+    MergeDef {
+        kind: MergeKind::ShieldedCaster,
+        name: "Shielded Caster",
+        tier: 2,
+        hp_multiplier: 3.0,
+        damage_multiplier: 2.0,
+        speed: 110.0,
+        range: 100.0,
+        scale: 2.0,
+        mass: 2.5,
+        block_bonus: 0.0,
+        splash: Some(120.0),
+        positioning: Positioning::Caster,
+        ability: Some(HazardKind::IcyPath),
+    },
+    MergeDef {
+        kind: MergeKind::Giant,
+        name: "Giant",
+        tier: 3,
+        hp_multiplier: 1.5,
+        damage_multiplier: 1.5,
+        speed: 100.0,
+        range: 130.0,
+        scale: 3.0,
+        mass: 5.0,
+        block_bonus: 0.0,
+        splash: Some(80.0),
+        positioning: Positioning::Melee,
+        ability: None,
+    },
+];
+
+impl MergeKind {
+    pub fn def(self) -> &'static MergeDef {
+        MERGES
+            .iter()
+            .find(|d| d.kind == self)
+            .expect("every merge kind has a MERGES entry")
+    }
+}
+
+/// What a slime counts as on the input side of a recipe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeInput {
+    Normal,
+    Tank,
+    Wizard,
+    /// Any tier-2 merge result.
+    Merged,
+}
+
+impl MergeInput {
+    /// None for slimes that can't merge at all: max-tier results and
+    /// merged slimes without a MergeKind (bosses).
+    pub fn of(slime_type: Option<&SlimeType>, merge_kind: Option<&MergeKind>) -> Option<Self> {
+        match (slime_type, merge_kind) {
+            (_, Some(kind)) if kind.def().tier >= MAX_MERGE_TIER => None,
+            (_, Some(_)) => Some(MergeInput::Merged),
+            (Some(SlimeType::Normal), None) => Some(MergeInput::Normal),
+            (Some(SlimeType::Tank), None) => Some(MergeInput::Tank),
+            (Some(SlimeType::Wizard), None) => Some(MergeInput::Wizard),
+            (None, None) => None,
+        }
+    }
+}
+
+pub struct MergeRecipe {
+    /// Order doesn't matter: tank + wizard and wizard + tank both match.
+    pub inputs: [MergeInput; 2],
+    pub result: MergeKind,
+}
+
+pub const RECIPES: &[MergeRecipe] = &[
+    MergeRecipe {
+        inputs: [MergeInput::Normal, MergeInput::Normal],
+        result: MergeKind::Blob,
+    },
+    MergeRecipe {
+        inputs: [MergeInput::Normal, MergeInput::Tank],
+        result: MergeKind::Blob,
+    },
+    MergeRecipe {
+        inputs: [MergeInput::Normal, MergeInput::Wizard],
+        result: MergeKind::Blob,
+    },
+    MergeRecipe {
+        inputs: [MergeInput::Wizard, MergeInput::Wizard],
+        result: MergeKind::Blob,
+    },
+    MergeRecipe {
+        inputs: [MergeInput::Tank, MergeInput::Tank],
+        result: MergeKind::Fortress,
+    },
+    MergeRecipe {
+        inputs: [MergeInput::Tank, MergeInput::Wizard],
+        result: MergeKind::ShieldedCaster,
+    },
+    MergeRecipe {
+        inputs: [MergeInput::Merged, MergeInput::Merged],
+        result: MergeKind::Giant,
+    },
+];
+
+/// The recipe for merging `a` with `b`, if there is one.
+pub fn find_recipe(a: MergeInput, b: MergeInput) -> Option<MergeKind> {
+    RECIPES
+        .iter()
+        .find(|r| r.inputs == [a, b] || r.inputs == [b, a])
+        .map(|r| r.result)
+}
+
+/// The stats of one merge ingredient that feed into the result.
+#[derive(Clone, Copy)]
+pub struct MergeIngredient {
+    pub max_hp: i32,
+    pub damage: i32,
+    pub range: f32,
+    pub block_chance: f32,
+    pub stun_chance: f32,
+}
+
+impl MergeIngredient {
+    pub fn new(
+        max_health: &MaxHealth,
+        attacks: &KnownAttacks,
+        block: Option<&BlockChance>,
+    ) -> Self {
+        let attack = attacks.0.first();
+        Self {
+            max_hp: max_health.0,
+            damage: attack.map_or(0, |a| a.on_hit_effect.damage),
+            range: attack.map_or(0.0, |a| a.range),
+            block_chance: block.map_or(0.0, |b| b.0),
+            stun_chance: attack.map_or(0.0, |a| a.on_hit_effect.stun_chance),
+        }
+    }
+}
+
+/// Spawns the result of a merge at `position`, ready to fight. Starts from
+/// spawn_merged_slime and overwrites the stats with ones derived from the
+/// ingredients.
+pub fn spawn_merge_result(
+    commands: &mut Commands,
+    team: Team,
+    position: Vec3,
+    kind: MergeKind,
+    ingredients: [MergeIngredient; 2],
+) -> Entity {
+    let def = kind.def();
+    let [a, b] = ingredients;
+
+    let hp = (((a.max_hp + b.max_hp) as f32 * def.hp_multiplier).round() as i32).max(1);
+    let damage = (((a.damage + b.damage) as f32 * def.damage_multiplier).round() as i32).max(1);
+    let block_chance = (a.block_chance.max(b.block_chance) + def.block_bonus).min(0.9);
+    // Only casters inherit a wizard's spell range. A melee blob made from a
+    // wizard still has to walk up and slam.
+    let range = if def.positioning.is_ranged() {
+        def.range.max(a.range).max(b.range)
+    } else {
+        def.range
+    };
+    let attack_anim = match team {
+        Team::Player => AnimationType::BigSlimeAttack,
+        Team::Enemy => AnimationType::EnemyBigSlimeAttack,
+    };
+
+    let entity = spawn_merged_slime(commands, team, Some(position));
+    commands.entity(entity).insert((
+        kind,
+        def.positioning,
+        Health(hp),
+        MaxHealth(hp),
+        Speed(def.speed),
+        Mass(def.mass),
+        Transform::from_translation(position).with_scale(Vec3::splat(def.scale)),
+        KnownAttacks(vec![Attack {
+            animation: attack_anim,
+            hit_frame: 3,
+            on_hit_effect: AttackEffect {
+                damage,
+                knockback: 0.0,
+                stun_chance: a.stun_chance.max(b.stun_chance),
+                stun_duration: 1.5,
+                aoe_distance: def.splash,
+            },
+            range,
+            hitbox: big_slime_hitbox(range),
+        }]),
+    ));
+
+    if let Some(kind) = def.ability {
+        commands.entity(entity).insert(HazardAbility::new(kind));
+    }
+
+    // Anything that blocks gets the tank's iceberg shield. It's a child, so
+    // its offset and size are in the parent's (already scaled-up) space.
+    if block_chance > 0.0 {
+        let shield_x = match team {
+            Team::Player => 15.0,
+            Team::Enemy => -15.0,
+        };
+        commands
+            .entity(entity)
+            .insert(BlockChance(block_chance))
+            .with_child((
+                Shield,
+                AnimationType::IcebergIdle,
+                Transform::from_xyz(shield_x, -10.0, 1.0).with_scale(Vec3::splat(1.5)),
+                Sprite {
+                    flip_x: team == Team::Enemy,
+                    ..default()
+                },
+            ));
+    }
+
+    entity
+}
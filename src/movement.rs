@@ -2,11 +2,18 @@ use std::time;
 
 use bevy::prelude::*;
 
-use crate::combat::ActiveAttack;
+use crate::combat::{AttackEffect, FloatingText, KnownAttacks};
+use crate::damage::{DamageQueue, PendingHit};
 use crate::health::{Dying, Health};
-use crate::setup_round::{Inert, StunTimer};
-use crate::status::{CanBeMoved, CanMove};
-use crate::{ArenaBounds, GameState};
+use crate::obstacles::{line_of_sight, obstacle_collision_system, Obstacle};
+use crate::pick_target::Team;
+use crate::positioning::{ranged_step, FieldUnit, Positioning};
+use crate::status::{CanBeMoved, CanBeTargeted, CanMove};
+use crate::steering::{
+    arrive, avoid_blockers, seek, separation, steer, Acceleration, Blocker, Footing, Mass,
+    Steering, Velocity, ARRIVE_SLOW_RADIUS, AVOID_LOOKAHEAD, BODY_RADIUS,
+};
+use crate::{ArenaBounds, GameFont, GameState};
 
 pub struct MovementPlugin;
 
@@ -18,7 +25,10 @@ impl Plugin for MovementPlugin {
                 knockback_system,
                 move_to_target_system,
                 move_to_target_transform_system,
-                unsmush_system,
+                separation_system,
+                integrate_system,
+                knockback_collision_system,
+                obstacle_collision_system,
                 out_of_bounds_system,
             )
                 .chain()
@@ -33,7 +43,10 @@ pub struct TargetEntity(pub Entity);
 #[derive(Component)]
 pub struct TargetTransform(pub Vec3);
 
+/// Top speed. Anything that moves gets the rest of the steering model
+/// (steering.rs) along with it.
 #[derive(Component, Copy, Clone, PartialEq)]
+#[require(Velocity, Acceleration, Steering, Mass)]
 pub struct Speed(pub f32);
 
 /// Clamps how far a child entity can drift from its parent's position.
@@ -41,34 +54,81 @@ pub struct Speed(pub f32);
 #[derive(Component, Copy, Clone)]
 pub struct StaysNearParent(pub f32);
 
-/// Temporary component that smoothly moves an entity from its current position
-/// to a target position over 1 second with an ease-out curve.
+/// Temporary component: the entity has been flung by a hit (or by another
+/// flung body) and is coasting on its Velocity instead of steering.
+/// knockback_system slows it with friction and removes this once it has
+/// all but stopped.
 #[derive(Component)]
-pub struct Knockback {
-    pub start_position: Vec3,
-    pub target_position: Vec3,
-    pub timer: Timer,
+pub struct Knockback;
+
+/// Melee units stop this close to their target — inside the shortest melee
+/// range (65), so they can always swing once they've arrived.
+const MELEE_STOP_RADIUS: f32 = 45.0;
+/// TargetTransform moves only ease off right at the end.
+const TRANSFORM_SLOW_RADIUS: f32 = 10.0;
+/// How quickly a knocked-back body slows down, in units per second².
+const KNOCKBACK_FRICTION: f32 = 600.0;
+/// Below this speed a knocked-back body is back on its feet.
+const KNOCKBACK_SETTLE_SPEED: f32 = 30.0;
+/// Bodies closer than this are touching, for knockback collisions.
+const CONTACT_DISTANCE: f32 = 40.0;
+/// How bouncy body-to-body collisions are. At 0.5, a body flung into an
+/// equal one keeps a quarter of its speed and sends the other off at three
+/// quarters.
+const KNOCKBACK_RESTITUTION: f32 = 0.5;
+/// Fraction of its speed a body keeps bouncing off the arena wall.
+const WALL_RESTITUTION: f32 = 0.3;
+/// Hitting the wall slower than this doesn't hurt.
+const WALL_SLAM_MIN_SPEED: f32 = 200.0;
+/// Every this much speed over WALL_SLAM_MIN_SPEED adds a point of damage.
+const WALL_SLAM_SPEED_PER_DAMAGE: f32 = 150.0;
+
+/// Speed a knockback of `strength` gives a body of mass 1: just enough for
+/// friction to stop it `strength` units later. Heavier bodies get
+/// proportionally less.
+pub fn knockback_speed(strength: f32) -> f32 {
+    (2.0 * KNOCKBACK_FRICTION * strength).sqrt()
 }
 
+/// Steers movers toward their TargetEntity. Melee units arrive next to it.
+/// Ranged units (those with a ranged Positioning, see positioning.rs) hold
+/// their distance band instead. Either way they steer around arena
+/// obstacles and anyone standing still in the way.
 pub fn move_to_target_system(
     mut movers: Query<
         (
             Entity,
-            &mut Transform,
             &GlobalTransform,
             &TargetEntity,
             &Speed,
-            Option<&StaysNearParent>,
+            &mut Steering,
+            Option<(&Positioning, &Team, &KnownAttacks)>,
         ),
         With<CanMove>,
     >,
     targets: Query<&GlobalTransform>,
+    field: Query<(&Team, &GlobalTransform, Option<&Positioning>), With<CanBeTargeted>>,
+    standing: Query<(Entity, &GlobalTransform), (With<CanBeTargeted>, Without<CanMove>)>,
+    obstacles: Query<(&GlobalTransform, &Obstacle)>,
     mut commands: Commands,
-    time: Res<Time>,
 ) {
-    let delta = time.delta_secs();
+    let obstacle_blockers: Vec<Blocker> = obstacles
+        .iter()
+        .map(|(transform, obstacle)| obstacle.blocker(transform.translation()))
+        .collect();
 
-    for (entity, mut transform, global_tf, target, speed, stays_near) in movers.iter_mut() {
+    // Snapshot of everyone on the field, for ranged units to keep their
+    // distance from enemies and stay behind allies.
+    let units: Vec<FieldUnit> = field
+        .iter()
+        .map(|(team, transform, positioning)| FieldUnit {
+            team: *team,
+            position: transform.translation().truncate(),
+            ranged: positioning.is_some_and(|p| p.is_ranged()),
+        })
+        .collect();
+
+    for (entity, global_tf, target, speed, mut steering, ranged) in movers.iter_mut() {
         let Ok(target_global) = targets.get(target.0) else {
             // Target no longer exists (despawned). Remove TargetEntity so
             // pick_target_system can assign a new one next frame.
@@ -80,19 +140,155 @@ pub fn move_to_target_system(
         // For top-level entities, GlobalTransform == Transform (no change).
         // For child entities (like the spear), this gives the correct
         // world position instead of the local offset from the parent.
-        let mover_pos = global_tf.translation();
-        let target_pos = target_global.translation();
+        let mover_pos = global_tf.translation().truncate();
+        let target_pos = target_global.translation().truncate();
+        let offset = target_pos - mover_pos;
+
+        let desired = match ranged.filter(|(p, ..)| p.is_ranged()) {
+            Some((positioning, team, attacks)) => {
+                let range = attacks.0.iter().map(|a| a.range).fold(0.0, f32::max);
+                let step = ranged_step(
+                    positioning.def(),
+                    *team,
+                    mover_pos,
+                    target_pos,
+                    range,
+                    line_of_sight(mover_pos, target_pos, &obstacle_blockers),
+                    &units,
+                );
+                seek(step, speed.0)
+            }
+            None => arrive(offset, speed.0, MELEE_STOP_RADIUS, ARRIVE_SLOW_RADIUS),
+        };
+
+        // The target itself is what we're walking up to, not something to go
+        // around. Obstacles are padded out by a body's width, since it's our
+        // centre that has to pass them.
+        let blockers: Vec<Blocker> = standing
+            .iter()
+            .filter(|(e, _)| *e != entity && *e != target.0)
+            .map(|(_, transform)| Blocker {
+                position: transform.translation().truncate(),
+                radius: BODY_RADIUS,
+            })
+            .chain(obstacle_blockers.iter().map(|b| Blocker {
+                position: b.position,
+                radius: b.radius + BODY_RADIUS,
+            }))
+            .collect();
+        let lookahead = AVOID_LOOKAHEAD.min(offset.length());
+
+        steering.0 = desired + avoid_blockers(mover_pos, desired, lookahead, &blockers);
+    }
+}
 
-        let x_diff = target_pos.x - mover_pos.x;
-        if x_diff.abs() > 50.0 {
-            transform.translation.x += speed.0 * delta * x_diff.signum();
+fn move_to_target_transform_system(
+    mut commands: Commands,
+    mut movers: Query<
+        (
+            Entity,
+            &Transform,
+            &TargetTransform,
+            &Speed,
+            &mut Steering,
+            &mut Velocity,
+        ),
+        (Without<Dying>, Without<Knockback>),
+    >,
+) {
+    for (entity, transform, target, speed, mut steering, mut velocity) in movers.iter_mut() {
+        let offset = (target.0 - transform.translation).truncate();
+
+        if offset.length() <= 2.0 {
+            velocity.0 = Vec2::ZERO;
+            if let Ok(mut cmds) = commands.get_entity(entity) {
+                cmds.remove::<TargetTransform>();
+                cmds.remove::<TargetEntity>();
+            }
+            continue;
         }
 
-        let y_diff = target_pos.y - mover_pos.y;
-        if y_diff.abs() > 35.0 {
-            transform.translation.y += speed.0 * delta * y_diff.signum();
+        steering.0 = arrive(offset, speed.0, 0.0, TRANSFORM_SLOW_RADIUS);
+    }
+}
+
+/// Adds a push away from nearby bodies to everything that can be nudged,
+/// so crowds spread out instead of stacking on top of each other.
+fn separation_system(
+    bodies: Query<(Entity, &GlobalTransform), (With<Health>, With<CanBeTargeted>)>,
+    mut movers: Query<(Entity, &GlobalTransform, &mut Steering), (With<Health>, With<CanBeMoved>)>,
+) {
+    // Snapshot positions first so every push is computed from the same frame.
+    let positions: Vec<(Entity, Vec2)> = bodies
+        .iter()
+        .map(|(entity, transform)| (entity, transform.translation().truncate()))
+        .collect();
+
+    for (entity, transform, mut steering) in &mut movers {
+        let neighbours = positions
+            .iter()
+            .filter(|(other, _)| *other != entity)
+            .map(|(_, position)| *position);
+        steering.0 += separation(transform.translation().truncate(), neighbours);
+    }
+}
+
+/// Turns each entity's Velocity toward its Steering, moves it, and clears
+/// Steering for next frame. Knocked-back entities coast on their Velocity
+/// instead. Anything else that can't be moved right now (dying, merging,
+/// inert without a TargetTransform) stops dead.
+fn integrate_system(
+    mut movers: Query<(
+        &mut Transform,
+        &mut Velocity,
+        &mut Steering,
+        &Speed,
+        &Acceleration,
+        Option<&Footing>,
+        Option<&StaysNearParent>,
+        Has<CanBeMoved>,
+        Has<TargetTransform>,
+        Has<Knockback>,
+        Has<Dying>,
+    )>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs();
+
+    for (
+        mut transform,
+        mut velocity,
+        mut steering,
+        speed,
+        acceleration,
+        footing,
+        stays_near,
+        can_be_moved,
+        has_target_transform,
+        knocked_back,
+        dying,
+    ) in &mut movers
+    {
+        let desired = std::mem::take(&mut steering.0);
+        if knocked_back && !dying {
+            transform.translation += (velocity.0 * delta).extend(0.0);
+            continue;
+        }
+        if dying || !(can_be_moved || has_target_transform) {
+            velocity.0 = Vec2::ZERO;
+            continue;
         }
 
+        let (speed_scale, grip) = footing.map_or((1.0, 1.0), |f| (f.speed, f.grip));
+        velocity.0 = steer(
+            velocity.0,
+            desired,
+            acceleration.0 * grip,
+            speed.0 * speed_scale,
+            delta,
+        );
+        transform.translation += (velocity.0 * delta).extend(0.0);
+
         // If StaysNearParent is present, clamp the local position so the entity
         // can't drift too far from its parent. Since this is a child entity,
         // local (0, 0) is the parent's position — so we clamp the length
@@ -110,121 +306,102 @@ pub fn move_to_target_system(
     }
 }
 
-fn move_to_target_transform_system(
+/// Slows knocked-back entities with friction, and stands them back up once
+/// they've all but stopped.
+fn knockback_system(
     mut commands: Commands,
-    mut movers: Query<
-        (Entity, &mut Transform, &TargetTransform, &Speed),
-        (Without<Dying>, Without<Knockback>),
-    >,
     time: Res<Time>,
+    mut query: Query<(Entity, &mut Velocity), With<Knockback>>,
 ) {
     let delta = time.delta_secs();
 
-    for (entity, mut transform, target, speed) in movers.iter_mut() {
-        let dest = target.0;
-        let x_diff = dest.x - transform.translation.x;
-        let y_diff = dest.y - transform.translation.y;
-
-        if x_diff.abs() > 2.0 {
-            transform.translation.x += speed.0 * delta * x_diff.signum();
-        }
-        if y_diff.abs() > 2.0 {
-            transform.translation.y += speed.0 * delta * y_diff.signum();
-        }
+    for (entity, mut velocity) in query.iter_mut() {
+        let speed = (velocity.0.length() - KNOCKBACK_FRICTION * delta).max(0.0);
+        velocity.0 = velocity.0.normalize_or_zero() * speed;
 
-        if x_diff.abs() <= 2.0 && y_diff.abs() <= 2.0 {
+        if speed < KNOCKBACK_SETTLE_SPEED {
             if let Ok(mut cmds) = commands.get_entity(entity) {
-                cmds.remove::<TargetTransform>();
-                cmds.remove::<TargetEntity>();
+                cmds.remove::<Knockback>();
             }
         }
     }
 }
 
-/// Smoothly moves knocked-back entities from start to target position over 1 second.
-fn knockback_system(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut query: Query<(Entity, &mut Transform, &mut Knockback), Without<Dying>>,
-) {
-    for (entity, mut transform, mut knockback) in query.iter_mut() {
-        knockback.timer.tick(time.delta());
-
-        // Quadratic ease-out: fast start, slow finish.
-        let t = knockback.timer.fraction();
-        let t_eased = 1.0 - (1.0 - t).powi(2);
-
-        // Lerp between start and target using the eased t value.
-        transform.translation = knockback
-            .start_position
-            .lerp(knockback.target_position, t_eased);
-
-        if knockback.timer.is_finished() {
-            if let Ok(mut cmds) = commands.get_entity(entity) {
-                cmds.remove::<Knockback>();
-            }
-        }
-    }
+/// One body in knockback_collision_system's snapshot.
+struct Body {
+    entity: Entity,
+    position: Vec2,
+    velocity: Vec2,
+    mass: f32,
+    knocked_back: bool,
+    hit: bool,
 }
 
-/// Pushes entities apart when they're within 50 units of each other.
-/// The closer they are, the stronger the push. This prevents units
-/// from stacking on top of each other.
-pub fn unsmush_system(
-    mut query: Query<(Entity, &mut Transform), (With<Sprite>, With<Health>, With<CanBeMoved>)>,
-    time: Res<Time>,
+/// Knocked-back bodies bowl into whoever they touch. Each collision swaps
+/// momentum along the line between the two centres (KNOCKBACK_RESTITUTION
+/// decides how much), and anyone sent moving fast enough is knocked back
+/// too, so a big hit can scatter a whole cluster. "Fast enough" means the
+/// collision itself changed its velocity by at least KNOCKBACK_SETTLE_SPEED.
+fn knockback_collision_system(
+    mut commands: Commands,
+    mut query: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &mut Velocity,
+            &Mass,
+            Has<Knockback>,
+        ),
+        (With<Health>, Without<ChildOf>, Without<Dying>),
+    >,
 ) {
-    let min_x_distance = 30.0;
-    let min_y_distance = 25.0;
-    let push_strength = 100.0;
-
-    // Phase 1: Collect all positions so we can compare without borrow conflicts.
-    // We need to read every entity's position while also mutating transforms,
-    // so we snapshot positions first.
-    let positions: Vec<(Entity, Vec3)> = query
+    let mut bodies: Vec<Body> = query
         .iter()
-        .map(|(entity, transform)| (entity, transform.translation))
+        .map(|(entity, transform, velocity, mass, knocked_back)| Body {
+            entity,
+            position: transform.translation().truncate(),
+            velocity: velocity.0,
+            mass: mass.0,
+            knocked_back,
+            hit: false,
+        })
         .collect();
 
-    // Phase 2: For each pair, calculate push forces.
-    // We accumulate forces first, then apply them — otherwise earlier pushes
-    // would affect later distance calculations within the same frame.
-    let mut pushes: Vec<(Entity, Vec3)> = Vec::new();
-
-    for i in 0..positions.len() {
-        for j in (i + 1)..positions.len() {
-            let (entity_a, pos_a) = positions[i];
-            let (entity_b, pos_b) = positions[j];
-
-            let x_diff = pos_a.x - pos_b.x;
-            let y_diff = pos_a.y - pos_b.y;
-
-            if x_diff.abs() < min_x_distance {
-                // How much are we violating the min distance? (0.0 = barely touching, 1.0 = fully overlapping)
-                let x_overlap_ratio = 1.0 - (x_diff.abs() / min_x_distance);
-
-                // Push in x and y directions separately, scaled by how much we're overlapping in each direction
-                let x_push = x_diff.signum() * x_overlap_ratio * push_strength * time.delta_secs();
-                pushes.push((entity_a, Vec3::new(x_push, 0.0, 0.0)));
-                pushes.push((entity_b, Vec3::new(-x_push, 0.0, 0.0))); // opposite direction
+    for j in 1..bodies.len() {
+        let (before, after) = bodies.split_at_mut(j);
+        let b = &mut after[0];
+        for a in before.iter_mut() {
+            if !(a.knocked_back || b.knocked_back) {
+                continue;
+            }
+            let offset = b.position - a.position;
+            if offset.length() >= CONTACT_DISTANCE {
+                continue;
+            }
+            let normal = offset.try_normalize().unwrap_or(Vec2::X);
+            let closing = (a.velocity - b.velocity).dot(normal);
+            if closing <= 0.0 {
+                continue;
             }
 
-            if y_diff.abs() < min_y_distance {
-                // How much are we violating the min distance? (0.0 = barely touching, 1.0 = fully overlapping)
-                let y_overlap_ratio = 1.0 - (y_diff.abs() / min_y_distance);
-
-                // Push in x and y directions separately, scaled by how much we're overlapping in each direction
-                let y_push = y_diff.signum() * y_overlap_ratio * push_strength * time.delta_secs();
-                pushes.push((entity_a, Vec3::new(0.0, y_push, 0.0)));
-                pushes.push((entity_b, Vec3::new(0.0, -y_push, 0.0))); // opposite direction
+            let impulse = (1.0 + KNOCKBACK_RESTITUTION) * closing / (1.0 / a.mass + 1.0 / b.mass);
+            a.velocity -= normal * impulse / a.mass;
+            b.velocity += normal * impulse / b.mass;
+            // Judge by the kick each body took, not its total speed, so a
+            // unit already walking fast isn't knocked back by a graze.
+            for body in [&mut *a, &mut *b] {
+                body.hit = true;
+                body.knocked_back |= impulse / body.mass >= KNOCKBACK_SETTLE_SPEED;
             }
         }
     }
 
-    // Phase 3: Apply all pushes
-    for (entity, force) in pushes {
-        if let Ok((_, mut transform)) = query.get_mut(entity) {
-            transform.translation += force;
+    for body in bodies.iter().filter(|b| b.hit) {
+        if let Ok((_, _, mut velocity, _, already_knocked_back)) = query.get_mut(body.entity) {
+            velocity.0 = body.velocity;
+            if body.knocked_back && !already_knocked_back {
+                commands.entity(body.entity).insert(Knockback);
+            }
         }
     }
 }
@@ -237,39 +414,74 @@ pub fn unsmush_system(
 ///
 /// .clamp() is Rust's built-in method on f32: it returns the value pinned
 /// between a min and max. Cleaner than chaining .min().max().
+///
+/// Whatever part of a body's velocity points out through the wall is
+/// dropped. Knocked-back bodies bounce off instead, and a hard enough
+/// impact (a wall slam) hurts in proportion to the speed. The slam goes
+/// through the damage pipeline as a hit with no attacker, so armor and
+/// invulnerability apply to it.
 pub fn out_of_bounds_system(
     mut query: Query<
-        (Entity, &mut Transform, Option<&Knockback>),
+        (
+            Entity,
+            &mut Transform,
+            Option<&mut Velocity>,
+            Has<Health>,
+            Has<Knockback>,
+        ),
         (With<Sprite>, Without<ChildOf>),
     >,
     arena: Res<ArenaBounds>,
+    game_font: Res<GameFont>,
+    mut damage_queue: ResMut<DamageQueue>,
     mut commands: Commands,
 ) {
     let half_w = arena.half_width() - 50.0;
     let half_h = arena.half_height() - 50.0;
 
-    for (entity, mut transform, knockback) in &mut query {
+    for (entity, mut transform, velocity, has_health, knocked_back) in &mut query {
         let pos = transform.translation;
-        let out_of_bounds = pos.x < -half_w || pos.x > half_w || pos.y < -half_h || pos.y > half_h;
-
-        // Wall slam: if the entity is being knocked into the wall and the
-        // knockback target is more than 50 units past the arena edge, stun it.
-        if out_of_bounds {
-            if let Some(kb) = knockback {
-                let overflow_x = (kb.target_position.x.abs() - half_w).max(0.0);
-                let overflow_y = (kb.target_position.y.abs() - half_h).max(0.0);
-                let overflow = (overflow_x * overflow_x + overflow_y * overflow_y).sqrt();
-
-                if overflow > 50.0 {
-                    if let Ok(mut cmds) = commands.get_entity(entity) {
-                        cmds.insert((Inert, StunTimer(Timer::from_seconds(1.5, TimerMode::Once))));
-                        cmds.remove::<(ActiveAttack, Knockback)>();
-                    }
-                }
-            }
-        }
-
         transform.translation.x = pos.x.clamp(-half_w, half_w);
         transform.translation.y = pos.y.clamp(-half_h, half_h);
+
+        let Some(mut velocity) = velocity else {
+            continue;
+        };
+        let bounce = if knocked_back { -WALL_RESTITUTION } else { 0.0 };
+        let mut impact: f32 = 0.0;
+        if pos.x.abs() > half_w && velocity.0.x * pos.x > 0.0 {
+            impact = impact.max(velocity.0.x.abs());
+            velocity.0.x *= bounce;
+        }
+        if pos.y.abs() > half_h && velocity.0.y * pos.y > 0.0 {
+            impact = impact.max(velocity.0.y.abs());
+            velocity.0.y *= bounce;
+        }
+
+        if !knocked_back || !has_health || impact < WALL_SLAM_MIN_SPEED {
+            continue;
+        }
+        let damage = ((impact - WALL_SLAM_MIN_SPEED) / WALL_SLAM_SPEED_PER_DAMAGE)
+            .ceil()
+            .max(1.0) as i32;
+        damage_queue.push(PendingHit::new(
+            None,
+            entity,
+            AttackEffect {
+                damage,
+                ..default()
+            },
+        ));
+        commands.spawn((
+            FloatingText(Timer::from_seconds(0.8, TimerMode::Once)),
+            Text2d::new("SLAM!"),
+            TextFont {
+                font: game_font.0.clone(),
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Transform::from_xyz(pos.x, pos.y + 20.0, 10.0),
+        ));
     }
 }
@@ -0,0 +1,251 @@
+// Arena obstacles: rocks and ice pillars that stand in the middle of a fight.
+//
+// Each fight picks an arena layout from ARENAS by depth and spawns its
+// obstacles when the fight's PreCombat starts. An obstacle is a circle:
+//   - Movement: obstacle_collision_system (run in the movement chain) pushes
+//     bodies back out and cancels the part of their velocity heading in, so
+//     they slide along the edge. move_to_target_system also steers around
+//     obstacles ahead (steering.rs avoidance), so units rarely touch them.
+//   - Ranged attacks: anything reaching further than RANGED_ATTACK_RANGE
+//     needs line_of_sight to its target to start, and if an obstacle has
+//     moved into the way by the hit frame, the shot is BLOCKED.
+//   - Targeting: ranged units only pick targets they can see (when there are
+//     any), and everyone judges distance by travel_distance, which adds a
+//     detour for each obstacle on the straight path.
+//
+// Both obstacle kinds use the Iceberg sheet; rocks are just tinted grey.
+
+use bevy::prelude::*;
+
+use crate::animation::AnimationType;
+use crate::bosses::is_boss_depth;
+use crate::combat::FloatingText;
+use crate::end_round::CombatLevel;
+use crate::health::Health;
+use crate::steering::{Blocker, Velocity};
+use crate::{ArenaBounds, CombatState, GameFont, GameState};
+
+pub struct ObstaclesPlugin;
+
+impl Plugin for ObstaclesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(CombatState::PreCombat), spawn_arena_obstacles);
+    }
+}
+
+/// Attacks that reach further than this are ranged and need line of sight.
+pub const RANGED_ATTACK_RANGE: f32 = 150.0;
+/// How far from an obstacle's edge a body's centre is kept.
+const BODY_CLEARANCE: f32 = 15.0;
+/// Extra travel per obstacle on the straight path, as a multiple of its
+/// radius. Going halfway round a circle instead of through it costs (π - 2)r.
+const DETOUR_FACTOR: f32 = std::f32::consts::PI - 2.0;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Obstacle {
+    Rock,
+    IcePillar,
+}
+
+pub struct ObstacleDef {
+    pub kind: Obstacle,
+    /// Collision radius, in world units.
+    pub radius: f32,
+    pub scale: f32,
+    pub tint: Color,
+}
+
+pub const OBSTACLES: &[ObstacleDef] = &[
+    ObstacleDef {
+        kind: Obstacle::Rock,
+        radius: 30.0,
+        scale: 2.5,
+        tint: Color::srgb(0.55, 0.5, 0.45),
+    },
+    ObstacleDef {
+        kind: Obstacle::IcePillar,
+        radius: 40.0,
+        scale: 3.5,
+        tint: Color::WHITE,
+    },
+];
+
+impl Obstacle {
+    pub fn def(self) -> &'static ObstacleDef {
+        OBSTACLES
+            .iter()
+            .find(|d| d.kind == self)
+            .expect("every obstacle has an OBSTACLES entry")
+    }
+
+    /// This obstacle as a circle, for line of sight and avoidance.
+    pub fn blocker(self, position: Vec3) -> Blocker {
+        Blocker {
+            position: position.truncate(),
+            radius: self.def().radius,
+        }
+    }
+}
+
+pub struct ObstaclePlacement {
+    pub kind: Obstacle,
+    pub x: f32,
+    pub y: f32,
+}
+
+pub struct ArenaDef {
+    pub name: &'static str,
+    pub obstacles: &'static [ObstaclePlacement],
+}
+
+const fn place(kind: Obstacle, x: f32, y: f32) -> ObstaclePlacement {
+    ObstaclePlacement { kind, x, y }
+}
+
+/// Armies spawn at x 100..500 either side of the middle and y -200..200, so
+/// layouts keep the middle of the field interesting and leave lanes open.
+pub const ARENAS: &[ArenaDef] = &[
+    ArenaDef {
+        name: "Open Field",
+        obstacles: &[],
+    },
+    ArenaDef {
+        name: "Rock Garden",
+        obstacles: &[
+            place(Obstacle::Rock, 0.0, 0.0),
+            place(Obstacle::Rock, -180.0, 150.0),
+            place(Obstacle::Rock, 180.0, -150.0),
+        ],
+    },
+    ArenaDef {
+        name: "Ice Pillars",
+        obstacles: &[
+            place(Obstacle::IcePillar, -120.0, 130.0),
+            place(Obstacle::IcePillar, 120.0, 130.0),
+            place(Obstacle::IcePillar, -120.0, -130.0),
+            place(Obstacle::IcePillar, 120.0, -130.0),
+        ],
+    },
+    ArenaDef {
+        name: "Frozen Wall",
+        obstacles: &[
+            place(Obstacle::IcePillar, 0.0, -250.0),
+            place(Obstacle::IcePillar, 0.0, 0.0),
+            place(Obstacle::IcePillar, 0.0, 250.0),
+            place(Obstacle::Rock, -60.0, 125.0),
+            place(Obstacle::Rock, 60.0, -125.0),
+        ],
+    },
+];
+
+/// The first fight and boss fights are always in the open. Everything else
+/// rotates through the layouts by depth.
+pub fn arena_for_depth(depth: u32) -> &'static ArenaDef {
+    if depth <= 1 || is_boss_depth(depth) {
+        return &ARENAS[0];
+    }
+    &ARENAS[depth as usize % ARENAS.len()]
+}
+
+/// Whether the straight line from `from` to `to` clears every blocker.
+pub fn line_of_sight(from: Vec2, to: Vec2, blockers: &[Blocker]) -> bool {
+    !blockers.iter().any(|b| crosses(from, to, b))
+}
+
+/// Straight-line distance plus a detour for every blocker in the way.
+pub fn travel_distance(from: Vec2, to: Vec2, blockers: &[Blocker]) -> f32 {
+    let detour: f32 = blockers
+        .iter()
+        .filter(|b| crosses(from, to, b))
+        .map(|b| b.radius * DETOUR_FACTOR)
+        .sum();
+    from.distance(to) + detour
+}
+
+/// Whether the segment from `from` to `to` passes through the blocker.
+fn crosses(from: Vec2, to: Vec2, blocker: &Blocker) -> bool {
+    let segment = to - from;
+    let t = if segment.length_squared() > 0.0 {
+        ((blocker.position - from).dot(segment) / segment.length_squared()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (from + segment * t).distance(blocker.position) < blocker.radius
+}
+
+/// Clears the last fight's obstacles and lays out this depth's arena, with
+/// its name floating up from the top of the field.
+fn spawn_arena_obstacles(
+    mut commands: Commands,
+    existing: Query<Entity, With<Obstacle>>,
+    combat_level: Option<Res<CombatLevel>>,
+    arena: Res<ArenaBounds>,
+    game_font: Res<GameFont>,
+) {
+    for entity in &existing {
+        commands.entity(entity).despawn();
+    }
+
+    let depth = combat_level.map_or(1, |level| level.0);
+    let layout = arena_for_depth(depth);
+    commands.spawn((
+        FloatingText(Timer::from_seconds(2.5, TimerMode::Once)),
+        Text2d::new(layout.name),
+        TextFont {
+            font: game_font.0.clone(),
+            font_size: 28.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_xyz(0.0, arena.half_height() - 120.0, 10.0),
+        DespawnOnExit(GameState::Combat),
+    ));
+
+    for placement in layout.obstacles {
+        let def = placement.kind.def();
+        commands.spawn((
+            DespawnOnExit(GameState::Combat),
+            placement.kind,
+            AnimationType::IcebergIdle,
+            Sprite {
+                color: def.tint,
+                ..default()
+            },
+            Transform::from_xyz(placement.x, placement.y, 0.0).with_scale(Vec3::splat(def.scale)),
+        ));
+    }
+}
+
+/// Pushes bodies out of obstacles. Whatever part of a body's velocity points
+/// into the obstacle is dropped, so it slides round the edge instead of
+/// grinding against it.
+pub fn obstacle_collision_system(
+    obstacles: Query<(&Transform, &Obstacle)>,
+    mut bodies: Query<
+        (&mut Transform, Option<&mut Velocity>),
+        (With<Health>, Without<ChildOf>, Without<Obstacle>),
+    >,
+) {
+    for (mut transform, mut velocity) in &mut bodies {
+        for (obstacle_transform, obstacle) in &obstacles {
+            let min_distance = obstacle.def().radius + BODY_CLEARANCE;
+            let away = (transform.translation - obstacle_transform.translation).truncate();
+            let distance = away.length();
+            if distance >= min_distance {
+                continue;
+            }
+
+            let normal = away.try_normalize().unwrap_or(Vec2::Y);
+            let pushed = obstacle_transform.translation.truncate() + normal * min_distance;
+            transform.translation.x = pushed.x;
+            transform.translation.y = pushed.y;
+
+            if let Some(velocity) = velocity.as_mut() {
+                let into = velocity.0.dot(normal);
+                if into < 0.0 {
+                    velocity.0 -= normal * into;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,545 @@
+// Odds: estimated chance of beating the next depth.
+//
+// When a round is won, we snapshot the surviving player slimes and hand them
+// to a background task that fights them against the next enemy waves a few
+// hundred times each. There's no movement, animation, or ECS in there —
+// just HP, damage, attack timing, blocks, stuns, and splash, ticked in fixed
+// steps. It's a rough model, which is why the result is shown as a range.
+//
+// The next waves are the ones already rolled onto the depth map (depth_map.rs):
+// every Fight, Elite or Boss node reachable from the current one is simulated
+// against its own `node.wave`, elite extras included. Nodes without a fight
+// are skipped. The hint shows the worst and best of those fights, since the
+// player picks which one they walk into.
+//
+// The simulator itself is public so other systems (side bets) can price
+// propositions from the same model.
+
+use bevy::prelude::*;
+use bevy::tasks::futures::check_ready;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::affixes::{Affix, ARMOR, ENRAGED_MAX_BONUS};
+use crate::armies::{Army, EnemyWave};
+use crate::combat::{BlockChance, KnownAttacks, TimeBetweenAttacks};
+use crate::depth_map::{DepthMap, NodeKind};
+use crate::end_round::CombatLevel;
+use crate::health::{Dying, Health};
+use crate::pick_target::Team;
+use crate::save_load::SaveData;
+use crate::special_abilities::MergedSlime;
+use crate::CombatState;
+
+pub struct OddsPlugin;
+
+impl Plugin for OddsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(CombatState::PostCombat), start_odds_simulation)
+            .add_systems(OnExit(CombatState::PostCombat), cleanup_odds)
+            .add_systems(
+                Update,
+                poll_odds_task.run_if(in_state(CombatState::PostCombat)),
+            );
+    }
+}
+
+/// Fights per reachable node. 400 keeps the 95% band within about ±5% and
+/// finishes in a few milliseconds.
+const SIMULATIONS: u32 = 400;
+/// Fixed simulation step in seconds.
+const SIM_STEP: f32 = 0.1;
+/// Fights still going after this long count as losses — in the real game a
+/// stalemate never pays out either.
+const SIM_TIME_LIMIT: f32 = 120.0;
+/// How many extra enemies a splash attack catches, on average, in a model
+/// with no positions.
+const SPLASH_TARGETS: usize = 2;
+/// Melee slimes spend roughly this long walking into range before their
+/// first swing. Wizards cast from range and start immediately.
+const MELEE_APPROACH_TIME: f32 = 1.5;
+
+// Rough seconds per attack, from the attack animations' frame counts and
+// frame times in animation.rs plus the cooldowns set in spawn_slimes.rs.
+const SLIME_ATTACK_INTERVAL: f32 = 0.8;
+const BIG_SLIME_ATTACK_INTERVAL: f32 = 1.6;
+const MAGE_CAST_INTERVAL: f32 = 1.5;
+const SPEAR_ANIMATION_TIME: f32 = 0.6;
+
+/// The estimate for one reachable fight node.
+#[derive(Clone, Copy, Debug)]
+pub struct NodeOdds {
+    pub kind: NodeKind,
+    /// Fraction of simulated fights won, 0..=1.
+    pub win_rate: f32,
+    /// 95% confidence band around win_rate.
+    pub low: f32,
+    pub high: f32,
+}
+
+/// The estimate shown on the PostCombat screen.
+#[derive(Resource, Clone, Copy, Debug)]
+pub enum WinOdds {
+    Calculating,
+    /// None of the nodes reachable next is a fight.
+    NoFight,
+    /// The hardest and easiest reachable fights. The same node when only one
+    /// fight is reachable.
+    Ready {
+        worst: NodeOdds,
+        best: NodeOdds,
+    },
+}
+
+impl WinOdds {
+    /// Short text for the PostCombat hint, e.g. "Fight: win 72% (67-76%)" or
+    /// "win 41% (Elite) to 80% (Fight)".
+    pub fn label(&self) -> String {
+        let percent = |p: f32| (p * 100.0).round() as u32;
+        match self {
+            WinOdds::Calculating => "win odds: calculating...".to_string(),
+            WinOdds::NoFight => "no fight next".to_string(),
+            WinOdds::Ready { worst, best } if percent(worst.win_rate) == percent(best.win_rate) => {
+                format!(
+                    "{}: win {}% ({}-{}%)",
+                    worst.kind.label(),
+                    percent(worst.win_rate),
+                    percent(worst.low),
+                    percent(worst.high)
+                )
+            }
+            WinOdds::Ready { worst, best } => format!(
+                "win {}% ({}) to {}% ({})",
+                percent(worst.win_rate),
+                worst.kind.label(),
+                percent(best.win_rate),
+                best.kind.label()
+            ),
+        }
+    }
+}
+
+/// Holds the running background simulation. Despawned with the PostCombat
+/// screen, which drops (and cancels) the task if it hasn't finished.
+#[derive(Component)]
+struct OddsTask(Task<WinOdds>);
+
+/// One combatant in the simplified model.
+#[derive(Clone, Debug)]
+pub struct SimUnit {
+    hp: i32,
+    damage: i32,
+    attack_interval: f32,
+    block_chance: f32,
+    stun_chance: f32,
+    stun_duration: f32,
+    splash: bool,
+    /// Flat reduction on every hit taken (the Armored affix).
+    armor: i32,
+    /// Wizards' frozen spear: a second, independent attack.
+    secondary: Option<SimAttack>,
+    next_attack: f32,
+    stunned_for: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct SimAttack {
+    damage: i32,
+    interval: f32,
+    next_attack: f32,
+}
+
+impl SimUnit {
+    fn melee(hp: i32, damage: i32, attack_interval: f32) -> Self {
+        Self {
+            hp,
+            damage,
+            attack_interval,
+            block_chance: 0.0,
+            stun_chance: 0.0,
+            stun_duration: 0.0,
+            splash: false,
+            armor: 0,
+            secondary: None,
+            next_attack: MELEE_APPROACH_TIME,
+            stunned_for: 0.0,
+        }
+    }
+
+    fn caster(hp: i32, damage: i32, spear: Option<SimAttack>) -> Self {
+        Self {
+            splash: true,
+            secondary: spear,
+            next_attack: 0.0,
+            ..Self::melee(hp, damage, MAGE_CAST_INTERVAL)
+        }
+    }
+
+    fn spear(damage: i32, time_between_attacks: f32) -> SimAttack {
+        SimAttack {
+            damage,
+            interval: time_between_attacks + SPEAR_ANIMATION_TIME,
+            next_attack: MELEE_APPROACH_TIME,
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        self.hp > 0
+    }
+}
+
+/// Units that haven't spawned yet come straight from army data, so their
+/// stats mirror the spawn functions in spawn_slimes.rs.
+pub fn army_units(army: &Army) -> Vec<SimUnit> {
+    let mut units = Vec::new();
+
+    for _ in 0..army.normal.count {
+        units.push(SimUnit::melee(army.normal.hp, 1, SLIME_ATTACK_INTERVAL));
+    }
+    for _ in 0..army.tanks.count {
+        units.push(SimUnit {
+            block_chance: army.tanks.block_chance,
+            stun_chance: army.tanks.stun_chance,
+            stun_duration: 1.5,
+            ..SimUnit::melee(army.tanks.hp, 2, SLIME_ATTACK_INTERVAL)
+        });
+    }
+    for _ in 0..army.wizards.count {
+        units.push(SimUnit::caster(
+            army.wizards.hp,
+            army.wizards.aoe_damage,
+            Some(SimUnit::spear(1, 2.0)),
+        ));
+    }
+
+    units
+}
+
+pub fn wave_units(wave: &EnemyWave) -> Vec<SimUnit> {
+    let mut units = army_units(&wave.army);
+    // The first enemy to spawn is the elite. Swift and Frost Aura only
+    // change movement, which the model doesn't have, and Vampiric's healing
+    // and Splitting's extra slimes are left out.
+    if let Some(elite) = units.first_mut() {
+        for affix in &wave.elite_affixes {
+            match affix {
+                Affix::Armored => elite.armor = ARMOR,
+                // Half the bonus, as it only builds up as HP drops.
+                Affix::Enraged => elite.damage += ENRAGED_MAX_BONUS / 2,
+                Affix::Swift | Affix::Vampiric | Affix::Splitting | Affix::FrostAura => {}
+            }
+        }
+    }
+    for _ in 0..wave.merged_count {
+        units.push(SimUnit::melee(40, 8, BIG_SLIME_ATTACK_INTERVAL));
+    }
+    // Bosses are modelled by their opening attack; phases aren't simulated.
+    if let Some(boss) = wave.boss {
+        let def = boss.def();
+        units.push(SimUnit::melee(
+            def.hp,
+            def.attack.damage,
+            BIG_SLIME_ATTACK_INTERVAL,
+        ));
+    }
+
+    units
+}
+
+/// Top-level combatants with everything the simulator needs to know.
+pub type CombatantQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Team,
+        &'static Health,
+        &'static KnownAttacks,
+        Option<&'static BlockChance>,
+        Has<MergedSlime>,
+    ),
+    (Without<Dying>, Without<ChildOf>),
+>;
+
+/// Attacking children (the wizard's frozen spear), looked up by parent.
+pub type SpearQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static ChildOf,
+        &'static KnownAttacks,
+        Option<&'static TimeBetweenAttacks>,
+    ),
+    Without<Health>,
+>;
+
+/// Converts one team's living units into simulator units, keeping their
+/// current HP and whatever stats they were spawned with (upgrades included).
+pub fn snapshot_team(team: Team, units: &CombatantQuery, spears: &SpearQuery) -> Vec<SimUnit> {
+    let mut snapshot = Vec::new();
+    for (entity, unit_team, health, known_attacks, block_chance, is_merged) in units {
+        if *unit_team != team {
+            continue;
+        }
+        let Some(attack) = known_attacks.0.first() else {
+            continue;
+        };
+        let effect = &attack.on_hit_effect;
+
+        let mut unit = if effect.aoe_distance.is_some() {
+            let spear = spears
+                .iter()
+                .find(|(parent, ..)| parent.parent() == entity)
+                .and_then(|(_, spear_attacks, cooldown)| {
+                    let damage = spear_attacks.0.first()?.on_hit_effect.damage;
+                    Some(SimUnit::spear(damage, cooldown.map(|c| c.0).unwrap_or(0.0)))
+                });
+            SimUnit::caster(health.0, effect.damage, spear)
+        } else if is_merged {
+            SimUnit::melee(health.0, effect.damage, BIG_SLIME_ATTACK_INTERVAL)
+        } else {
+            SimUnit::melee(health.0, effect.damage, SLIME_ATTACK_INTERVAL)
+        };
+        unit.block_chance = block_chance.map(|b| b.0).unwrap_or(0.0);
+        unit.stun_chance = effect.stun_chance;
+        unit.stun_duration = effect.stun_duration;
+
+        snapshot.push(unit);
+    }
+    snapshot
+}
+
+/// Snapshots the surviving player slimes and the waves reachable from the
+/// current map node, and starts the background task. Only runs after a
+/// victory, and not at all if the player hid the odds.
+fn start_odds_simulation(
+    mut commands: Commands,
+    save_data: Res<SaveData>,
+    combat_level: Res<CombatLevel>,
+    mut map: ResMut<DepthMap>,
+    units: CombatantQuery,
+    spears: SpearQuery,
+) {
+    if save_data.settings.hide_odds {
+        return;
+    }
+    if units.iter().any(|(_, team, ..)| *team == Team::Enemy) {
+        return;
+    }
+
+    let players = snapshot_team(Team::Player, &units, &spears);
+    if players.is_empty() {
+        return;
+    }
+
+    let current = combat_level.0;
+    map.ensure_depth(current + 1);
+    let next_row = map.row(current + 1);
+    let waves: Vec<(NodeKind, EnemyWave)> = map.row(current)[map.position]
+        .next
+        .iter()
+        .filter_map(|&index| {
+            let node = &next_row[index];
+            node.wave.clone().map(|wave| (node.kind, wave))
+        })
+        .collect();
+
+    let task = AsyncComputeTaskPool::get()
+        .spawn(async move { estimate_win_odds(&players, &waves, SIMULATIONS) });
+
+    commands.insert_resource(WinOdds::Calculating);
+    commands.spawn((DespawnOnExit(CombatState::PostCombat), OddsTask(task)));
+}
+
+fn poll_odds_task(mut commands: Commands, mut tasks: Query<(Entity, &mut OddsTask)>) {
+    for (entity, mut task) in &mut tasks {
+        if let Some(odds) = check_ready(&mut task.0) {
+            commands.insert_resource(odds);
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn cleanup_odds(mut commands: Commands) {
+    commands.remove_resource::<WinOdds>();
+}
+
+fn estimate_win_odds(
+    players: &[SimUnit],
+    waves: &[(NodeKind, EnemyWave)],
+    simulations: u32,
+) -> WinOdds {
+    let mut rng = rand::thread_rng();
+    let odds: Vec<NodeOdds> = waves
+        .iter()
+        .map(|(kind, wave)| {
+            let enemies = wave_units(wave);
+            let wins = (0..simulations)
+                .filter(|_| simulate_fight(players.to_vec(), enemies.clone(), &mut rng).player_won)
+                .count() as u32;
+            let (low, high) = wilson_interval(wins, simulations);
+            NodeOdds {
+                kind: *kind,
+                win_rate: wins as f32 / simulations as f32,
+                low,
+                high,
+            }
+        })
+        .collect();
+
+    let by_win_rate = |a: &&NodeOdds, b: &&NodeOdds| a.win_rate.total_cmp(&b.win_rate);
+    match (
+        odds.iter().min_by(by_win_rate),
+        odds.iter().max_by(by_win_rate),
+    ) {
+        (Some(worst), Some(best)) => WinOdds::Ready {
+            worst: *worst,
+            best: *best,
+        },
+        _ => WinOdds::NoFight,
+    }
+}
+
+/// 95% Wilson score interval. Unlike the textbook p ± 1.96·σ it stays inside
+/// 0..1 and behaves sensibly when nearly every fight is won (or lost).
+pub fn wilson_interval(successes: u32, trials: u32) -> (f32, f32) {
+    if trials == 0 {
+        return (0.0, 1.0);
+    }
+    let z = 1.96_f32;
+    let n = trials as f32;
+    let p = successes as f32 / n;
+
+    let denominator = 1.0 + z * z / n;
+    let center = (p + z * z / (2.0 * n)) / denominator;
+    let margin = z * ((p * (1.0 - p) + z * z / (4.0 * n)) / n).sqrt() / denominator;
+
+    ((center - margin).max(0.0), (center + margin).min(1.0))
+}
+
+/// How one simulated fight went.
+#[derive(Clone, Copy, Debug)]
+pub struct FightOutcome {
+    /// True if the player side is the last one standing.
+    pub player_won: bool,
+    /// Player units alive at the end.
+    pub survivors: u32,
+    /// Seconds until one side was wiped out (or the time limit).
+    pub duration: f32,
+}
+
+pub fn simulate_fight(
+    mut players: Vec<SimUnit>,
+    mut enemies: Vec<SimUnit>,
+    rng: &mut impl Rng,
+) -> FightOutcome {
+    let mut elapsed = 0.0;
+    while elapsed < SIM_TIME_LIMIT {
+        let survivors = players.iter().filter(|u| u.is_alive()).count() as u32;
+        let enemies_alive = enemies.iter().any(SimUnit::is_alive);
+        if !enemies_alive || survivors == 0 {
+            return FightOutcome {
+                player_won: !enemies_alive && survivors > 0,
+                survivors,
+                duration: elapsed,
+            };
+        }
+
+        // Both sides pick their hits from the same snapshot of who's alive,
+        // and the hits only land once both have acted, so there's no
+        // first-mover advantage within a step.
+        let player_hits = step_side(&mut players, &enemies, rng);
+        let enemy_hits = step_side(&mut enemies, &players, rng);
+        for hit in player_hits {
+            apply_hit(&mut enemies[hit.target], &hit, rng);
+        }
+        for hit in enemy_hits {
+            apply_hit(&mut players[hit.target], &hit, rng);
+        }
+        elapsed += SIM_STEP;
+    }
+    FightOutcome {
+        player_won: false,
+        survivors: players.iter().filter(|u| u.is_alive()).count() as u32,
+        duration: SIM_TIME_LIMIT,
+    }
+}
+
+/// A hit one side has thrown this step, waiting to land.
+struct SimHit {
+    /// Index into the defending side.
+    target: usize,
+    damage: i32,
+    stun_chance: f32,
+    stun_duration: f32,
+}
+
+/// Ticks one side's timers and returns the hits it throws this step. The
+/// defenders aren't touched; simulate_fight applies the hits afterwards.
+fn step_side(attackers: &mut [SimUnit], defenders: &[SimUnit], rng: &mut impl Rng) -> Vec<SimHit> {
+    let mut thrown = Vec::new();
+    for attacker in attackers.iter_mut().filter(|u| u.is_alive()) {
+        if attacker.stunned_for > 0.0 {
+            attacker.stunned_for -= SIM_STEP;
+            continue;
+        }
+
+        attacker.next_attack -= SIM_STEP;
+        if attacker.next_attack <= 0.0 {
+            attacker.next_attack += attacker.attack_interval;
+            let hits = if attacker.splash {
+                1 + SPLASH_TARGETS
+            } else {
+                1
+            };
+            for target in pick_targets(defenders, hits, rng) {
+                thrown.push(SimHit {
+                    target,
+                    damage: attacker.damage,
+                    stun_chance: attacker.stun_chance,
+                    stun_duration: attacker.stun_duration,
+                });
+            }
+        }
+
+        if let Some(spear) = attacker.secondary.as_mut() {
+            spear.next_attack -= SIM_STEP;
+            if spear.next_attack <= 0.0 {
+                spear.next_attack += spear.interval;
+                if let Some(&target) = pick_targets(defenders, 1, rng).first() {
+                    thrown.push(SimHit {
+                        target,
+                        damage: spear.damage,
+                        stun_chance: 0.0,
+                        stun_duration: 0.0,
+                    });
+                }
+            }
+        }
+    }
+    thrown
+}
+
+fn pick_targets(defenders: &[SimUnit], count: usize, rng: &mut impl Rng) -> Vec<usize> {
+    let alive: Vec<usize> = (0..defenders.len())
+        .filter(|&i| defenders[i].is_alive())
+        .collect();
+    alive.choose_multiple(rng, count).copied().collect()
+}
+
+/// Same order as the damage pipeline (damage.rs): block roll first, then
+/// armor and damage, then stun.
+fn apply_hit(target: &mut SimUnit, hit: &SimHit, rng: &mut impl Rng) {
+    if target.block_chance > 0.0 && rng.gen::<f32>() < target.block_chance {
+        return;
+    }
+    target.hp -= if hit.damage > 0 {
+        (hit.damage - target.armor).max(1)
+    } else {
+        0
+    };
+    if target.hp > 0 && hit.stun_chance > 0.0 && rng.gen::<f32>() < hit.stun_chance {
+        target.stunned_for = target.stunned_for.max(hit.stun_duration);
+    }
+}
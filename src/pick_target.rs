@@ -1,30 +1,105 @@
 use std::time;
 
 use bevy::{prelude::*, state::commands};
-use rand::seq::IteratorRandom;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
 
+use crate::armies::SlimeType;
+use crate::combat::KnownAttacks;
+use crate::health::Health;
 use crate::movement::TargetEntity;
+use crate::obstacles::{line_of_sight, travel_distance, Obstacle};
 use crate::status::{CanBeTargeted, CanTarget};
+use crate::steering::Blocker;
+use crate::threat::{Taunted, Threat};
 use crate::CombatState;
 
 pub struct PickTargetPlugin;
 
 impl Plugin for PickTargetPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.insert_resource(RetargetTimer(Timer::from_seconds(
+            RETARGET_INTERVAL,
+            TimerMode::Repeating,
+        )))
+        .add_systems(
             Update,
-            (pick_target_system, closest_target_system)
-                .run_if(in_state(CombatState::DuringCombat)),
+            pick_target_system.run_if(in_state(CombatState::DuringCombat)),
         );
     }
 }
 
-#[derive(Component, Copy, Clone, PartialEq, Eq, Hash)]
+/// Seconds between re-evaluations for strategies that retarget periodically.
+const RETARGET_INTERVAL: f32 = 0.5;
+
+/// How many random enemies Close shortlists before picking the nearest.
+const CLOSE_SHORTLIST: usize = 3;
+
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PickTargetStrategy {
     /// Picks from a random shortlist of 3 enemies, then chooses the closest.
     Close,
     /// Constantly re-evaluates every frame to always target the single closest enemy.
     Closest,
+    /// Goes after the enemy with the least HP left.
+    LowestHealth,
+    /// Goes after the enemy that has built the most threat on this unit
+    /// (see threat.rs), or the hardest hitter if nobody has yet.
+    HighestThreat,
+    /// Dives past the front line for wizards and other casters.
+    Backline,
+    /// Any enemy at all.
+    Random,
+    /// Locks onto the closest enemy and never lets go until it dies.
+    Stickiest,
+    /// Attacks whatever the nearest ally is attacking.
+    Assist,
+}
+
+/// When a unit that already has a target looks for a new one.
+enum Retarget {
+    EveryFrame,
+    Periodically,
+    Never,
+}
+
+impl PickTargetStrategy {
+    pub const ALL: [PickTargetStrategy; 8] = [
+        PickTargetStrategy::Close,
+        PickTargetStrategy::Closest,
+        PickTargetStrategy::LowestHealth,
+        PickTargetStrategy::HighestThreat,
+        PickTargetStrategy::Backline,
+        PickTargetStrategy::Random,
+        PickTargetStrategy::Stickiest,
+        PickTargetStrategy::Assist,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PickTargetStrategy::Close => "Close",
+            PickTargetStrategy::Closest => "Closest",
+            PickTargetStrategy::LowestHealth => "Weakest",
+            PickTargetStrategy::HighestThreat => "Threat",
+            PickTargetStrategy::Backline => "Backline",
+            PickTargetStrategy::Random => "Random",
+            PickTargetStrategy::Stickiest => "Sticky",
+            PickTargetStrategy::Assist => "Assist",
+        }
+    }
+
+    fn retarget(self) -> Retarget {
+        match self {
+            PickTargetStrategy::Closest => Retarget::EveryFrame,
+            PickTargetStrategy::LowestHealth
+            | PickTargetStrategy::HighestThreat
+            | PickTargetStrategy::Backline
+            | PickTargetStrategy::Assist => Retarget::Periodically,
+            PickTargetStrategy::Close
+            | PickTargetStrategy::Random
+            | PickTargetStrategy::Stickiest => Retarget::Never,
+        }
+    }
 }
 
 #[derive(Component, Copy, Clone, PartialEq, Eq, Hash)]
@@ -33,88 +108,254 @@ pub enum Team {
     Enemy,
 }
 
+/// Resource — gates Retarget::Periodically strategies, the same way
+/// MergeCheckTimer gates merge rolls.
+#[derive(Resource)]
+pub struct RetargetTimer(pub Timer);
+
+/// A potential target, as the strategies see it.
+struct Candidate {
+    entity: Entity,
+    team: Team,
+    position: Vec3,
+    health: i32,
+    /// Hardest hit among its attacks.
+    threat: i32,
+    /// Wizards and anything else with a splash attack.
+    backline: bool,
+}
+
+/// The unit doing the picking.
+struct Seeker<'a> {
+    entity: Entity,
+    team: Team,
+    position: Vec3,
+    threat: Option<&'a Threat>,
+    /// Has a ranged attack, so only wants targets it can see.
+    ranged: bool,
+}
+
+/// A unit that already has a target, for Assist.
+struct AllyTarget {
+    entity: Entity,
+    team: Team,
+    position: Vec3,
+    target: Entity,
+}
+
+/// Picks a target for one seeker. Every strategy goes through here, so a
+/// new strategy is one enum variant plus one match arm.
+///
+/// Arena obstacles (obstacles.rs) shape every strategy the same way:
+/// distances are travel distances around them, and ranged seekers only
+/// consider enemies they have line of sight to, unless there are none.
+fn choose_target(
+    strategy: PickTargetStrategy,
+    seeker: &Seeker,
+    candidates: &[Candidate],
+    allies: &[AllyTarget],
+    blockers: &[Blocker],
+    rng: &mut impl rand::Rng,
+) -> Option<Entity> {
+    let Seeker {
+        team,
+        position,
+        threat,
+        ..
+    } = *seeker;
+    let from = position.truncate();
+    let distance = |c: &&Candidate| travel_distance(from, c.position.truncate(), blockers);
+    let by_distance =
+        |a: &&Candidate, b: &&Candidate| distance(a).partial_cmp(&distance(b)).unwrap();
+    let mut enemies: Vec<&Candidate> = candidates.iter().filter(|c| c.team != team).collect();
+    if seeker.ranged {
+        let visible: Vec<&Candidate> = enemies
+            .iter()
+            .copied()
+            .filter(|c| line_of_sight(from, c.position.truncate(), blockers))
+            .collect();
+        if !visible.is_empty() {
+            enemies = visible;
+        }
+    }
+    let closest = || {
+        enemies
+            .iter()
+            .copied()
+            .min_by(by_distance)
+            .map(|c| c.entity)
+    };
+
+    match strategy {
+        PickTargetStrategy::Close => enemies
+            .choose_multiple(rng, CLOSE_SHORTLIST)
+            .copied()
+            .min_by(by_distance)
+            .map(|c| c.entity),
+        PickTargetStrategy::Closest | PickTargetStrategy::Stickiest => closest(),
+        PickTargetStrategy::LowestHealth => enemies
+            .iter()
+            .copied()
+            .min_by(|a, b| a.health.cmp(&b.health).then(by_distance(a, b)))
+            .map(|c| c.entity),
+        PickTargetStrategy::HighestThreat => threat
+            .and_then(|threat| {
+                enemies
+                    .iter()
+                    .filter_map(|c| Some((c.entity, *threat.0.get(&c.entity)?)))
+                    .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                    .map(|(e, _)| e)
+            })
+            .or_else(|| {
+                enemies
+                    .iter()
+                    .copied()
+                    .min_by(|a, b| b.threat.cmp(&a.threat).then(by_distance(a, b)))
+                    .map(|c| c.entity)
+            }),
+        PickTargetStrategy::Backline => enemies
+            .iter()
+            .copied()
+            .filter(|c| c.backline)
+            .min_by(by_distance)
+            .map(|c| c.entity)
+            .or_else(closest),
+        PickTargetStrategy::Random => enemies.choose(rng).map(|c| c.entity),
+        PickTargetStrategy::Assist => allies
+            .iter()
+            .filter(|a| a.team == team && a.entity != seeker.entity)
+            .filter(|a| enemies.iter().any(|c| c.entity == a.target))
+            .min_by(|a, b| {
+                let dist_a = position.distance(a.position);
+                let dist_b = position.distance(b.position);
+                dist_a.partial_cmp(&dist_b).unwrap()
+            })
+            .map(|a| a.target)
+            .or_else(closest),
+    }
+}
+
+/// Assigns and re-evaluates targets for everything with a PickTargetStrategy.
+///
+/// Units without a target always get one. Units that already have one only
+/// look again if their strategy says so: Closest every frame (so it switches
+/// the moment someone nearer walks by), the situational strategies every
+/// RETARGET_INTERVAL, and Close/Random/Stickiest never — they keep their
+/// target until it dies. A taunted unit (threat.rs) ignores its strategy and
+/// sticks to its taunter.
 pub fn pick_target_system(
     // GlobalTransform gives world-space position, which is necessary for child entities
     // (like the frozen spear) whose local Transform is relative to their parent.
     // For top-level entities, GlobalTransform equals Transform — no change in behavior.
-    entities_needing_targets: Query<
-        (Entity, &PickTargetStrategy, &Team, &GlobalTransform),
-        (Without<TargetEntity>, With<CanTarget>),
+    seekers: Query<
+        (
+            Entity,
+            &PickTargetStrategy,
+            &Team,
+            &GlobalTransform,
+            Option<&TargetEntity>,
+            Option<&Threat>,
+            Option<&Taunted>,
+            Option<&KnownAttacks>,
+        ),
+        With<CanTarget>,
     >,
-    potential_targets: Query<(Entity, &Team, &GlobalTransform), With<CanBeTargeted>>,
+    potential_targets: Query<
+        (
+            Entity,
+            &Team,
+            &GlobalTransform,
+            &Health,
+            Option<&KnownAttacks>,
+            Option<&SlimeType>,
+        ),
+        With<CanBeTargeted>,
+    >,
+    obstacles: Query<(&GlobalTransform, &Obstacle)>,
+    mut retarget_timer: ResMut<RetargetTimer>,
+    time: Res<Time>,
     mut commands: Commands,
 ) {
-    let mut rng = rand::thread_rng();
-    for (entity, strategy, team, transform) in entities_needing_targets {
-        let target_entity: Option<Entity> = match strategy {
-            PickTargetStrategy::Close => {
-                let candidates: Vec<(Entity, &GlobalTransform)> = potential_targets
+    retarget_timer.0.tick(time.delta());
+    let periodic = retarget_timer.0.just_finished();
+
+    let candidates: Vec<Candidate> = potential_targets
+        .iter()
+        .map(|(entity, team, transform, health, attacks, slime_type)| {
+            let attacks = attacks.map_or(&[][..], |a| &a.0[..]);
+            Candidate {
+                entity,
+                team: *team,
+                position: transform.translation(),
+                health: health.0,
+                threat: attacks
                     .iter()
-                    .filter(|(_, target_team, _)| *target_team != team) // Step 1: filter to enemies
-                    .map(|(e, _, t)| (e, t))
-                    .choose_multiple(&mut rng, 3); // randomly sample up to 3
-
-                // Of our shortlist, pick whichever is closest.
-                // GlobalTransform uses .translation() method instead of .translation field.
-                candidates
-                    .into_iter()
-                    .min_by(|(_, a_transform), (_, b_transform)| {
-                        let dist_a = transform.translation().distance(a_transform.translation());
-                        let dist_b = transform.translation().distance(b_transform.translation());
-                        dist_a.partial_cmp(&dist_b).unwrap()
-                    })
-                    .map(|(target_entity, _)| target_entity)
+                    .map(|a| a.on_hit_effect.damage)
+                    .max()
+                    .unwrap_or(0),
+                backline: slime_type == Some(&SlimeType::Wizard)
+                    || attacks
+                        .iter()
+                        .any(|a| a.on_hit_effect.aoe_distance.is_some()),
             }
-            // Closest is handled by closest_target_system, which re-evaluates
-            // every frame. Skip here to avoid redundant work.
-            PickTargetStrategy::Closest => continue,
-        };
+        })
+        .collect();
+
+    let allies: Vec<AllyTarget> = seekers
+        .iter()
+        .filter_map(|(entity, _, team, transform, target, ..)| {
+            Some(AllyTarget {
+                entity,
+                team: *team,
+                position: transform.translation(),
+                target: target?.0,
+            })
+        })
+        .collect();
 
-        if let Some(target_entity) = target_entity {
-            commands.entity(entity).insert(TargetEntity(target_entity));
+    let blockers: Vec<Blocker> = obstacles
+        .iter()
+        .map(|(transform, obstacle)| obstacle.blocker(transform.translation()))
+        .collect();
+
+    let mut rng = rand::thread_rng();
+    for (entity, strategy, team, transform, current, threat, taunted, attacks) in &seekers {
+        if let Some(taunted) = taunted {
+            if current.map(|c| c.0) != Some(taunted.by) {
+                commands.entity(entity).insert(TargetEntity(taunted.by));
+            }
+            continue;
         }
-    }
-}
 
-/// Re-evaluates the closest enemy every frame for entities with PickTargetStrategy::Closest.
-///
-/// Unlike pick_target_system (which only runs when no target is assigned),
-/// this system runs every frame and overwrites TargetEntity. This means
-/// the entity always attacks whoever is nearest — if a closer enemy walks by,
-/// it switches targets immediately.
-///
-/// Note: there's no Without<TargetEntity> filter here. That's the whole point —
-/// we want to run even when a target already exists so we can replace it
-/// with a closer one.
-fn closest_target_system(
-    seekers: Query<
-        (Entity, &PickTargetStrategy, &Team, &GlobalTransform),
-        With<CanTarget>,
-    >,
-    potential_targets: Query<(Entity, &Team, &GlobalTransform), With<CanBeTargeted>>,
-    mut commands: Commands,
-) {
-    for (entity, strategy, team, transform) in seekers.iter() {
         // Can't filter on enum variant at the query level — Bevy queries
         // filter on component presence, not component values. So we check
-        // the variant in code and skip non-Closest entities.
-        if *strategy != PickTargetStrategy::Closest {
+        // the strategy's retarget rule in code.
+        let evaluate = match (current, strategy.retarget()) {
+            (None, _) => true,
+            (Some(_), Retarget::EveryFrame) => true,
+            (Some(_), Retarget::Periodically) => periodic,
+            (Some(_), Retarget::Never) => false,
+        };
+        if !evaluate {
             continue;
         }
 
-        // Find the single closest enemy. No random shortlist like Close —
-        // we check every potential target to guarantee we pick the actual closest.
-        let closest = potential_targets
-            .iter()
-            .filter(|(_, target_team, _)| *target_team != team)
-            .min_by(|(_, _, a), (_, _, b)| {
-                let dist_a = transform.translation().distance(a.translation());
-                let dist_b = transform.translation().distance(b.translation());
-                dist_a.partial_cmp(&dist_b).unwrap()
-            })
-            .map(|(e, _, _)| e);
-
-        if let Some(target) = closest {
+        let seeker = Seeker {
+            entity,
+            team: *team,
+            position: transform.translation(),
+            threat,
+            ranged: attacks.is_some_and(|a| a.0.iter().any(|attack| attack.is_ranged())),
+        };
+        let target = choose_target(
+            *strategy,
+            &seeker,
+            &candidates,
+            &allies,
+            &blockers,
+            &mut rng,
+        );
+        if let Some(target) = target.filter(|t| current.map(|c| c.0) != Some(*t)) {
             commands.entity(entity).insert(TargetEntity(target));
         }
     }
@@ -0,0 +1,141 @@
+// Positioning: how far from the fight each kind of unit wants to stand.
+//
+// Melee units walk right up to their target (move_to_target_system arrives
+// at MELEE_STOP_RADIUS). Ranged units carry a Positioning archetype instead,
+// and move_to_target_system asks ranged_step which way to go:
+//   1. If an enemy melee unit is inside retreat_distance, back away from it.
+//   2. Otherwise hold a distance band around the target. The band is a
+//      fraction of the unit's longest attack range, so range upgrades move
+//      the band out with it. With an obstacle in the way (obstacles.rs),
+//      close in instead; steering takes the unit round it into the open.
+//   3. Never step past the friendly front line: the most forward ally that
+//      isn't itself ranged, minus behind_front_line. With no front line left
+//      (all melee allies dead), ranged units are on their own.
+//
+// Units without the component (including bosses and spears) count as melee.
+
+use bevy::prelude::*;
+
+use crate::pick_target::Team;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Positioning {
+    Melee,
+    Caster,
+}
+
+pub struct PositioningDef {
+    pub positioning: Positioning,
+    /// Preferred distance to the target, as (min, max) fractions of the
+    /// unit's longest attack range. None walks right up to the target.
+    pub band: Option<(f32, f32)>,
+    /// Back away from enemy melee units closer than this.
+    pub retreat_distance: f32,
+    /// Stay at least this far behind the friendly front line.
+    pub behind_front_line: Option<f32>,
+}
+
+pub const POSITIONINGS: &[PositioningDef] = &[
+    PositioningDef {
+        positioning: Positioning::Melee,
+        band: None,
+        retreat_distance: 0.0,
+        behind_front_line: None,
+    },
+    // Wizards cast from 500 units, so this holds them 250-425 away.
+    PositioningDef {
+        positioning: Positioning::Caster,
+        band: Some((0.5, 0.85)),
+        retreat_distance: 150.0,
+        behind_front_line: Some(80.0),
+    },
+];
+
+impl Positioning {
+    pub fn def(self) -> &'static PositioningDef {
+        POSITIONINGS
+            .iter()
+            .find(|d| d.positioning == self)
+            .expect("every positioning has a POSITIONINGS entry")
+    }
+
+    pub fn is_ranged(self) -> bool {
+        self.def().band.is_some()
+    }
+}
+
+/// Which way along x a team advances. Players start on the left.
+pub fn forward(team: Team) -> f32 {
+    match team {
+        Team::Player => 1.0,
+        Team::Enemy => -1.0,
+    }
+}
+
+/// A unit on the field, as ranged_step sees it.
+pub struct FieldUnit {
+    pub team: Team,
+    pub position: Vec2,
+    pub ranged: bool,
+}
+
+/// Direction (unit length, or zero to stand still) a ranged unit should move
+/// this frame. `range` is its longest attack range; `in_sight` is whether it
+/// has line of sight to the target.
+pub fn ranged_step(
+    def: &PositioningDef,
+    team: Team,
+    position: Vec2,
+    target: Vec2,
+    range: f32,
+    in_sight: bool,
+    units: &[FieldUnit],
+) -> Vec2 {
+    let Some((min, max)) = def.band else {
+        return Vec2::ZERO;
+    };
+
+    let closest_melee_enemy = units
+        .iter()
+        .filter(|u| u.team != team && !u.ranged)
+        .map(|u| u.position)
+        .filter(|p| p.distance(position) < def.retreat_distance)
+        .min_by(|a, b| {
+            a.distance(position)
+                .partial_cmp(&b.distance(position))
+                .unwrap()
+        });
+
+    let distance = position.distance(target);
+    let mut step = if let Some(threat) = closest_melee_enemy {
+        (position - threat).normalize_or_zero()
+    } else if distance > range * max || !in_sight {
+        (target - position).normalize_or_zero()
+    } else if distance < range * min {
+        (position - target).normalize_or_zero()
+    } else {
+        Vec2::ZERO
+    };
+
+    if let Some(gap) = def.behind_front_line {
+        let fwd = forward(team);
+        let front_line = units
+            .iter()
+            .filter(|u| u.team == team && !u.ranged)
+            .map(|u| u.position.x * fwd)
+            .max_by(|a, b| a.partial_cmp(b).unwrap());
+        if let Some(front_line) = front_line {
+            let limit = front_line - gap;
+            let ahead = position.x * fwd - limit;
+            if ahead > 0.0 {
+                // Past the line: fall back behind it.
+                step.x = -fwd;
+            } else if ahead > -5.0 && step.x * fwd > 0.0 {
+                // Right at the line: slide along it instead of crossing.
+                step.x = 0.0;
+            }
+        }
+    }
+
+    step.normalize_or_zero()
+}
@@ -0,0 +1,490 @@
+// Relics: passive modifiers picked between depths.
+//
+// After "Risk deeper" the player is offered three random relics and keeps one
+// for the rest of the run. Relics are never offered twice in a run, and the
+// collection is lost when the run ends (win or lose, going home clears it).
+//
+// Relics don't touch the spawn functions directly. Instead, every player slime
+// carries an AppliedRelics list, and apply_relics_system patches its
+// components (KnownAttacks, BlockChance, Health) for any owned relic it hasn't
+// received yet. That covers survivors when a relic is picked, and anything
+// spawned later in the run (merged slimes) — each relic lands exactly once.
+//
+// Run-wide effects (merge frequency) adjust the MergeCheckTimer instead.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+
+use crate::armies::SlimeType;
+use crate::combat::{BlockChance, KnownAttacks};
+use crate::health::{Dying, Health, MaxHealth};
+use crate::pick_target::Team;
+use crate::special_abilities::{MergeCheckTimer, MergedSlime, MERGE_CHECK_INTERVAL};
+use crate::{CombatState, GameFont, GameState};
+
+pub struct RelicsPlugin;
+
+impl Plugin for RelicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Combat),
+            (init_run_relics, spawn_relic_hud),
+        )
+        .add_systems(OnExit(GameState::Combat), cleanup_run_relics)
+        .add_systems(OnEnter(CombatState::RelicChoice), setup_relic_choice)
+        .add_systems(
+            Update,
+            (apply_relics_system, merge_relic_system, update_relic_hud)
+                .run_if(in_state(GameState::Combat)),
+        )
+        .add_systems(
+            Update,
+            (relic_button_system, button_hover_system).run_if(in_state(CombatState::RelicChoice)),
+        );
+    }
+}
+
+/// How many relics are offered after each victory.
+const OFFER_SIZE: usize = 3;
+/// Tanks can never block more often than this, however many bonuses stack.
+const MAX_BLOCK_CHANCE: f32 = 0.9;
+
+const BUTTON_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const BUTTON_HOVER_COLOR: Color = Color::srgb(0.35, 0.35, 0.35);
+const BUTTON_PRESSED_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relic {
+    SharpGoop,
+    ThickIce,
+    Frostbite,
+    WideFrost,
+    HeartyGel,
+    Catalyst,
+}
+
+pub struct RelicDef {
+    pub relic: Relic,
+    pub name: &'static str,
+    /// Short glyph drawn on the HUD icon.
+    pub icon: &'static str,
+    pub color: Color,
+    pub description: &'static str,
+}
+
+pub const RELICS: &[RelicDef] = &[
+    RelicDef {
+        relic: Relic::SharpGoop,
+        name: "Sharp Goop",
+        icon: "+1",
+        color: Color::srgb(0.35, 0.65, 0.3),
+        description: "Normal slimes deal +1 damage",
+    },
+    RelicDef {
+        relic: Relic::ThickIce,
+        name: "Thick Ice",
+        icon: "TI",
+        color: Color::srgb(0.3, 0.55, 0.75),
+        description: "Tanks block 10% more often",
+    },
+    RelicDef {
+        relic: Relic::Frostbite,
+        name: "Frostbite",
+        icon: "FB",
+        color: Color::srgb(0.5, 0.75, 0.85),
+        description: "Tank hits stun 10% more often, 0.5s longer",
+    },
+    RelicDef {
+        relic: Relic::WideFrost,
+        name: "Wide Frost",
+        icon: "WF",
+        color: Color::srgb(0.55, 0.4, 0.75),
+        description: "Wizard spell splash radius +30%",
+    },
+    RelicDef {
+        relic: Relic::HeartyGel,
+        name: "Hearty Gel",
+        icon: "HG",
+        color: Color::srgb(0.75, 0.3, 0.3),
+        description: "All your slimes gain +2 max HP",
+    },
+    RelicDef {
+        relic: Relic::Catalyst,
+        name: "Catalyst",
+        icon: "CA",
+        color: Color::srgb(0.8, 0.6, 0.2),
+        description: "Merges happen twice as often (both armies)",
+    },
+];
+
+impl Relic {
+    pub fn def(self) -> &'static RelicDef {
+        RELICS
+            .iter()
+            .find(|d| d.relic == self)
+            .expect("every relic has a RELICS entry")
+    }
+}
+
+/// Relics owned this run, in the order they were picked.
+/// Inserted when a run starts, removed when it ends.
+#[derive(Resource, Default)]
+pub struct RunRelics(pub Vec<Relic>);
+
+impl RunRelics {
+    pub fn has(&self, relic: Relic) -> bool {
+        self.0.contains(&relic)
+    }
+
+    /// Every relic not owned yet, in RELICS order.
+    pub fn unowned(&self) -> Vec<Relic> {
+        RELICS
+            .iter()
+            .map(|d| d.relic)
+            .filter(|r| !self.has(*r))
+            .collect()
+    }
+}
+
+/// The relics already applied to one slime, so a relic is never applied twice.
+#[derive(Component, Default)]
+pub struct AppliedRelics(Vec<Relic>);
+
+#[derive(Component)]
+struct RelicButton(Relic);
+
+#[derive(Component)]
+struct RelicHud;
+
+fn init_run_relics(mut commands: Commands) {
+    commands.insert_resource(RunRelics::default());
+}
+
+fn cleanup_run_relics(mut commands: Commands, mut merge_timer: ResMut<MergeCheckTimer>) {
+    commands.remove_resource::<RunRelics>();
+    merge_timer
+        .0
+        .set_duration(Duration::from_secs_f32(MERGE_CHECK_INTERVAL));
+}
+
+/// Applies one relic to one slime. Relics that don't concern this kind of
+/// slime do nothing — they still get recorded as applied by the caller.
+fn apply_relic(
+    relic: Relic,
+    slime_type: Option<SlimeType>,
+    attacks: Option<&mut KnownAttacks>,
+    block: Option<&mut BlockChance>,
+    health: &mut Health,
+    max_health: &mut MaxHealth,
+) {
+    match (relic, slime_type) {
+        (Relic::SharpGoop, Some(SlimeType::Normal)) => {
+            for attack in attacks.into_iter().flat_map(|a| a.0.iter_mut()) {
+                attack.on_hit_effect.damage += 1;
+            }
+        }
+        (Relic::ThickIce, Some(SlimeType::Tank)) => {
+            if let Some(block) = block {
+                block.0 = (block.0 + 0.1).min(MAX_BLOCK_CHANCE);
+            }
+        }
+        (Relic::Frostbite, Some(SlimeType::Tank)) => {
+            for attack in attacks.into_iter().flat_map(|a| a.0.iter_mut()) {
+                let effect = &mut attack.on_hit_effect;
+                effect.stun_chance = (effect.stun_chance + 0.1).min(1.0);
+                effect.stun_duration += 0.5;
+            }
+        }
+        (Relic::WideFrost, Some(SlimeType::Wizard)) => {
+            for attack in attacks.into_iter().flat_map(|a| a.0.iter_mut()) {
+                if let Some(distance) = attack.on_hit_effect.aoe_distance.as_mut() {
+                    *distance *= 1.3;
+                }
+            }
+        }
+        (Relic::HeartyGel, _) => {
+            max_health.0 += 2;
+            health.0 += 2;
+        }
+        _ => {}
+    }
+}
+
+/// Brings every living player slime up to date with the run's relics.
+/// Merged slimes have no SlimeType, so only run-wide relics reach them.
+fn apply_relics_system(
+    mut commands: Commands,
+    relics: Option<Res<RunRelics>>,
+    mut slimes: Query<
+        (
+            Entity,
+            &Team,
+            Option<&SlimeType>,
+            Option<&mut KnownAttacks>,
+            Option<&mut BlockChance>,
+            &mut Health,
+            &mut MaxHealth,
+            Option<&mut AppliedRelics>,
+        ),
+        (Or<(With<SlimeType>, With<MergedSlime>)>, Without<Dying>),
+    >,
+) {
+    let Some(relics) = relics else {
+        return;
+    };
+    if relics.0.is_empty() {
+        return;
+    }
+
+    for (entity, team, slime_type, mut attacks, mut block, mut health, mut max_health, applied) in
+        &mut slimes
+    {
+        if *team != Team::Player {
+            continue;
+        }
+        let already: &[Relic] = applied.as_ref().map_or(&[], |a| &a.0);
+        let missing: Vec<Relic> = relics
+            .0
+            .iter()
+            .copied()
+            .filter(|r| !already.contains(r))
+            .collect();
+        if missing.is_empty() {
+            continue;
+        }
+
+        for &relic in &missing {
+            apply_relic(
+                relic,
+                slime_type.copied(),
+                attacks.as_deref_mut(),
+                block.as_deref_mut(),
+                &mut health,
+                &mut max_health,
+            );
+        }
+
+        match applied {
+            Some(mut applied) => applied.0.extend(missing),
+            None => {
+                commands.entity(entity).insert(AppliedRelics(missing));
+            }
+        }
+    }
+}
+
+/// Catalyst halves the time between merge checks for the rest of the run.
+fn merge_relic_system(relics: Option<Res<RunRelics>>, mut merge_timer: ResMut<MergeCheckTimer>) {
+    let Some(relics) = relics else {
+        return;
+    };
+    if !relics.is_changed() {
+        return;
+    }
+    let interval = if relics.has(Relic::Catalyst) {
+        MERGE_CHECK_INTERVAL / 2.0
+    } else {
+        MERGE_CHECK_INTERVAL
+    };
+    merge_timer
+        .0
+        .set_duration(Duration::from_secs_f32(interval));
+}
+
+/// Bottom-left row of relic icons, below the combat HUD's other text.
+fn spawn_relic_hud(mut commands: Commands) {
+    commands.spawn((
+        RelicHud,
+        DespawnOnExit(GameState::Combat),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(16.0),
+            bottom: Val::Px(16.0),
+            column_gap: Val::Px(6.0),
+            ..default()
+        },
+        Pickable::IGNORE,
+    ));
+}
+
+fn spawn_relic_icon(parent: &mut ChildSpawnerCommands, def: &RelicDef, font: &Handle<Font>) {
+    parent
+        .spawn((
+            Node {
+                width: Val::Px(40.0),
+                height: Val::Px(40.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BackgroundColor(def.color),
+            BorderColor::all(Color::WHITE),
+        ))
+        .with_child((
+            Text::new(def.icon),
+            TextFont {
+                font: font.clone(),
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        ));
+}
+
+/// Rebuilds the icon row whenever a relic is picked.
+fn update_relic_hud(
+    mut commands: Commands,
+    relics: Option<Res<RunRelics>>,
+    hud: Query<Entity, With<RelicHud>>,
+    game_font: Res<GameFont>,
+) {
+    let Some(relics) = relics else {
+        return;
+    };
+    if !relics.is_changed() {
+        return;
+    }
+    for entity in &hud {
+        commands
+            .entity(entity)
+            .despawn_related::<Children>()
+            .with_children(|row| {
+                for relic in &relics.0 {
+                    spawn_relic_icon(row, relic.def(), &game_font.0);
+                }
+            });
+    }
+}
+
+/// Rolls three relics the run doesn't own yet. If every relic is owned
+/// there's nothing to choose, so go straight on to camp.
+fn setup_relic_choice(
+    mut commands: Commands,
+    relics: Res<RunRelics>,
+    game_font: Res<GameFont>,
+    mut next_state: ResMut<NextState<CombatState>>,
+) {
+    let unowned = relics.unowned();
+    let mut rng = rand::thread_rng();
+    let offer: Vec<Relic> = unowned
+        .choose_multiple(&mut rng, OFFER_SIZE)
+        .copied()
+        .collect();
+    if offer.is_empty() {
+        next_state.set(CombatState::Camp);
+        return;
+    }
+
+    let font = game_font.0.clone();
+    commands
+        .spawn((
+            DespawnOnExit(CombatState::RelicChoice),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(24.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        ))
+        .with_children(|root| {
+            root.spawn((
+                Text::new("Choose a relic"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+            root.spawn((
+                Text::new("It lasts until the run ends"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+
+            root.spawn(Node {
+                column_gap: Val::Px(24.0),
+                ..default()
+            })
+            .with_children(|row| {
+                for relic in &offer {
+                    let def = relic.def();
+                    row.spawn((
+                        RelicButton(*relic),
+                        Button,
+                        Node {
+                            width: Val::Px(240.0),
+                            height: Val::Px(200.0),
+                            flex_direction: FlexDirection::Column,
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            row_gap: Val::Px(12.0),
+                            padding: UiRect::all(Val::Px(12.0)),
+                            ..default()
+                        },
+                        BackgroundColor(BUTTON_COLOR),
+                    ))
+                    .with_children(|card| {
+                        spawn_relic_icon(card, def, &font);
+                        card.spawn((
+                            Text::new(def.name),
+                            TextFont {
+                                font: font.clone(),
+                                font_size: 26.0,
+                                ..default()
+                            },
+                            TextColor(Color::WHITE),
+                        ));
+                        card.spawn((
+                            Text::new(def.description),
+                            TextFont {
+                                font: font.clone(),
+                                font_size: 18.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                            TextLayout::new_with_justify(Justify::Center),
+                        ));
+                    });
+                }
+            });
+        });
+}
+
+fn relic_button_system(
+    query: Query<(&Interaction, &RelicButton), Changed<Interaction>>,
+    mut relics: ResMut<RunRelics>,
+    mut next_state: ResMut<NextState<CombatState>>,
+) {
+    for (interaction, button) in &query {
+        if *interaction == Interaction::Pressed {
+            info!("Picked relic {}", button.0.def().name);
+            relics.0.push(button.0);
+            next_state.set(CombatState::Camp);
+            return;
+        }
+    }
+}
+
+fn button_hover_system(
+    mut query: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<Button>)>,
+) {
+    for (interaction, mut bg) in &mut query {
+        *bg = match interaction {
+            Interaction::Pressed => BUTTON_PRESSED_COLOR.into(),
+            Interaction::Hovered => BUTTON_HOVER_COLOR.into(),
+            Interaction::None => BUTTON_COLOR.into(),
+        };
+    }
+}
@@ -0,0 +1,185 @@
+// Risk curve: how much venturing deeper pays, and how to hedge it.
+//
+// Every "Risk deeper" multiplies the run's goop. The multiplier is built
+// from the tables below:
+//   - BASE_MULTIPLIER on an ordinary depth
+//   - a bigger multiplier when the next depth is a milestone (MILESTONES)
+//   - a streak bonus for each consecutive venture without resting at camp
+//
+// Insurance is bought at camp with run goop. It covers the next fight only:
+// if that fight is lost, a fraction of the run's goop is banked anyway.
+
+use bevy::prelude::*;
+
+use crate::end_round::GoopEarned;
+use crate::pick_target::Team;
+use crate::save_load::SaveData;
+use crate::{CombatState, GameState};
+
+pub struct RiskPlugin;
+
+impl Plugin for RiskPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Combat), init_run_risk)
+            .add_systems(OnExit(GameState::Combat), cleanup_run_risk)
+            .add_systems(OnEnter(CombatState::PostCombat), settle_insurance)
+            .add_systems(OnExit(CombatState::PostCombat), expire_insurance);
+    }
+}
+
+/// Multiplier for venturing into a depth that isn't a milestone.
+pub const BASE_MULTIPLIER: f32 = 1.2;
+
+pub struct Milestone {
+    pub depth: u32,
+    pub multiplier: f32,
+}
+
+/// Depths that pay extra for reaching them. Depths 10 and 20 are boss
+/// fights (see bosses.rs).
+pub const MILESTONES: &[Milestone] = &[
+    Milestone {
+        depth: 5,
+        multiplier: 1.5,
+    },
+    Milestone {
+        depth: 10,
+        multiplier: 2.0,
+    },
+    Milestone {
+        depth: 15,
+        multiplier: 2.5,
+    },
+    Milestone {
+        depth: 20,
+        multiplier: 3.0,
+    },
+];
+
+/// Added to the multiplier per consecutive venture without resting.
+pub const STREAK_BONUS_PER_VENTURE: f32 = 0.05;
+pub const MAX_STREAK_BONUS: f32 = 0.5;
+
+pub struct InsuranceTier {
+    /// Fraction of run goop banked if the insured fight is lost.
+    pub keep_fraction: f32,
+    /// Fraction of current run goop paid up front.
+    pub premium_fraction: f32,
+}
+
+pub const INSURANCE_TIERS: &[InsuranceTier] = &[
+    InsuranceTier {
+        keep_fraction: 0.25,
+        premium_fraction: 0.10,
+    },
+    InsuranceTier {
+        keep_fraction: 0.5,
+        premium_fraction: 0.25,
+    },
+];
+
+impl InsuranceTier {
+    pub fn premium(&self, run_goop: u32) -> u32 {
+        (run_goop as f32 * self.premium_fraction).ceil() as u32
+    }
+
+    pub fn payout(&self, run_goop: u32) -> u32 {
+        (run_goop as f32 * self.keep_fraction).floor() as u32
+    }
+}
+
+/// Per-run risk state. Inserted when a run starts, removed when it ends.
+#[derive(Resource, Default)]
+pub struct RunRisk {
+    /// Consecutive ventures without resting.
+    pub streak: u32,
+    /// Index into INSURANCE_TIERS covering the next fight, if any.
+    pub insurance: Option<usize>,
+}
+
+impl RunRisk {
+    pub fn insurance_tier(&self) -> Option<&'static InsuranceTier> {
+        self.insurance.and_then(|i| INSURANCE_TIERS.get(i))
+    }
+}
+
+/// The exact multiplier for one venture, broken into its parts so the UI
+/// can show where the number comes from.
+#[derive(Clone, Copy, Debug)]
+pub struct VentureQuote {
+    pub depth_multiplier: f32,
+    pub streak_bonus: f32,
+    pub milestone: bool,
+}
+
+impl VentureQuote {
+    pub fn multiplier(&self) -> f32 {
+        self.depth_multiplier + self.streak_bonus
+    }
+
+    pub fn apply(&self, goop: u32) -> u32 {
+        (goop as f32 * self.multiplier()).ceil() as u32
+    }
+
+    /// e.g. "goop x1.50 (depth 5 milestone) +0.10 streak -> 48"
+    pub fn describe(&self, next_depth: u32, goop: u32) -> String {
+        let mut text = format!("goop x{:.2}", self.depth_multiplier);
+        if self.milestone {
+            text.push_str(&format!(" (depth {next_depth} milestone)"));
+        }
+        if self.streak_bonus > 0.0 {
+            text.push_str(&format!(" +{:.2} streak", self.streak_bonus));
+        }
+        format!("{text} -> {}", self.apply(goop))
+    }
+}
+
+/// Quote for venturing into `next_depth` after `streak` consecutive ventures.
+pub fn venture_quote(next_depth: u32, streak: u32) -> VentureQuote {
+    let milestone = MILESTONES.iter().find(|m| m.depth == next_depth);
+    VentureQuote {
+        depth_multiplier: milestone.map_or(BASE_MULTIPLIER, |m| m.multiplier),
+        streak_bonus: (streak as f32 * STREAK_BONUS_PER_VENTURE).min(MAX_STREAK_BONUS),
+        milestone: milestone.is_some(),
+    }
+}
+
+fn init_run_risk(mut commands: Commands) {
+    commands.insert_resource(RunRisk::default());
+}
+
+fn cleanup_run_risk(mut commands: Commands) {
+    commands.remove_resource::<RunRisk>();
+}
+
+/// On a defeat, insurance banks its share of the run's goop before the
+/// screen fades home. The policy itself stays visible to the PostCombat
+/// screen and expires when leaving it.
+fn settle_insurance(
+    teams: Query<&Team>,
+    risk: Option<Res<RunRisk>>,
+    goop_earned: Res<GoopEarned>,
+    mut save_data: ResMut<SaveData>,
+) {
+    let Some(tier) = risk.and_then(|r| r.insurance_tier()) else {
+        return;
+    };
+
+    // Same victory rule as enter_post_combat.
+    let has_player = teams.iter().any(|t| *t == Team::Player);
+    let has_enemy = teams.iter().any(|t| *t == Team::Enemy);
+    if has_player && !has_enemy {
+        return;
+    }
+
+    let payout = tier.payout(goop_earned.0);
+    info!("Insurance paid out {payout} goop");
+    save_data.goop += payout;
+}
+
+/// Insurance covers one fight, win or lose.
+fn expire_insurance(risk: Option<ResMut<RunRisk>>) {
+    if let Some(mut risk) = risk {
+        risk.insurance = None;
+    }
+}
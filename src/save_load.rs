@@ -2,20 +2,36 @@ use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use crate::armies::Army;
+use crate::casino::CasinoStats;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::share_code::SHARE_CODE_FILE_NAME;
+use crate::upgrades::Upgrades;
 use crate::GameState;
 
 pub struct SaveLoadPlugin;
 
 impl Plugin for SaveLoadPlugin {
     fn build(&self, app: &mut App) {
+        // Only pick a backend if nothing else already did. Tests and the
+        // simulator insert their own ActiveSaveStorage before adding this plugin.
+        if !app.world().contains_resource::<ActiveSaveStorage>() {
+            app.insert_resource(ActiveSaveStorage::from_args(std::env::args()));
+        }
+
         app.add_systems(PreStartup, load_save_data)
-            .add_systems(OnEnter(GameState::Home), save_game)
-            .add_systems(OnExit(GameState::Home), save_game);
+            .add_systems(OnEnter(GameState::Home), save_game.in_set(SaveGame))
+            .add_systems(OnExit(GameState::Home), save_game.in_set(SaveGame));
     }
 }
 
-fn save_game(save_data: Res<SaveData>) {
-    save_to_disk(&save_data);
+/// The systems that write SaveData out. Anything that changes SaveData on
+/// the same state transition should run .before(SaveGame) so the change is
+/// saved.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SaveGame;
+
+fn save_game(save_data: Res<SaveData>, mut storage: ResMut<ActiveSaveStorage>) {
+    save_to_storage(&mut storage, &save_data);
 }
 
 /// The player's persistent save data.
@@ -41,6 +57,12 @@ pub struct SaveData {
     pub army: Army,
     #[serde(default)]
     pub goop: u32,
+    #[serde(default)]
+    pub settings: Settings,
+    #[serde(default)]
+    pub upgrades: Upgrades,
+    #[serde(default)]
+    pub stats: Stats,
 }
 
 impl Default for SaveData {
@@ -48,42 +70,137 @@ impl Default for SaveData {
         Self {
             army: Army::default(),
             goop: 0,
+            settings: Settings::default(),
+            upgrades: Upgrades::default(),
+            stats: Stats::default(),
         }
     }
 }
 
-// =============================================================================
-// Storage backend: Native (macOS, Linux, Windows)
+/// Player-chosen game rules and preferences, toggled on the Home screen.
+/// Everything here defaults to off so existing saves play exactly as before.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Settings {
+    /// Casualties rule: slimes that die (or merge) during a run are removed
+    /// from the saved army when the run ends.
+    #[serde(default)]
+    pub casualties: bool,
+    /// Hides the predicted win chance on the PostCombat screen, for players
+    /// who'd rather not know.
+    #[serde(default)]
+    pub hide_odds: bool,
+}
+
+/// Lifetime records kept with the save. Purely informational — nothing
+/// here affects gameplay.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Stats {
+    #[serde(default)]
+    pub casino: CasinoStats,
+}
 
+// =============================================================================
+// Storage backends
 //
-// #[cfg(...)] is Rust's conditional compilation. The compiler completely excludes
-// code that doesn't match the current target — it's not an if-statement at
-// runtime, the code literally doesn't exist in the binary. Zero cost.
+// Every place the save file can live implements SaveStorage. The active
+// backend is held in the ActiveSaveStorage resource, so systems never care
+// whether they're writing to disk, to memory, or nowhere at all.
 //
-// For native platforms, we save to the filesystem using the `dirs` crate to
-// find the right directory for each OS.
+// Box<dyn SaveStorage> is a "trait object": a pointer to some type that
+// implements the trait, where the concrete type is only known at runtime.
+// That's what lets a test swap in MemoryStorage without any #[cfg] tricks.
+// The Send + Sync bounds are required because Bevy resources can be read
+// from any thread in the system scheduler.
 // =============================================================================
 
+pub trait SaveStorage: Send + Sync + 'static {
+    /// Reads the stored save, or returns None if there isn't one (or it's unreadable).
+    fn load(&self) -> Option<SaveData>;
+
+    /// Persists the given save. Backends log their own errors instead of
+    /// returning them — a failed save should never crash the game.
+    fn save(&mut self, save_data: &SaveData);
+
+    /// Where share codes may be written to and read from as a file, or None
+    /// if this backend keeps nothing on disk (read-only, in-memory, browser).
+    fn share_code_path(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+}
+
+/// Resource holding whichever storage backend this run uses.
+#[derive(Resource)]
+pub struct ActiveSaveStorage(pub Box<dyn SaveStorage>);
+
+impl ActiveSaveStorage {
+    pub fn new(storage: impl SaveStorage) -> Self {
+        Self(Box::new(storage))
+    }
+
+    /// Picks a backend from command-line flags:
+    /// - `--kiosk`: load the normal save but never write it back (demo machines)
+    /// - `--no-save`: start fresh and keep everything in memory (playtesting)
+    ///
+    /// Anything else gets the platform default.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        for arg in args {
+            match arg.as_str() {
+                "--kiosk" => {
+                    let inner = Self::platform_default();
+                    return Self::new(ReadOnlyStorage(inner.0));
+                }
+                "--no-save" => return Self::new(MemoryStorage::default()),
+                _ => {}
+            }
+        }
+        Self::platform_default()
+    }
+
+    /// The backend a normal build uses: the save file on native, and
+    /// localStorage in the browser.
+    ///
+    /// #[cfg(...)] is Rust's conditional compilation. The compiler completely
+    /// excludes code that doesn't match the current target — it's not an
+    /// if-statement at runtime, the code literally doesn't exist in the binary.
+    pub fn platform_default() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Self::new(FileStorage::default())
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            Self::new(BrowserStorage)
+        }
+    }
+}
+
+/// Saves to a RON file on the local filesystem (macOS, Linux, Windows).
 #[cfg(not(target_arch = "wasm32"))]
-mod storage {
-    use super::SaveData;
-    use bevy::prelude::*;
+pub struct FileStorage {
+    pub path: std::path::PathBuf,
+}
 
-    /// Returns the path to the save file in the project root (save.ron).
-    fn save_file_path() -> Option<std::path::PathBuf> {
-        Some(std::path::PathBuf::from("save.ron"))
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for FileStorage {
+    /// The save file lives in the project root (save.ron).
+    fn default() -> Self {
+        Self {
+            path: std::path::PathBuf::from("save.ron"),
+        }
     }
+}
 
-    /// Reads SaveData from the filesystem, or returns None if no save exists.
-    pub fn load() -> Option<SaveData> {
-        let path = save_file_path()?;
+#[cfg(not(target_arch = "wasm32"))]
+impl SaveStorage for FileStorage {
+    fn load(&self) -> Option<SaveData> {
+        let path = &self.path;
 
         if !path.exists() {
             info!("No save file found at {:?}. Starting fresh.", path);
             return None;
         }
 
-        match std::fs::read_to_string(&path) {
+        match std::fs::read_to_string(path) {
             Ok(contents) => match ron::from_str::<SaveData>(&contents) {
                 Ok(data) => {
                     info!("Loaded save data from {:?}: {:?}", path, data);
@@ -103,12 +220,8 @@ mod storage {
         }
     }
 
-    /// Writes SaveData to the filesystem.
-    pub fn save(save_data: &SaveData) {
-        let Some(path) = save_file_path() else {
-            error!("Could not determine save file path!");
-            return;
-        };
+    fn save(&mut self, save_data: &SaveData) {
+        let path = &self.path;
 
         // Create the directory if it doesn't exist.
         // create_dir_all is like `mkdir -p` — creates all parent dirs too.
@@ -124,7 +237,7 @@ mod storage {
         let pretty = ron::ser::PrettyConfig::default();
         match ron::ser::to_string_pretty(save_data, pretty) {
             Ok(serialized) => {
-                if let Err(e) = std::fs::write(&path, serialized) {
+                if let Err(e) = std::fs::write(path, serialized) {
                     error!("Failed to write save file: {}", e);
                 } else {
                     info!("Game saved to {:?}", path);
@@ -133,27 +246,21 @@ mod storage {
             Err(e) => error!("Failed to serialize save data: {}", e),
         }
     }
-}
 
-// =============================================================================
-// Storage backend: WASM (browser)
-//
-// When you eventually compile for the web with `cargo build --target wasm32-unknown-unknown`,
-// this block will be used instead of the native one above.
-//
-// Browsers don't have a filesystem, but they do have localStorage — a simple
-// key-value store that persists across page reloads. Perfect for save data.
-//
-// You'll need to add `web-sys` or `gloo-storage` as a dependency when you
-// get to this point. For now, this is a placeholder showing the structure.
-// =============================================================================
+    /// Next to the save file.
+    fn share_code_path(&self) -> Option<std::path::PathBuf> {
+        Some(self.path.with_file_name(SHARE_CODE_FILE_NAME))
+    }
+}
 
+/// Browser localStorage — a simple key-value store that persists across page
+/// reloads. Placeholder until we add `web-sys` or `gloo-storage`.
 #[cfg(target_arch = "wasm32")]
-mod storage {
-    use super::SaveData;
-    use bevy::prelude::*;
+pub struct BrowserStorage;
 
-    pub fn load() -> Option<SaveData> {
+#[cfg(target_arch = "wasm32")]
+impl SaveStorage for BrowserStorage {
+    fn load(&self) -> Option<SaveData> {
         // TODO: When targeting WASM, implement using web-sys or gloo-storage:
         //
         // let window = web_sys::window()?;
@@ -165,7 +272,7 @@ mod storage {
         None
     }
 
-    pub fn save(save_data: &SaveData) {
+    fn save(&mut self, _save_data: &SaveData) {
         // TODO: When targeting WASM, implement using web-sys or gloo-storage:
         //
         // let window = web_sys::window().expect("no window");
@@ -178,31 +285,62 @@ mod storage {
     }
 }
 
+/// Keeps the save in memory only. Used by tests and the battle simulator so
+/// they never touch the real save file; `data` can be inspected afterwards.
+#[derive(Default)]
+pub struct MemoryStorage {
+    pub data: Option<SaveData>,
+}
+
+impl SaveStorage for MemoryStorage {
+    fn load(&self) -> Option<SaveData> {
+        self.data.clone()
+    }
+
+    fn save(&mut self, save_data: &SaveData) {
+        self.data = Some(save_data.clone());
+    }
+}
+
+/// Loads from another backend but silently drops every write. For demo and
+/// kiosk builds, where each visitor should start from the same save.
+pub struct ReadOnlyStorage(pub Box<dyn SaveStorage>);
+
+impl SaveStorage for ReadOnlyStorage {
+    fn load(&self) -> Option<SaveData> {
+        self.0.load()
+    }
+
+    fn save(&mut self, _save_data: &SaveData) {
+        info!("Read-only storage: skipping save.");
+    }
+}
+
 // =============================================================================
 // Public API — these are what the rest of the game calls.
-// They delegate to whichever storage backend was compiled in.
+// They delegate to whichever storage backend is in ActiveSaveStorage.
 // =============================================================================
 
 /// Startup system: loads the save file, or creates default save data if none exists.
 ///
 /// Inserts SaveData as a Bevy Resource so any system can access it via
 /// Res<SaveData> (read-only) or ResMut<SaveData> (read-write).
-fn load_save_data(mut commands: Commands) {
-    let save_data = storage::load().unwrap_or_default();
+fn load_save_data(mut commands: Commands, storage: Res<ActiveSaveStorage>) {
+    let save_data = storage.0.load().unwrap_or_default();
     commands.insert_resource(save_data);
 }
 
-/// Saves the current SaveData to disk (or localStorage on web).
+/// Saves the current SaveData through the active storage backend.
 ///
 /// This is a plain function, not a system. Call it from systems at specific
 /// moments (after a battle, when the player quits, etc.) rather than every frame.
 ///
 /// Example usage from a system:
 /// ```rust
-/// fn end_of_battle(save_data: Res<SaveData>) {
-///     save_to_disk(&save_data);
+/// fn end_of_battle(save_data: Res<SaveData>, mut storage: ResMut<ActiveSaveStorage>) {
+///     save_to_storage(&mut storage, &save_data);
 /// }
 /// ```
-pub fn save_to_disk(save_data: &SaveData) {
-    storage::save(save_data);
+pub fn save_to_storage(storage: &mut ActiveSaveStorage, save_data: &SaveData) {
+    storage.0.save(save_data);
 }
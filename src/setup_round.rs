@@ -141,7 +141,7 @@ fn stun_timer_system(
 
 /// Spawns floating "STUNNED!" text when any entity first receives a StunTimer.
 /// Added<StunTimer> fires once on the frame the component is inserted, covering
-/// all stun sources (ice blast, wall slam, etc.) without each source needing
+/// all stun sources (ice blast, tank hits, etc.) without each source needing
 /// to spawn the text itself.
 fn on_add_stun_system(
     query: Query<&GlobalTransform, Added<StunTimer>>,
@@ -0,0 +1,273 @@
+// Share codes: compact text strings for trading army builds in chat.
+//
+// A code looks like `NTMO1A-eJyrVkrOz0nNS...`:
+//   - `NTMO`  fixed prefix so we can tell a share code from random text
+//   - `1`     format version, bumped if the layout below ever changes
+//   - `A`/`S` what's inside: an Army or a whole SaveData
+//   - `-`     separator
+//   - payload: URL-safe base64 of [crc32 (4 bytes, big-endian)] + [DEFLATE(RON)]
+//
+// The checksum covers the compressed bytes, so a typo or a hand-edited
+// number is caught before we even try to decompress.
+
+use std::fmt;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::armies::Army;
+use crate::save_load::SaveData;
+
+const PREFIX: &str = "NTMO";
+const VERSION: char = '1';
+const KIND_ARMY: char = 'A';
+const KIND_SAVE: char = 'S';
+
+/// A real save is a few hundred bytes of RON. Anything that inflates past
+/// this is either garbage or a deliberate zip bomb, so we stop reading.
+const MAX_DECOMPRESSED_BYTES: u64 = 64 * 1024;
+
+/// Upper bound on each unit count in an imported army. Each extra slime of
+/// a type costs more than the last, so no real army gets near this, and
+/// the spawner still places a full one in a few seconds.
+const MAX_IMPORTED_COUNT: u32 = 50;
+
+/// Name of the file exports fall back to when the clipboard is out, and
+/// "Load code file" reads. The save backend decides where it lives
+/// (SaveStorage::share_code_path).
+pub const SHARE_CODE_FILE_NAME: &str = "share_code.txt";
+
+/// What a decoded share code contained.
+pub enum ShareCode {
+    Army(Army),
+    Save(SaveData),
+}
+
+/// Why a share code was rejected. Each variant's Display text is shown to
+/// the player as-is, so keep them short and friendly.
+#[derive(Debug)]
+pub enum ShareCodeError {
+    Empty,
+    NotAShareCode,
+    UnsupportedVersion(char),
+    UnknownKind(char),
+    BadEncoding,
+    ChecksumMismatch,
+    Corrupted,
+    InvalidData(String),
+}
+
+impl fmt::Display for ShareCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShareCodeError::Empty => write!(f, "No code found"),
+            ShareCodeError::NotAShareCode => write!(f, "That isn't a share code"),
+            ShareCodeError::UnsupportedVersion(v) => {
+                write!(f, "Code is from an unsupported version ({v})")
+            }
+            ShareCodeError::UnknownKind(k) => write!(f, "Unknown code type '{k}'"),
+            ShareCodeError::BadEncoding => write!(f, "Code contains invalid characters"),
+            ShareCodeError::ChecksumMismatch => write!(f, "Code is damaged or was edited"),
+            ShareCodeError::Corrupted => write!(f, "Code is corrupted"),
+            ShareCodeError::InvalidData(reason) => write!(f, "Code rejected: {reason}"),
+        }
+    }
+}
+
+pub fn encode_army(army: &Army) -> String {
+    encode(KIND_ARMY, army)
+}
+
+pub fn encode_save(save_data: &SaveData) -> String {
+    encode(KIND_SAVE, save_data)
+}
+
+/// Parses a share code. Whitespace around the code (from sloppy copy-paste)
+/// is ignored. The decoded contents are validated before being returned, so
+/// callers can apply them directly.
+pub fn decode(code: &str) -> Result<ShareCode, ShareCodeError> {
+    let code = code.trim();
+    if code.is_empty() {
+        return Err(ShareCodeError::Empty);
+    }
+
+    let rest = code
+        .strip_prefix(PREFIX)
+        .ok_or(ShareCodeError::NotAShareCode)?;
+    let mut chars = rest.chars();
+    let version = chars.next().ok_or(ShareCodeError::NotAShareCode)?;
+    let kind = chars.next().ok_or(ShareCodeError::NotAShareCode)?;
+    let payload = chars
+        .as_str()
+        .strip_prefix('-')
+        .ok_or(ShareCodeError::NotAShareCode)?;
+
+    if version != VERSION {
+        return Err(ShareCodeError::UnsupportedVersion(version));
+    }
+
+    match kind {
+        KIND_ARMY => {
+            let army: Army = decode_payload(payload)?;
+            validate_army(&army)?;
+            Ok(ShareCode::Army(army))
+        }
+        KIND_SAVE => {
+            let save_data: SaveData = decode_payload(payload)?;
+            validate_army(&save_data.army)?;
+            if !save_data.upgrades.is_valid() {
+                return Err(ShareCodeError::InvalidData(
+                    "upgrade level above its cap".to_string(),
+                ));
+            }
+            Ok(ShareCode::Save(save_data))
+        }
+        other => Err(ShareCodeError::UnknownKind(other)),
+    }
+}
+
+fn encode<T: Serialize>(kind: char, value: &T) -> String {
+    // Compact (non-pretty) RON — whitespace would just be compressed away
+    // anyway, but there's no reason to feed it to the compressor.
+    let ron = ron::to_string(value).expect("save types always serialize");
+
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(ron.as_bytes())
+        .expect("writing to a Vec can't fail");
+    let compressed = encoder.finish().expect("writing to a Vec can't fail");
+
+    let mut bytes = crc32fast::hash(&compressed).to_be_bytes().to_vec();
+    bytes.extend_from_slice(&compressed);
+
+    format!("{PREFIX}{VERSION}{kind}-{}", URL_SAFE_NO_PAD.encode(bytes))
+}
+
+fn decode_payload<T: DeserializeOwned>(payload: &str) -> Result<T, ShareCodeError> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|_| ShareCodeError::BadEncoding)?;
+    if bytes.len() < 4 {
+        return Err(ShareCodeError::Corrupted);
+    }
+
+    let (checksum, compressed) = bytes.split_at(4);
+    let expected = u32::from_be_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]);
+    if crc32fast::hash(compressed) != expected {
+        return Err(ShareCodeError::ChecksumMismatch);
+    }
+
+    let mut ron = String::new();
+    DeflateDecoder::new(compressed)
+        .take(MAX_DECOMPRESSED_BYTES)
+        .read_to_string(&mut ron)
+        .map_err(|_| ShareCodeError::Corrupted)?;
+
+    ron::from_str(&ron).map_err(|_| ShareCodeError::Corrupted)
+}
+
+/// Rejects armies that parse fine but couldn't exist in a real game. The
+/// checksum only catches typos, so this is what stops hand-edited codes.
+///
+/// The per-type stats in an Army never change in play (upgrades are kept
+/// separately and folded in at spawn), so they must match the defaults
+/// exactly. Only the counts are the player's own.
+fn validate_army(army: &Army) -> Result<(), ShareCodeError> {
+    let invalid = |reason: &str| Err(ShareCodeError::InvalidData(reason.to_string()));
+
+    let counts = [army.normal.count, army.tanks.count, army.wizards.count];
+    if counts.iter().any(|&c| c > MAX_IMPORTED_COUNT) {
+        return invalid("too many slimes");
+    }
+
+    let base = Army::default();
+    let (normal, tanks, wizards) = (&army.normal, &army.tanks, &army.wizards);
+    let stats_match = normal.hp == base.normal.hp
+        && tanks.hp == base.tanks.hp
+        && tanks.block_chance == base.tanks.block_chance
+        && tanks.stun_chance == base.tanks.stun_chance
+        && wizards.hp == base.wizards.hp
+        && wizards.spell_range == base.wizards.spell_range
+        && wizards.aoe_damage == base.wizards.aoe_damage
+        && wizards.spear_knockback == base.wizards.spear_knockback;
+    if !stats_match {
+        return invalid("slime stats have been edited");
+    }
+
+    let merging = &army.merging;
+    if !(0.0..=1.0).contains(&merging.chance) {
+        return invalid("chances must be between 0 and 1");
+    }
+    if !merging.radius.is_finite() || merging.radius < 0.0 {
+        return invalid("merge radius can't be negative");
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// Clipboard and file transport
+//
+// arboard talks to the OS clipboard on macOS, Windows, and X11/Wayland.
+// It has no browser support, so the WASM build reports that instead.
+//
+// On Linux the copied text lives in our process: whoever pastes asks the
+// Clipboard that set it, and dropping that Clipboard drops the text. So
+// each Clipboard we open is kept (one per thread, since arboard's isn't
+// Send everywhere) for the rest of the run.
+// =============================================================================
+
+#[cfg(not(target_arch = "wasm32"))]
+thread_local! {
+    static CLIPBOARD: std::cell::RefCell<Option<arboard::Clipboard>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Runs `f` on the kept Clipboard, opening it first if needed.
+#[cfg(not(target_arch = "wasm32"))]
+fn with_clipboard<T>(
+    f: impl FnOnce(&mut arboard::Clipboard) -> Result<T, arboard::Error>,
+) -> Result<T, String> {
+    CLIPBOARD.with_borrow_mut(|slot| {
+        let clipboard = match slot {
+            Some(clipboard) => clipboard,
+            None => slot.insert(arboard::Clipboard::new().map_err(|e| e.to_string())?),
+        };
+        f(clipboard).map_err(|e| e.to_string())
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    with_clipboard(|clipboard| clipboard.set_text(text.to_string()))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_clipboard() -> Result<String, String> {
+    with_clipboard(|clipboard| clipboard.get_text())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn copy_to_clipboard(_text: &str) -> Result<(), String> {
+    Err("clipboard isn't available in the browser yet".to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn read_clipboard() -> Result<String, String> {
+    Err("clipboard isn't available in the browser yet".to_string())
+}
+
+pub fn write_code_file(path: &Path, code: &str) -> Result<(), String> {
+    std::fs::write(path, code).map_err(|e| e.to_string())
+}
+
+pub fn read_code_file(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| e.to_string())
+}
@@ -0,0 +1,693 @@
+// Side bets: wager banked goop on how the next battle plays out.
+//
+// While the READY countdown runs, a row of propositions appears at the top
+// of the screen. Each click stakes another STAKE_STEP goop from the bank
+// (SaveData.goop, not this run's winnings). Payouts are priced from the
+// same fight simulator the odds display uses, minus a small house edge —
+// long shots pay a lot, sure things barely pay at all.
+//
+// During the fight we record the handful of events bets care about
+// (RoundEvents), and when the round ends every bet is settled against them
+// and the results are listed on the PostCombat screen.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::tasks::futures::check_ready;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use rand::seq::IteratorRandom;
+
+use crate::armies::{Army, MergeRules, SlimeType};
+use crate::health::{Dying, Health};
+use crate::merge_recipes::{find_recipe, MergeInput, MergeKind};
+use crate::odds::{
+    army_units, simulate_fight, snapshot_team, wave_units, CombatantQuery, FightOutcome, SimUnit,
+    SpearQuery,
+};
+use crate::pick_target::Team;
+use crate::save_load::SaveData;
+use crate::spawn_slimes::SlimesToSpawn;
+use crate::special_abilities::{
+    pair_merge_chance, MergeCheckTimer, SlimesMergedEvent, TeamMergeRules,
+};
+use crate::{ArenaBounds, CombatState, GameFont, GameState};
+
+pub struct SideBetsPlugin;
+
+impl Plugin for SideBetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(on_slimes_merged)
+            .add_systems(OnEnter(CombatState::PreCombat), open_betting)
+            .add_systems(OnEnter(CombatState::DuringCombat), start_round_events)
+            .add_systems(OnEnter(CombatState::PostCombat), settle_bets)
+            .add_systems(OnExit(CombatState::PostCombat), cleanup_bets)
+            .add_systems(OnExit(GameState::Combat), cleanup_bets)
+            .add_systems(
+                Update,
+                (
+                    poll_pricing_task,
+                    mark_enemy_system,
+                    bet_button_system,
+                    update_bet_labels_system,
+                    button_hover_system,
+                )
+                    .run_if(in_state(CombatState::PreCombat)),
+            )
+            .add_systems(
+                Update,
+                record_first_enemy_death
+                    .run_if(in_state(CombatState::DuringCombat))
+                    .run_if(resource_exists::<RoundEvents>),
+            );
+    }
+}
+
+/// Goop added to a bet per click.
+const STAKE_STEP: u32 = 5;
+/// Fraction of a fair payout the house keeps. At 0.05, a coin flip pays x1.9.
+const HOUSE_EDGE: f32 = 0.05;
+/// Propositions offered each battle: one betting button each.
+const PROPOSITION_COUNT: usize = 6;
+/// Fights simulated to price the propositions.
+const PRICING_SIMULATIONS: usize = 300;
+/// Probabilities are clamped to this range before pricing, so nothing is
+/// ever a free lunch or an absurd jackpot because of simulator noise.
+const MIN_PROBABILITY: f32 = 0.03;
+const MAX_PROBABILITY: f32 = 0.97;
+/// WoundedOnly armies only merge once both slimes are hurt; count them as
+/// hurt for this share of the fight.
+const WOUNDED_FRACTION: f32 = 0.5;
+
+const BUTTON_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const BUTTON_HOVER_COLOR: Color = Color::srgb(0.35, 0.35, 0.35);
+const BUTTON_PRESSED_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
+const WIN_COLOR: Color = Color::srgb(0.4, 0.9, 0.2);
+const LOSE_COLOR: Color = Color::srgb(0.9, 0.3, 0.3);
+const MARK_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+
+/// Something to bet on. Thresholds are picked when betting opens so the
+/// proposition is close to a coin flip for the current matchup.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Proposition {
+    Win,
+    Lose,
+    /// More than this many player slimes survive.
+    SurvivorsAbove(u32),
+    /// The battle is over in under this many seconds.
+    FasterThan(f32),
+    /// Any two slimes, on either side, merge.
+    MergeHappens,
+    /// The enemy marked with "$" is the first enemy to die.
+    MarkedEnemyDiesFirst,
+}
+
+impl Proposition {
+    pub fn label(&self) -> String {
+        match self {
+            Proposition::Win => "Win".to_string(),
+            Proposition::Lose => "Lose".to_string(),
+            Proposition::SurvivorsAbove(n) => format!("{}+ survive", n + 1),
+            Proposition::FasterThan(secs) => format!("Under {}s", *secs as u32),
+            Proposition::MergeHappens => "A merge".to_string(),
+            Proposition::MarkedEnemyDiesFirst => "$ dies first".to_string(),
+        }
+    }
+}
+
+/// One proposition on offer, with its price and what the player has staked.
+#[derive(Clone, Debug)]
+pub struct SideBet {
+    pub proposition: Proposition,
+    /// Total returned per goop staked if the bet wins (stake included).
+    pub payout: f32,
+    pub stake: u32,
+}
+
+/// The betting slip for the current battle. Created when PreCombat starts;
+/// `bets` stays empty until pricing finishes.
+#[derive(Resource, Default)]
+pub struct SideBets {
+    pub bets: Vec<SideBet>,
+    /// The enemy targeted by MarkedEnemyDiesFirst.
+    pub marked_enemy: Option<Entity>,
+}
+
+impl SideBets {
+    pub fn total_staked(&self) -> u32 {
+        self.bets.iter().map(|b| b.stake).sum()
+    }
+}
+
+/// The facts bets are settled against, recorded during DuringCombat.
+#[derive(Resource)]
+pub struct RoundEvents {
+    pub started_at: f32,
+    pub merge_happened: bool,
+    pub first_enemy_death: Option<Entity>,
+}
+
+/// Background pricing run, despawned with the betting panel.
+#[derive(Component)]
+struct PricingTask(Task<Vec<SideBet>>);
+
+/// Placed on the enemy the "$ dies first" bet is about.
+#[derive(Component)]
+struct MarkedEnemy;
+
+#[derive(Component)]
+struct BetButton(usize);
+
+#[derive(Component)]
+struct BetButtonText(usize);
+
+#[derive(Component)]
+struct BetBankText;
+
+/// One team's side of the "A merge" pricing, read from the same rules
+/// check_merge_system rolls against.
+struct TeamMergeOdds {
+    /// Same-team pairs that have a recipe (find_recipe).
+    recipe_pairs: usize,
+    /// Chance per check that one of those pairs merges, once in range.
+    pair_chance: f32,
+    /// Share of those pairs in range at any moment: the merge radius's
+    /// circle against the arena, as if slimes stood anywhere in it.
+    in_range: f32,
+}
+
+/// Everything the "A merge" bet is priced from.
+struct MergeOdds {
+    teams: [TeamMergeOdds; 2],
+    /// Seconds between merge checks (MergeCheckTimer, relics included).
+    check_interval: f32,
+}
+
+/// What open_betting reads to price "A merge".
+#[derive(SystemParam)]
+struct MergeContext<'w, 's> {
+    /// Field units with what check_merge_system needs to pair them up.
+    candidates: Query<
+        'w,
+        's,
+        (
+            &'static Team,
+            Option<&'static SlimeType>,
+            Option<&'static MergeKind>,
+        ),
+        (With<Health>, Without<Dying>, Without<ChildOf>),
+    >,
+    rules: Res<'w, TeamMergeRules>,
+    timer: Res<'w, MergeCheckTimer>,
+    arena: Res<'w, ArenaBounds>,
+}
+
+/// Snapshots both sides — units already on the field plus any still queued
+/// in SlimesToSpawn — and prices the bets in the background.
+fn open_betting(
+    mut commands: Commands,
+    game_font: Res<GameFont>,
+    save_data: Res<SaveData>,
+    slimes_to_spawn: Option<Res<SlimesToSpawn>>,
+    units: CombatantQuery,
+    spears: SpearQuery,
+    merge_context: MergeContext,
+) {
+    let mut players = snapshot_team(Team::Player, &units, &spears);
+    let mut enemies = snapshot_team(Team::Enemy, &units, &spears);
+    let mut inputs = [Team::Player, Team::Enemy].map(|team| {
+        merge_context
+            .candidates
+            .iter()
+            .filter(|(t, ..)| **t == team)
+            .filter_map(|(_, slime_type, kind)| MergeInput::of(slime_type, kind))
+            .collect::<Vec<_>>()
+    });
+    // spawn_slimes_system copies a queued army's rules into TeamMergeRules
+    // once it starts spawning, which may not have happened yet.
+    let mut rules = [merge_context.rules.player, merge_context.rules.enemy];
+    if let Some(pending) = &slimes_to_spawn {
+        if let Some(army) = &pending.player_army {
+            players.extend(army_units(army));
+            inputs[0].extend(army_inputs(army));
+            rules[0] = army.merging;
+        }
+        enemies.extend(wave_units(&pending.enemy_wave));
+        inputs[1].extend(army_inputs(&pending.enemy_wave.army));
+        rules[1] = pending.enemy_wave.army.merging;
+    }
+
+    let outnumbered = [players.len() < enemies.len(), enemies.len() < players.len()];
+    let arena_area = merge_context.arena.width * merge_context.arena.height;
+    let team_odds = |i: usize| team_merge_odds(&rules[i], &inputs[i], outnumbered[i], arena_area);
+    let merge = MergeOdds {
+        teams: [team_odds(0), team_odds(1)],
+        check_interval: merge_context.timer.0.duration().as_secs_f32(),
+    };
+
+    let task =
+        AsyncComputeTaskPool::get().spawn(async move { price_bets(&players, &enemies, &merge) });
+
+    commands.insert_resource(SideBets::default());
+    spawn_betting_panel(&mut commands, &game_font, save_data.goop, task);
+}
+
+fn spawn_betting_panel(
+    commands: &mut Commands,
+    game_font: &GameFont,
+    bank: u32,
+    task: Task<Vec<SideBet>>,
+) {
+    let font = game_font.0.clone();
+
+    commands
+        .spawn((
+            DespawnOnExit(CombatState::PreCombat),
+            PricingTask(task),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(16.0),
+                width: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(8.0),
+                ..default()
+            },
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                BetBankText,
+                Text::new(format!(
+                    "Side bets: {STAKE_STEP} goop per click (bank: {bank})"
+                )),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            panel
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(8.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    // One slot per proposition; labels fill in once priced.
+                    for index in 0..PROPOSITION_COUNT {
+                        row.spawn((
+                            BetButton(index),
+                            Button,
+                            Node {
+                                width: Val::Px(170.0),
+                                height: Val::Px(60.0),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            BackgroundColor(BUTTON_COLOR),
+                        ))
+                        .with_children(|btn| {
+                            btn.spawn((
+                                BetButtonText(index),
+                                Text::new("..."),
+                                TextFont {
+                                    font: font.clone(),
+                                    font_size: 20.0,
+                                    ..default()
+                                },
+                                TextColor(Color::WHITE),
+                                TextLayout::new_with_justify(Justify::Center),
+                            ));
+                        });
+                    }
+                });
+        });
+}
+
+/// Runs the simulator and turns outcome frequencies into payouts.
+fn price_bets(players: &[SimUnit], enemies: &[SimUnit], merge: &MergeOdds) -> Vec<SideBet> {
+    if players.is_empty() || enemies.is_empty() {
+        return Vec::new();
+    }
+
+    let mut rng = rand::thread_rng();
+    let outcomes: Vec<FightOutcome> = (0..PRICING_SIMULATIONS)
+        .map(|_| simulate_fight(players.to_vec(), enemies.to_vec(), &mut rng))
+        .collect();
+    let frequency = |hit: &dyn Fn(&FightOutcome) -> bool| {
+        outcomes.iter().filter(|o| hit(o)).count() as f32 / outcomes.len() as f32
+    };
+
+    // Thresholds sit around the typical result so both sides of each
+    // proposition are live.
+    let survivors_line = players.len() as u32 / 2;
+    let mut durations: Vec<f32> = outcomes.iter().map(|o| o.duration).collect();
+    durations.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let median_duration = durations[durations.len() / 2];
+    let time_line = ((median_duration / 5.0).ceil() * 5.0).max(5.0);
+
+    let win = frequency(&|o| o.player_won);
+    let survivors = frequency(&|o| o.survivors > survivors_line);
+    let fast = frequency(&|o| o.duration < time_line);
+    let merge = merge_probability(merge, median_duration);
+    let marked_first = 1.0 / enemies.len() as f32;
+
+    // Sized by PROPOSITION_COUNT so the panel's buttons can't drift out of
+    // step with this list.
+    let priced: [(Proposition, f32); PROPOSITION_COUNT] = [
+        (Proposition::Win, win),
+        (Proposition::Lose, 1.0 - win),
+        (Proposition::SurvivorsAbove(survivors_line), survivors),
+        (Proposition::FasterThan(time_line), fast),
+        (Proposition::MergeHappens, merge),
+        (Proposition::MarkedEnemyDiesFirst, marked_first),
+    ];
+    priced
+        .into_iter()
+        .map(|(proposition, probability)| SideBet {
+            proposition,
+            payout: payout_for(probability),
+            stake: 0,
+        })
+        .collect()
+}
+
+/// The MergeInputs a queued army will spawn as.
+fn army_inputs(army: &Army) -> impl Iterator<Item = MergeInput> {
+    [
+        (MergeInput::Normal, army.normal.count),
+        (MergeInput::Tank, army.tanks.count),
+        (MergeInput::Wizard, army.wizards.count),
+    ]
+    .into_iter()
+    .flat_map(|(input, count)| std::iter::repeat_n(input, count as usize))
+}
+
+fn team_merge_odds(
+    rules: &MergeRules,
+    inputs: &[MergeInput],
+    outnumbered: bool,
+    arena_area: f32,
+) -> TeamMergeOdds {
+    let recipe_pairs = (0..inputs.len())
+        .flat_map(|i| (i + 1..inputs.len()).map(move |j| (i, j)))
+        .filter(|&(i, j)| find_recipe(inputs[i], inputs[j]).is_some())
+        .count();
+    let pair_chance = pair_merge_chance(rules, outnumbered, false) * (1.0 - WOUNDED_FRACTION)
+        + pair_merge_chance(rules, outnumbered, true) * WOUNDED_FRACTION;
+    let in_range = (std::f32::consts::PI * rules.radius * rules.radius / arena_area).min(1.0);
+    TeamMergeOdds {
+        recipe_pairs,
+        pair_chance,
+        in_range,
+    }
+}
+
+/// Merges aren't part of the fight simulator, so this estimates them the
+/// way check_merge_system rolls them: every check, each recipe pair in
+/// range gets its policy's chance, on both teams.
+fn merge_probability(merge: &MergeOdds, duration: f32) -> f32 {
+    let checks = duration / merge.check_interval;
+    let no_merge: f32 = merge
+        .teams
+        .iter()
+        .map(|team| {
+            (1.0 - team.pair_chance).powf(team.recipe_pairs as f32 * team.in_range * checks)
+        })
+        .product();
+    1.0 - no_merge
+}
+
+/// Fair odds are 1/p; the house keeps HOUSE_EDGE of that. Rounded down to
+/// one decimal so the number on the button is exactly what gets paid.
+fn payout_for(probability: f32) -> f32 {
+    let p = probability.clamp(MIN_PROBABILITY, MAX_PROBABILITY);
+    let payout = (1.0 - HOUSE_EDGE) / p;
+    ((payout * 10.0).floor() / 10.0).max(1.1)
+}
+
+fn poll_pricing_task(
+    mut commands: Commands,
+    mut tasks: Query<(Entity, &mut PricingTask)>,
+    mut side_bets: ResMut<SideBets>,
+) {
+    for (entity, mut task) in &mut tasks {
+        if let Some(bets) = check_ready(&mut task.0) {
+            side_bets.bets = bets;
+            commands.entity(entity).remove::<PricingTask>();
+        }
+    }
+}
+
+/// Picks the "$" enemy once the first enemies have spawned.
+fn mark_enemy_system(
+    mut commands: Commands,
+    game_font: Res<GameFont>,
+    mut side_bets: ResMut<SideBets>,
+    enemies: Query<(Entity, &Team), (With<Health>, Without<ChildOf>, Without<Dying>)>,
+) {
+    if side_bets.marked_enemy.is_some() {
+        return;
+    }
+    let Some(enemy) = enemies
+        .iter()
+        .filter(|(_, team)| **team == Team::Enemy)
+        .map(|(entity, _)| entity)
+        .choose(&mut rand::thread_rng())
+    else {
+        return;
+    };
+
+    side_bets.marked_enemy = Some(enemy);
+    commands
+        .entity(enemy)
+        .insert(MarkedEnemy)
+        .with_children(|parent| {
+            parent.spawn((
+                Text2d::new("$"),
+                TextFont {
+                    font: game_font.0.clone(),
+                    font_size: 30.0,
+                    ..default()
+                },
+                TextColor(MARK_COLOR),
+                Transform::from_xyz(0.0, 25.0, 1.0),
+            ));
+        });
+}
+
+fn bet_button_system(
+    query: Query<(&Interaction, &BetButton), Changed<Interaction>>,
+    mut side_bets: ResMut<SideBets>,
+    mut save_data: ResMut<SaveData>,
+) {
+    for (interaction, bet_btn) in &query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // The marked-enemy bet opens once there's a marked enemy.
+        let has_mark = side_bets.marked_enemy.is_some();
+        let Some(bet) = side_bets.bets.get_mut(bet_btn.0) else {
+            continue;
+        };
+        if bet.proposition == Proposition::MarkedEnemyDiesFirst && !has_mark {
+            continue;
+        }
+        if save_data.goop < STAKE_STEP {
+            continue;
+        }
+
+        save_data.goop -= STAKE_STEP;
+        bet.stake += STAKE_STEP;
+    }
+}
+
+fn update_bet_labels_system(
+    side_bets: Res<SideBets>,
+    save_data: Res<SaveData>,
+    mut labels: Query<(&BetButtonText, &mut Text), Without<BetBankText>>,
+    mut bank_text: Query<&mut Text, With<BetBankText>>,
+) {
+    if !side_bets.is_changed() && !save_data.is_changed() {
+        return;
+    }
+
+    for (label, mut text) in &mut labels {
+        let Some(bet) = side_bets.bets.get(label.0) else {
+            continue;
+        };
+        let price = format!("{} x{:.1}", bet.proposition.label(), bet.payout);
+        **text = if bet.stake > 0 {
+            format!("{price}\nstake {}", bet.stake)
+        } else {
+            price
+        };
+    }
+
+    for mut text in &mut bank_text {
+        **text = format!(
+            "Side bets: {STAKE_STEP} goop per click (bank: {})",
+            save_data.goop
+        );
+    }
+}
+
+fn start_round_events(mut commands: Commands, time: Res<Time>) {
+    commands.insert_resource(RoundEvents {
+        started_at: time.elapsed_secs(),
+        merge_happened: false,
+        first_enemy_death: None,
+    });
+}
+
+fn on_slimes_merged(_trigger: On<SlimesMergedEvent>, events: Option<ResMut<RoundEvents>>) {
+    if let Some(mut events) = events {
+        events.merge_happened = true;
+    }
+}
+
+fn record_first_enemy_death(
+    query: Query<(Entity, &Team), Added<Dying>>,
+    mut events: ResMut<RoundEvents>,
+) {
+    if events.first_enemy_death.is_some() {
+        return;
+    }
+    if let Some((entity, _)) = query.iter().find(|(_, team)| **team == Team::Enemy) {
+        events.first_enemy_death = Some(entity);
+    }
+}
+
+/// Settles every bet with a stake, pays winners into the bank, and lists
+/// the results on the PostCombat screen.
+fn settle_bets(
+    mut commands: Commands,
+    game_font: Res<GameFont>,
+    time: Res<Time>,
+    side_bets: Option<Res<SideBets>>,
+    events: Option<Res<RoundEvents>>,
+    teams: Query<&Team>,
+    survivors: Query<&Team, (With<Health>, Without<ChildOf>, Without<Dying>)>,
+    mut save_data: ResMut<SaveData>,
+) {
+    let (Some(side_bets), Some(events)) = (side_bets, events) else {
+        return;
+    };
+    if side_bets.total_staked() == 0 {
+        return;
+    }
+
+    // Same victory rule as enter_post_combat.
+    let has_player = teams.iter().any(|t| *t == Team::Player);
+    let has_enemy = teams.iter().any(|t| *t == Team::Enemy);
+    let victory = has_player && !has_enemy;
+    let survivor_count = survivors.iter().filter(|t| **t == Team::Player).count() as u32;
+    let duration = time.elapsed_secs() - events.started_at;
+
+    let mut lines = Vec::new();
+    let mut net: i64 = 0;
+    for bet in side_bets.bets.iter().filter(|b| b.stake > 0) {
+        let won = match bet.proposition {
+            Proposition::Win => victory,
+            Proposition::Lose => !victory,
+            Proposition::SurvivorsAbove(n) => survivor_count > n,
+            Proposition::FasterThan(secs) => duration < secs,
+            Proposition::MergeHappens => events.merge_happened,
+            Proposition::MarkedEnemyDiesFirst => {
+                side_bets.marked_enemy.is_some()
+                    && events.first_enemy_death == side_bets.marked_enemy
+            }
+        };
+
+        if won {
+            let paid = (bet.stake as f32 * bet.payout).floor() as u32;
+            save_data.goop += paid;
+            net += paid as i64 - bet.stake as i64;
+            lines.push((
+                format!(
+                    "{}: staked {}, paid {}",
+                    bet.proposition.label(),
+                    bet.stake,
+                    paid
+                ),
+                WIN_COLOR,
+            ));
+        } else {
+            net -= bet.stake as i64;
+            lines.push((
+                format!("{}: lost {}", bet.proposition.label(), bet.stake),
+                LOSE_COLOR,
+            ));
+        }
+    }
+
+    let font = game_font.0.clone();
+    commands
+        .spawn((
+            DespawnOnExit(CombatState::PostCombat),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(16.0),
+                left: Val::Px(16.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Text::new("Side bets"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+            for (line, color) in lines {
+                panel.spawn((
+                    Text::new(line),
+                    TextFont {
+                        font: font.clone(),
+                        font_size: 22.0,
+                        ..default()
+                    },
+                    TextColor(color),
+                ));
+            }
+            let (net_text, net_color) = if net >= 0 {
+                (format!("Net: +{net}"), WIN_COLOR)
+            } else {
+                (format!("Net: {net}"), LOSE_COLOR)
+            };
+            panel.spawn((
+                Text::new(net_text),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(net_color),
+            ));
+        });
+}
+
+fn cleanup_bets(mut commands: Commands) {
+    commands.remove_resource::<SideBets>();
+    commands.remove_resource::<RoundEvents>();
+}
+
+fn button_hover_system(
+    mut query: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<Button>)>,
+) {
+    for (interaction, mut bg) in &mut query {
+        *bg = match interaction {
+            Interaction::Pressed => BUTTON_PRESSED_COLOR.into(),
+            Interaction::Hovered => BUTTON_HOVER_COLOR.into(),
+            Interaction::None => BUTTON_COLOR.into(),
+        };
+    }
+}
@@ -2,16 +2,25 @@ use bevy::prelude::*;
 use rand::Rng;
 
 use crate::{
+    affixes::EliteAffixes,
     animation::{AnimationType, IdleAnimation, VictoryAnimation},
-    armies::{Army, EnemyWave},
+    armies::{Army, EnemyWave, SlimeType},
+    bosses::spawn_boss,
     combat::{Attack, AttackEffect, BlockChance, KnownAttacks, Shield, TimeBetweenAttacks},
+    hazards::{HazardAbility, HazardKind, WaveHazards},
     health::{DeathAnimation, Health, MaxHealth},
+    hitboxes::{SLIME_BITE, SLIME_SLAM, SPEAR_THRUST, SPELL_BURST},
+    merge_recipes::MergeKind,
     movement::{Speed, StaysNearParent},
     pick_target::{PickTargetStrategy, Team},
+    positioning::Positioning,
     save_load::SaveData,
     setup_round::Inert,
-    special_abilities::MergedSlime,
+    special_abilities::{MergedSlime, TeamMergeRules},
+    splitting::MERGED_SPLIT,
     sprite_modifications::{LerpType, SpriteModification},
+    steering::Mass,
+    threat::Taunt,
     GameState,
 };
 
@@ -41,13 +50,18 @@ pub struct SlimesToSpawn {
 #[derive(Resource)]
 pub struct SlimeSpawnTimer(pub Timer);
 
-/// Queues armies for staggered spawning. Pass `None` for `player_army` when
-/// survivors are already on the field (e.g. venture further).
+/// Queues armies for staggered spawning, and sets up the wave's
+/// environmental hazards. Pass `None` for `player_army` when survivors are
+/// already on the field (e.g. venture further).
 pub fn setup_slime_spawn(
     commands: &mut Commands,
     player_army: Option<Army>,
     enemy_wave: EnemyWave,
 ) {
+    commands.insert_resource(WaveHazards {
+        kinds: enemy_wave.hazards.clone(),
+        ..default()
+    });
     commands.insert_resource(SlimesToSpawn {
         player_army,
         enemy_wave,
@@ -58,10 +72,11 @@ pub fn setup_slime_spawn(
     )));
 }
 
+/// The player's army spawns with Home-screen upgrades folded into its stats.
 fn start_combat_system(mut commands: Commands, save_data: Res<SaveData>) {
     setup_slime_spawn(
         &mut commands,
-        Some(save_data.army.clone()),
+        Some(save_data.upgrades.apply(&save_data.army)),
         crate::armies::create_enemy_army(1),
     );
 }
@@ -70,61 +85,105 @@ fn spawn_slimes_system(
     mut commands: Commands,
     mut slimes_to_spawn: ResMut<SlimesToSpawn>,
     mut timer: ResMut<SlimeSpawnTimer>,
+    mut merge_rules: ResMut<TeamMergeRules>,
     game_time: Res<Time>,
 ) {
+    // A freshly queued fight brings its armies' merge rules. With no player
+    // army (venture further), the survivors keep the rules they started with.
+    if slimes_to_spawn.is_added() {
+        if let Some(player) = &slimes_to_spawn.player_army {
+            merge_rules.player = player.merging;
+        }
+        merge_rules.enemy = slimes_to_spawn.enemy_wave.army.merging;
+    }
+
     if timer.0.just_finished() {
         if let Some(ref mut player) = slimes_to_spawn.player_army {
-            if player.normal.count > 0 {
-                spawn_normal_slime(&mut commands, Team::Player, player.normal.hp);
+            let spawned = if player.normal.count > 0 {
                 player.normal.count -= 1;
+                Some((
+                    spawn_normal_slime(&mut commands, Team::Player, player.normal.hp),
+                    SlimeType::Normal,
+                ))
             } else if player.tanks.count > 0 {
-                spawn_tank_slime(
-                    &mut commands,
-                    Team::Player,
-                    player.tanks.hp,
-                    player.tanks.block_chance,
-                    player.tanks.stun_chance,
-                );
                 player.tanks.count -= 1;
+                Some((
+                    spawn_tank_slime(
+                        &mut commands,
+                        Team::Player,
+                        player.tanks.hp,
+                        player.tanks.block_chance,
+                        player.tanks.stun_chance,
+                    ),
+                    SlimeType::Tank,
+                ))
             } else if player.wizards.count > 0 {
-                spawn_wizard_slime(
-                    &mut commands,
-                    Team::Player,
-                    player.wizards.hp,
-                    player.wizards.spell_range,
-                    player.wizards.aoe_damage,
-                    player.wizards.spear_knockback,
-                );
                 player.wizards.count -= 1;
+                Some((
+                    spawn_wizard_slime(
+                        &mut commands,
+                        Team::Player,
+                        player.wizards.hp,
+                        player.wizards.spell_range,
+                        player.wizards.aoe_damage,
+                        player.wizards.spear_knockback,
+                    ),
+                    SlimeType::Wizard,
+                ))
+            } else {
+                None
+            };
+
+            // Targeting overrides from the Home screen replace the type default.
+            if let Some((entity, slime_type)) = spawned {
+                if let Some(strategy) = player.targeting.get(slime_type) {
+                    commands.entity(entity).insert(strategy);
+                }
             }
         }
 
         let enemy = &mut slimes_to_spawn.enemy_wave.army;
-        if enemy.normal.count > 0 {
-            spawn_normal_slime(&mut commands, Team::Enemy, enemy.normal.hp);
+        let spawned = if enemy.normal.count > 0 {
             enemy.normal.count -= 1;
+            Some(spawn_normal_slime(
+                &mut commands,
+                Team::Enemy,
+                enemy.normal.hp,
+            ))
         } else if enemy.tanks.count > 0 {
-            spawn_tank_slime(
+            enemy.tanks.count -= 1;
+            Some(spawn_tank_slime(
                 &mut commands,
                 Team::Enemy,
                 enemy.tanks.hp,
                 enemy.tanks.block_chance,
                 enemy.tanks.stun_chance,
-            );
-            enemy.tanks.count -= 1;
+            ))
         } else if enemy.wizards.count > 0 {
-            spawn_wizard_slime(
+            enemy.wizards.count -= 1;
+            Some(spawn_wizard_slime(
                 &mut commands,
                 Team::Enemy,
                 enemy.wizards.hp,
                 enemy.wizards.spell_range,
                 enemy.wizards.aoe_damage,
                 enemy.wizards.spear_knockback,
-            );
-            enemy.wizards.count -= 1;
+            ))
         } else if slimes_to_spawn.enemy_wave.merged_count > 0 {
-            spawn_merged_slime(&mut commands, Team::Enemy, None);
             slimes_to_spawn.enemy_wave.merged_count -= 1;
+            Some(spawn_merged_slime(&mut commands, Team::Enemy, None))
+        } else if let Some(boss) = slimes_to_spawn.enemy_wave.boss.take() {
+            Some(spawn_boss(&mut commands, boss))
+        } else {
+            None
+        };
+
+        // The first enemy of an elite wave is the elite.
+        let affixes = &mut slimes_to_spawn.enemy_wave.elite_affixes;
+        if let Some(entity) = spawned.filter(|_| !affixes.is_empty()) {
+            commands
+                .entity(entity)
+                .insert(EliteAffixes(std::mem::take(affixes)));
         }
     }
 
@@ -137,7 +196,8 @@ fn spawn_slimes_system(
     let enemy_remaining = enemy.normal.count
         + enemy.tanks.count
         + enemy.wizards.count
-        + slimes_to_spawn.enemy_wave.merged_count;
+        + slimes_to_spawn.enemy_wave.merged_count
+        + slimes_to_spawn.enemy_wave.boss.is_some() as u32;
 
     if player_remaining + enemy_remaining == 0 {
         commands.remove_resource::<SlimeSpawnTimer>();
@@ -147,7 +207,7 @@ fn spawn_slimes_system(
     timer.0.tick(game_time.delta());
 }
 
-fn spawn_normal_slime(commands: &mut Commands, team: Team, hp: i32) -> Entity {
+pub fn spawn_normal_slime(commands: &mut Commands, team: Team, hp: i32) -> Entity {
     let mut rng = rand::thread_rng();
 
     let player_x = rng.gen_range(-500.0..-100.0);
@@ -186,7 +246,7 @@ fn spawn_normal_slime(commands: &mut Commands, team: Team, hp: i32) -> Entity {
             VictoryAnimation(victory_anim),
             Transform::from_xyz(x, y, 0.0).with_scale(Vec3::splat(scale as f32)),
             team,
-            PickTargetStrategy::Close,
+            SlimeType::Normal.default_target_strategy(),
             Sprite {
                 flip_x: team == Team::Enemy,
                 ..default()
@@ -204,6 +264,7 @@ fn spawn_normal_slime(commands: &mut Commands, team: Team, hp: i32) -> Entity {
                     ..Default::default()
                 },
                 range: 65.0,
+                hitbox: SLIME_BITE,
             }]),
             Inert,
             SpriteModification {
@@ -213,6 +274,12 @@ fn spawn_normal_slime(commands: &mut Commands, team: Team, hp: i32) -> Entity {
         ))
         .id();
 
+    // Bundles max out at 15 components, so this goes in separately.
+    // Tanks and wizards overwrite it with their own type.
+    commands
+        .entity(entity)
+        .insert((SlimeType::Normal, Mass(SlimeType::Normal.mass())));
+
     if team == Team::Enemy {
         commands.entity(entity).insert(GoopValue(1));
     }
@@ -253,6 +320,7 @@ fn spawn_tank_slime(
             ..default()
         },
         range: 65.0,
+        hitbox: SLIME_BITE,
     }]));
     if team == Team::Enemy {
         commands.entity(entity).insert(GoopValue(3));
@@ -260,7 +328,13 @@ fn spawn_tank_slime(
 
     commands
         .entity(entity)
-        .insert(BlockChance(block_chance))
+        .insert((
+            SlimeType::Tank,
+            SlimeType::Tank.default_target_strategy(),
+            Mass(SlimeType::Tank.mass()),
+            BlockChance(block_chance),
+            Taunt::default(),
+        ))
         .with_child((
             Shield,
             AnimationType::IcebergIdle,
@@ -301,6 +375,13 @@ fn spawn_wizard_slime(
 
     commands
         .entity(entity)
+        .insert((
+            SlimeType::Wizard,
+            SlimeType::Wizard.default_target_strategy(),
+            Mass(SlimeType::Wizard.mass()),
+            Positioning::Caster,
+            HazardAbility::new(HazardKind::FrozenRain),
+        ))
         .insert(KnownAttacks(vec![Attack {
             animation: mage_cast_anim,
             hit_frame: 0,
@@ -310,6 +391,7 @@ fn spawn_wizard_slime(
                 ..Default::default()
             },
             range: spell_range,
+            hitbox: SPELL_BURST,
         }]))
         .with_child((
             AnimationType::FrozenSpearIdle,
@@ -328,6 +410,7 @@ fn spawn_wizard_slime(
                     ..Default::default()
                 },
                 range: 65.0,
+                hitbox: SPEAR_THRUST,
             }]),
             TimeBetweenAttacks(2.0),
         ));
@@ -335,8 +418,9 @@ fn spawn_wizard_slime(
     entity
 }
 
-/// Spawns a merged (big) slime. Used both by the spawn system (for level 10
-/// pre-made merged slimes) and by the merge system in special_abilities.rs.
+/// Spawns a merged (big) slime — a Blob. Used by the spawn system (for
+/// pre-made merged slimes), by bosses, and as the base of every merge result
+/// in merge_recipes.rs.
 /// Pass `Some(pos)` to place at a specific position (merge midpoint), or
 /// `None` for a random enemy-side position.
 pub fn spawn_merged_slime(commands: &mut Commands, team: Team, position: Option<Vec3>) -> Entity {
@@ -389,8 +473,14 @@ pub fn spawn_merged_slime(commands: &mut Commands, team: Team, position: Option<
                     ..Default::default()
                 },
                 range: 100.0,
+                hitbox: SLIME_SLAM,
             }]),
-            MergedSlime,
+            (
+                MergedSlime,
+                MergeKind::Blob,
+                MERGED_SPLIT,
+                Mass(MergeKind::Blob.def().mass),
+            ),
             Inert,
             SpriteModification {
                 lerp: LerpType::EaseInOut,
@@ -3,13 +3,15 @@ use rand::Rng;
 
 use crate::{
     animation::{AnimationState, AnimationType, IdleAnimation, SpriteSheets},
+    armies::{MergePolicy, MergeRules, SlimeType},
     audio::GameAudio,
-    combat::ActiveAttack,
-    health::{Dying, Health},
+    combat::{ActiveAttack, BlockChance, KnownAttacks},
+    health::{Dying, Health, MaxHealth},
+    merge_recipes::{find_recipe, spawn_merge_result, MergeIngredient, MergeInput, MergeKind},
     movement::{Speed, TargetEntity},
     pick_target::Team,
     setup_round::Inert,
-    spawn_slimes::spawn_merged_slime,
+    splitting::SplitSpawn,
     CombatState, GameFont,
 };
 
@@ -21,9 +23,10 @@ impl Plugin for SpecialAbilitiesPlugin {
         // 0.5s means we evaluate merge chances twice per second — frequent enough
         // to feel responsive, but not so frequent that merges happen instantly.
         app.insert_resource(MergeCheckTimer(Timer::from_seconds(
-            0.5,
+            MERGE_CHECK_INTERVAL,
             TimerMode::Repeating,
-        )));
+        )))
+        .init_resource::<TeamMergeRules>();
 
         // Chain the 4 merge systems so they run in a guaranteed order each frame.
         // This is the same pattern combat.rs uses: each system builds on the state
@@ -68,9 +71,9 @@ pub struct Merging {
     pub meeting_point: Vec3,
 }
 
-/// Permanent marker — prevents a merged slime from merging again.
-/// This is a "tag" component: it has no data, just presence/absence.
-/// We query `Without<MergedSlime>` to filter out entities that have already merged.
+/// Permanent marker for big merged slimes. This is a "tag" component: it has
+/// no data, just presence/absence. Whether a merged slime can merge again is
+/// up to its MergeKind and the recipe table in merge_recipes.rs.
 #[derive(Component)]
 pub struct MergedSlime;
 
@@ -80,11 +83,67 @@ pub struct MergedSlime;
 #[derive(Resource)]
 pub struct MergeCheckTimer(pub Timer);
 
+/// Default seconds between merge checks. Relics can shorten it for a run.
+pub const MERGE_CHECK_INTERVAL: f32 = 0.5;
+
+/// Aggressive armies roll this many times their base merge chance.
+const AGGRESSIVE_MULTIPLIER: f32 = 3.0;
+/// Outnumbered and Wounded only merge in a pinch, but then they mean it.
+const SITUATIONAL_MULTIPLIER: f32 = 2.0;
+/// Below this fraction of max HP a slime counts as wounded.
+const WOUNDED_THRESHOLD: f32 = 0.5;
+
+/// Resource — each team's merge rules for the current fight. Copied from the
+/// armies as they're queued in spawn_slimes_system.
+#[derive(Resource, Default)]
+pub struct TeamMergeRules {
+    pub player: MergeRules,
+    pub enemy: MergeRules,
+}
+
+impl TeamMergeRules {
+    pub fn get(&self, team: Team) -> &MergeRules {
+        match team {
+            Team::Player => &self.player,
+            Team::Enemy => &self.enemy,
+        }
+    }
+}
+
+/// Chance per merge check that a pair merges under `rules`, or 0 when the
+/// policy rules it out.
+pub fn pair_merge_chance(rules: &MergeRules, outnumbered: bool, both_wounded: bool) -> f32 {
+    match rules.policy {
+        MergePolicy::Sometimes => rules.chance,
+        MergePolicy::Never => 0.0,
+        MergePolicy::Aggressive => rules.chance * AGGRESSIVE_MULTIPLIER,
+        MergePolicy::WhenOutnumbered if outnumbered => rules.chance * SITUATIONAL_MULTIPLIER,
+        MergePolicy::WoundedOnly if both_wounded => rules.chance * SITUATIONAL_MULTIPLIER,
+        MergePolicy::WhenOutnumbered | MergePolicy::WoundedOnly => 0.0,
+    }
+    .min(1.0)
+}
+
+/// Fired when two slimes finish merging, just before they're despawned.
+/// Carries the consumed slimes' types (None for anything without a
+/// SlimeType) so listeners don't need to query entities that are about to vanish.
+#[derive(Event)]
+pub struct SlimesMergedEvent {
+    pub team: Team,
+    pub consumed: [Option<SlimeType>; 2],
+}
+
+/// Marker for the "!" and odds text above a slime that's about to merge.
+#[derive(Component)]
+struct MergeIndicator;
+
 #[derive(Component)]
 pub struct PreMerging {
     pub timer: Timer,
     pub partner: Entity,
     pub meeting_point: Vec3,
+    /// The odds this merge was rolled at, shown under the "!".
+    pub chance: f32,
 }
 
 // ── Systems ─────────────────────────────────────────────────────────────────
@@ -92,11 +151,12 @@ pub struct PreMerging {
 /// System 1: Periodically checks if any same-team slime pairs should merge.
 ///
 /// Every 0.5s (gated by MergeCheckTimer), iterates all eligible slimes and checks
-/// every same-team pair. If two slimes are within 100 units, rolls a 0.5% chance
-/// for them to start merging.
+/// every same-team pair. If two slimes are within their army's merge radius and
+/// merge_recipes.rs has a recipe for them, rolls the chance their army's merge
+/// policy allows (0.5% by default) for them to start merging.
 ///
-/// "Eligible" means: not inert, not dying, not attacking, not already merging,
-/// and not a MergedSlime. All these exclusions use query filters — this is how
+/// "Eligible" means: not inert, not dying, not attacking, and not already
+/// merging. All these exclusions use query filters — this is how
 /// ECS handles complex conditions. Instead of `if (!entity.isInert && !entity.isDying ...)`
 /// like in OOP, we declare the filters in the query signature and Bevy automatically
 /// skips non-matching entities.
@@ -112,17 +172,25 @@ fn check_merge_system(
     // system uses archetype-based filtering, so entities that don't match are never
     // even iterated over.
     eligible: Query<
-        (Entity, &Team, &Transform),
+        (
+            Entity,
+            &Team,
+            &Transform,
+            &Health,
+            &MaxHealth,
+            Option<&SlimeType>,
+            Option<&MergeKind>,
+        ),
         (
             Without<Inert>,
             Without<Dying>,
             Without<ActiveAttack>,
             Without<PreMerging>,
             Without<Merging>,
-            Without<MergedSlime>,
-            With<Health>,
         ),
     >,
+    living: Query<&Team, (With<Health>, Without<Dying>)>,
+    merge_rules: Res<TeamMergeRules>,
     mut commands: Commands,
 ) {
     timer.0.tick(time.delta());
@@ -136,37 +204,61 @@ fn check_merge_system(
     // We can't nest .iter() calls on the same query because Rust's borrow checker
     // won't allow two simultaneous immutable borrows of the query iterator state.
     // Collecting to a Vec is the standard ECS workaround for pair-wise comparisons.
-    let candidates: Vec<(Entity, &Team, Vec3)> = eligible
+    // Slimes with no MergeInput (Giants, bosses) can't merge at all.
+    let candidates: Vec<(Entity, &Team, Vec3, MergeInput, bool)> = eligible
         .iter()
-        .map(|(e, team, t)| (e, team, t.translation))
+        .filter_map(|(e, team, t, health, max_health, slime_type, kind)| {
+            let wounded = (health.0 as f32) < max_health.0 as f32 * WOUNDED_THRESHOLD;
+            Some((
+                e,
+                team,
+                t.translation,
+                MergeInput::of(slime_type, kind)?,
+                wounded,
+            ))
+        })
         .collect();
 
+    let players = living.iter().filter(|t| **t == Team::Player).count();
+    let enemies = living.iter().filter(|t| **t == Team::Enemy).count();
+
     // Track which entities we've already paired this tick, so one slime
     // doesn't get matched with multiple partners simultaneously.
     let mut already_paired: Vec<Entity> = Vec::new();
 
     for i in 0..candidates.len() {
         for j in (i + 1)..candidates.len() {
-            let (entity_a, team_a, pos_a) = candidates[i];
-            let (entity_b, team_b, pos_b) = candidates[j];
+            let (entity_a, team_a, pos_a, input_a, wounded_a) = candidates[i];
+            let (entity_b, team_b, pos_b, input_b, wounded_b) = candidates[j];
 
             // Same-team only — cross-team merging doesn't make gameplay sense
             if team_a != team_b {
                 continue;
             }
 
+            // Only pairs with a recipe can merge
+            if find_recipe(input_a, input_b).is_none() {
+                continue;
+            }
+
             // Skip if either entity was already paired this tick
             if already_paired.contains(&entity_a) || already_paired.contains(&entity_b) {
                 continue;
             }
 
+            let rules = merge_rules.get(*team_a);
             let distance = pos_a.distance(pos_b);
-            if distance > 150.0 {
+            if distance > rules.radius {
                 continue;
             }
 
-            // Roll the dice — 0.5% chance per qualifying pair per tick
-            if rng.gen::<f32>() > 0.005 {
+            // Roll the dice at whatever odds the army's policy gives this pair
+            let outnumbered = match team_a {
+                Team::Player => players < enemies,
+                Team::Enemy => enemies < players,
+            };
+            let chance = pair_merge_chance(rules, outnumbered, wounded_a && wounded_b);
+            if chance <= 0.0 || rng.gen::<f32>() > chance {
                 continue;
             }
 
@@ -183,6 +275,7 @@ fn check_merge_system(
                 timer: Timer::from_seconds(1.5, TimerMode::Once),
                 partner: entity_b,
                 meeting_point,
+                chance,
             });
             commands.entity(entity_a).remove::<TargetEntity>();
 
@@ -190,6 +283,7 @@ fn check_merge_system(
                 timer: Timer::from_seconds(1.5, TimerMode::Once),
                 partner: entity_a,
                 meeting_point,
+                chance,
             });
             commands.entity(entity_b).remove::<TargetEntity>();
 
@@ -201,12 +295,21 @@ fn check_merge_system(
 
 fn on_add_pre_merge_system(
     mut commands: Commands,
-    mut query: Query<(Entity, &IdleAnimation, &mut Sprite, &mut AnimationState), Added<PreMerging>>,
+    mut query: Query<
+        (
+            Entity,
+            &PreMerging,
+            &IdleAnimation,
+            &mut Sprite,
+            &mut AnimationState,
+        ),
+        Added<PreMerging>,
+    >,
     sprite_sheets: Res<SpriteSheets>,
     audio: Res<GameAudio>,
     game_font: Res<GameFont>,
 ) {
-    for (entity, idle_animation, mut sprite, mut anim_state) in &mut query {
+    for (entity, pre_merging, idle_animation, mut sprite, mut anim_state) in &mut query {
         commands.spawn((
             AudioPlayer::new(audio.merge_alert.clone()),
             PlaybackSettings::DESPAWN.with_volume(Volume::Linear(0.5)),
@@ -220,23 +323,33 @@ fn on_add_pre_merge_system(
         // We read IdleAnimation to get the correct sprite sheet image for this
         // entity, rather than matching on Team. This way if we add new entity
         // types with different idle sprites, this code doesn't need to change.
-        let image = match idle_animation.0 {
-            AnimationType::SlimeMoveSmallJump => sprite_sheets.slime_move_small_jump.clone(),
-            AnimationType::EnemySlimeMoveSmallJump => {
-                sprite_sheets.enemy_slime_move_small_jump.clone()
-            }
-            // Merged slimes shouldn't be entering PreMerging (MergedSlime filter
-            // excludes them in check_merge_system), but handle it gracefully.
-            AnimationType::BigSlimeJumpIdle => sprite_sheets.slime_jump_idle.clone(),
-            AnimationType::EnemyBigSlimeJumpIdle => sprite_sheets.enemy_slime_jump_idle.clone(),
+        let (image, layout) = match idle_animation.0 {
+            AnimationType::SlimeMoveSmallJump => (
+                sprite_sheets.slime_move_small_jump.clone(),
+                sprite_sheets.move_small_jump_layout.clone(),
+            ),
+            AnimationType::EnemySlimeMoveSmallJump => (
+                sprite_sheets.enemy_slime_move_small_jump.clone(),
+                sprite_sheets.move_small_jump_layout.clone(),
+            ),
+            // Merged slimes can merge again (see merge_recipes.rs). Their idle
+            // is the big jump-idle sheet, which has its own layout.
+            AnimationType::BigSlimeJumpIdle => (
+                sprite_sheets.slime_jump_idle.clone(),
+                sprite_sheets.jump_idle_layout.clone(),
+            ),
+            AnimationType::EnemyBigSlimeJumpIdle => (
+                sprite_sheets.enemy_slime_jump_idle.clone(),
+                sprite_sheets.jump_idle_layout.clone(),
+            ),
             // Fallback for any future idle types
-            _ => sprite_sheets.slime_move_small_jump.clone(),
+            _ => (
+                sprite_sheets.slime_move_small_jump.clone(),
+                sprite_sheets.move_small_jump_layout.clone(),
+            ),
         };
         sprite.image = image;
-        sprite.texture_atlas = Some(TextureAtlas {
-            layout: sprite_sheets.move_small_jump_layout.clone(),
-            index: 0,
-        });
+        sprite.texture_atlas = Some(TextureAtlas { layout, index: 0 });
 
         // Freeze the animation at frame 0 so animation_system doesn't advance it
         // for the remainder of this frame (commands are deferred, but these direct
@@ -250,9 +363,11 @@ fn on_add_pre_merge_system(
         // have already prevented any same-frame advancement.
         commands.entity(entity).remove::<AnimationState>();
 
-        // Spawn "!" text above the slime to visually indicate the merge alert
+        // Spawn "!" text above the slime to visually indicate the merge alert,
+        // with the odds it was rolled at in small print underneath
         commands.entity(entity).with_children(|parent| {
             parent.spawn((
+                MergeIndicator,
                 Text2d::new("!"),
                 TextFont {
                     font: game_font.0.clone(),
@@ -262,6 +377,17 @@ fn on_add_pre_merge_system(
                 TextColor(Color::WHITE),
                 Transform::from_xyz(0.0, 25.0, 1.0),
             ));
+            parent.spawn((
+                MergeIndicator,
+                Text2d::new(format!("{:.1}%", pre_merging.chance * 100.0)),
+                TextFont {
+                    font: game_font.0.clone(),
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.5)),
+                Transform::from_xyz(0.0, 8.0, 1.0),
+            ));
         });
     }
 }
@@ -275,10 +401,21 @@ fn pre_merge_system(
     // Separate query to check if the partner is still alive — same pattern as cancel_merge_system.
     // This prevents the crash: without it, commands.entity(dead_partner) panics.
     alive_check: Query<Entity, Without<Dying>>,
+    sprites: Query<&Sprite>,
     time: Res<Time>,
-    sprite_sheets: Res<SpriteSheets>,
     layouts: Res<Assets<TextureAtlasLayout>>,
 ) {
+    // Small and big slimes freeze on different sheets, so each restarts with
+    // the frame count of whatever layout on_add_pre_merge_system gave it.
+    let total_frames = |entity: Entity| {
+        sprites
+            .get(entity)
+            .ok()
+            .and_then(|sprite| sprite.texture_atlas.as_ref())
+            .and_then(|atlas| layouts.get(&atlas.layout))
+            .map_or(1, |layout| layout.len())
+    };
+
     for (entity, mut pre_merging) in query.iter_mut() {
         pre_merging.timer.tick(time.delta());
 
@@ -299,16 +436,12 @@ fn pre_merge_system(
             // Now the slime needs to animate again as it walks to the meeting point.
             // The sprite image/layout was already set correctly in on_add_pre_merge_system,
             // so we only need to restore the AnimationState — no need to touch the sprite.
-            let total_frames = layouts
-                .get(&sprite_sheets.move_small_jump_layout)
-                .unwrap()
-                .len();
             commands
                 .entity(entity)
-                .insert(AnimationState::new(0.1, total_frames, true));
+                .insert(AnimationState::new(0.1, total_frames(entity), true));
             commands
                 .entity(partner)
-                .insert(AnimationState::new(0.1, total_frames, true));
+                .insert(AnimationState::new(0.1, total_frames(partner), true));
 
             // Swap PreMerging → Merging on THIS entity
             commands.entity(entity).remove::<PreMerging>();
@@ -368,9 +501,24 @@ fn merge_walk_system(
 /// This is the ECS way: rather than "transforming" an existing entity, we destroy
 /// the old ones and create a new one with the properties we want. It's cleaner than
 /// trying to modify one entity and delete the other, because the merged slime has
-/// fundamentally different stats.
+/// fundamentally different stats. Which slime it becomes, and its stats, come from
+/// the recipe table in merge_recipes.rs.
 fn execute_merge_system(
-    query: Query<(Entity, &Merging, &Transform, &Team), Without<Dying>>,
+    query: Query<
+        (
+            Entity,
+            &Merging,
+            &Transform,
+            &Team,
+            Option<&SlimeType>,
+            Option<&MergeKind>,
+            &MaxHealth,
+            &KnownAttacks,
+            Option<&BlockChance>,
+            Has<SplitSpawn>,
+        ),
+        Without<Dying>,
+    >,
     mut commands: Commands,
     audio: Res<GameAudio>,
 ) {
@@ -378,13 +526,27 @@ fn execute_merge_system(
     // trying to despawn the same entity twice (both partners would match).
     let mut already_merged: Vec<Entity> = Vec::new();
 
-    for (entity, merging, transform, team) in query.iter() {
+    for (entity, merging, transform, team, slime_type, kind, max_health, attacks, block, split) in
+        query.iter()
+    {
         if already_merged.contains(&entity) {
             continue;
         }
 
         // Check if our partner still exists and has Merging
-        let Ok((partner_entity, _, partner_transform, _)) = query.get(merging.partner) else {
+        let Ok((
+            partner_entity,
+            _,
+            partner_transform,
+            _,
+            partner_type,
+            partner_kind,
+            partner_max_health,
+            partner_attacks,
+            partner_block,
+            partner_split,
+        )) = query.get(merging.partner)
+        else {
             continue;
         };
 
@@ -396,6 +558,15 @@ fn execute_merge_system(
             continue;
         }
 
+        // check_merge_system only pairs slimes with a recipe, so this only
+        // fails if something changed one of them mid-merge.
+        let Some(result) = MergeInput::of(slime_type, kind)
+            .zip(MergeInput::of(partner_type, partner_kind))
+            .and_then(|(a, b)| find_recipe(a, b))
+        else {
+            continue;
+        };
+
         // Play merge-complete sound. This only fires once per pair because
         // the already_merged check prevents processing the partner again.
         commands.spawn(AudioPlayer::new(audio.merge_complete.clone()));
@@ -405,12 +576,31 @@ fn execute_merge_system(
         already_merged.push(entity);
         already_merged.push(partner_entity);
 
+        // Split-off children were never part of the army, so they aren't
+        // reported as consumed.
+        commands.trigger(SlimesMergedEvent {
+            team: *team,
+            consumed: [
+                slime_type.copied().filter(|_| !split),
+                partner_type.copied().filter(|_| !partner_split),
+            ],
+        });
+
         commands.entity(entity).despawn();
         commands.entity(partner_entity).despawn();
 
         // Merged slimes from combat merges spawn ready to fight — remove Inert
         // and the spawn-scale animation that spawn_merged_slime adds by default.
-        let merged = spawn_merged_slime(&mut commands, *team, Some(midpoint));
+        let merged = spawn_merge_result(
+            &mut commands,
+            *team,
+            midpoint,
+            result,
+            [
+                MergeIngredient::new(max_health, attacks, block),
+                MergeIngredient::new(partner_max_health, partner_attacks, partner_block),
+            ],
+        );
         commands
             .entity(merged)
             .remove::<(Inert, crate::sprite_modifications::SpriteModification)>();
@@ -429,13 +619,19 @@ fn cancel_merge_system(
     // We use a separate query (instead of checking within the first) because the
     // partner might not have a Merging component anymore if it was already cleaned up.
     alive_check: Query<Entity, Without<Dying>>,
+    indicators: Query<(Entity, &ChildOf), With<MergeIndicator>>,
     mut commands: Commands,
 ) {
     for (entity, merging) in query.iter() {
         // If the partner doesn't exist at all (despawned) or is dying, cancel the merge.
         if alive_check.get(merging.partner).is_err() {
             commands.entity(entity).remove::<Merging>();
-            commands.entity(entity).despawn_children(); // Remove the "!" indicator
+            // Remove the "!" indicator, leaving other children (shields, spears) alone
+            for (indicator, parent) in &indicators {
+                if parent.parent() == entity {
+                    commands.entity(indicator).despawn();
+                }
+            }
         }
     }
 }
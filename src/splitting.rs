@@ -0,0 +1,127 @@
+// Splitting: the inverse of merging.
+//
+// A slime with SplitsOnDeath breaks into smaller normal slimes when its death
+// animation finishes. when_finishes_dying_system despawns the dying slime and
+// leaves a PendingSplit in its place; a moment later the pending split spawns
+// the children at the death position with a short spawn-in animation.
+//
+// Until a pending split resolves, check_round_end_system counts it as a live
+// unit of its team — otherwise killing the last enemy blob would end the
+// round one frame before its children appear.
+//
+// Children are tagged SplitSpawn. They fight like any normal slime, but they
+// were never part of the army, so casualties don't count them.
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::pick_target::Team;
+use crate::setup_round::Inert;
+use crate::spawn_slimes::spawn_normal_slime;
+use crate::sprite_modifications::{LerpType, SpriteModification};
+use crate::{CombatState, GameState};
+
+pub struct SplittingPlugin;
+
+impl Plugin for SplittingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            resolve_pending_splits_system.run_if(in_state(GameState::Combat)),
+        );
+    }
+}
+
+/// Pause between the death animation ending and the children appearing.
+const SPLIT_DELAY: f32 = 0.3;
+/// Length of the children's spawn-in scale animation.
+const SPAWN_IN_TIME: f32 = 0.5;
+/// How far from the death position children land.
+const SPLIT_SPREAD: f32 = 40.0;
+
+/// On-death trait: split into `count` normal slimes, each with
+/// `hp_fraction` of this slime's max HP (at least 1).
+#[derive(Component, Clone, Copy)]
+pub struct SplitsOnDeath {
+    pub count: u32,
+    pub hp_fraction: f32,
+}
+
+/// Merged slimes fall apart into two halves' worth of normals.
+pub const MERGED_SPLIT: SplitsOnDeath = SplitsOnDeath {
+    count: 2,
+    hp_fraction: 0.25,
+};
+
+/// Marker for slimes that came out of a split rather than the army.
+#[derive(Component)]
+pub struct SplitSpawn;
+
+/// A split waiting to spawn its children. Counts as a live unit of `team`
+/// for round-end detection.
+#[derive(Component)]
+pub struct PendingSplit {
+    pub team: Team,
+    pub position: Vec3,
+    pub count: u32,
+    pub hp: i32,
+    pub timer: Timer,
+}
+
+/// Called by when_finishes_dying_system for a slime with SplitsOnDeath.
+pub fn queue_split(
+    commands: &mut Commands,
+    split: SplitsOnDeath,
+    team: Team,
+    position: Vec3,
+    max_hp: i32,
+) {
+    commands.spawn((
+        PendingSplit {
+            team,
+            position,
+            count: split.count,
+            hp: ((max_hp as f32 * split.hp_fraction).round() as i32).max(1),
+            timer: Timer::from_seconds(SPLIT_DELAY, TimerMode::Once),
+        },
+        DespawnOnExit(GameState::Combat),
+    ));
+}
+
+/// Spawns the children of each pending split once its delay is over. They
+/// use a short spawn-in instead of the long one spawn_normal_slime sets up,
+/// and join the fight straight away if one is going on.
+fn resolve_pending_splits_system(
+    mut commands: Commands,
+    mut pending: Query<(Entity, &mut PendingSplit)>,
+    combat_state: Option<Res<State<CombatState>>>,
+    time: Res<Time>,
+) {
+    let fighting = combat_state.is_some_and(|s| *s.get() == CombatState::DuringCombat);
+    let mut rng = rand::thread_rng();
+    for (entity, mut split) in &mut pending {
+        split.timer.tick(time.delta());
+        if !split.timer.is_finished() {
+            continue;
+        }
+
+        for i in 0..split.count {
+            let angle =
+                i as f32 / split.count as f32 * std::f32::consts::TAU + rng.gen_range(-0.3..0.3);
+            let pos = split.position + Vec3::new(angle.cos(), angle.sin(), 0.0) * SPLIT_SPREAD;
+            let child = spawn_normal_slime(&mut commands, split.team, split.hp);
+            if fighting {
+                commands.entity(child).remove::<Inert>();
+            }
+            commands.entity(child).insert((
+                SplitSpawn,
+                Transform::from_translation(pos).with_scale(Vec3::splat(0.25)),
+                SpriteModification {
+                    lerp: LerpType::EaseInOut,
+                    timer: Timer::from_seconds(SPAWN_IN_TIME, TimerMode::Once),
+                },
+            ));
+        }
+        commands.entity(entity).despawn();
+    }
+}
@@ -34,7 +34,7 @@ impl Plugin for StatusPlugin {
 }
 
 /// Present when an entity's position is allowed to be nudged by
-/// external forces (unsmush, etc.).  Removed while merging, pre-merging,
+/// external forces (separation, etc.).  Removed while merging, pre-merging,
 /// or mid-knockback.
 #[derive(Component)]
 pub struct CanBeMoved;
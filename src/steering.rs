@@ -0,0 +1,153 @@
+// Steering: how units actually get where they're going.
+//
+// Nothing sets a unit's position directly any more. Each frame the movement
+// systems in movement.rs build a desired velocity out of a few behaviours
+// and write it to Steering:
+//   - seek:       full speed toward a point.
+//   - arrive:     seek, but slow down near the stop radius and stop inside
+//                 it, so units don't overshoot and jitter.
+//   - separation: push away from bodies that are too close, so a crowd
+//                 spreads out instead of stacking.
+//   - avoidance:  steer sideways around bodies that are standing still in
+//                 the way, so a crowd flows around a unit that's busy
+//                 attacking instead of shoving through it.
+// integrate_system then turns Velocity toward that desired velocity, no
+// faster than Acceleration allows, caps it at Speed, and moves the unit.
+// Footing (icy ground, say) scales both of those while it lasts.
+// Knocked-back units skip all of this and coast on their Velocity until
+// friction stops them (movement.rs).
+// Every behaviour works on whole vectors, so speed is the same in every
+// direction.
+
+use bevy::prelude::*;
+
+/// How fast (units per second) an entity is currently moving.
+#[derive(Component, Default, Clone, Copy)]
+pub struct Velocity(pub Vec2);
+
+/// How quickly an entity can change its velocity, in units per second².
+#[derive(Component, Clone, Copy)]
+pub struct Acceleration(pub f32);
+
+impl Default for Acceleration {
+    fn default() -> Self {
+        Self(DEFAULT_ACCELERATION)
+    }
+}
+
+/// How hard an entity is to shove. A knockback impulse moves a body of mass
+/// 2 half as fast as one of mass 1, and collisions share momentum by mass.
+#[derive(Component, Clone, Copy)]
+pub struct Mass(pub f32);
+
+impl Default for Mass {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// The velocity an entity wants this frame. Rebuilt from scratch every frame.
+#[derive(Component, Default, Clone, Copy)]
+pub struct Steering(pub Vec2);
+
+/// What the ground under a unit does to its movement, set by hazards.rs
+/// while it stands in a hazard zone or a frost aura. `speed` scales its top speed; `grip`
+/// scales its acceleration, so low grip means it slides.
+#[derive(Component, Clone, Copy)]
+pub struct Footing {
+    pub speed: f32,
+    pub grip: f32,
+}
+
+/// Reaches full slime speed in about a sixth of a second.
+const DEFAULT_ACCELERATION: f32 = 800.0;
+/// Start slowing down this far outside the stop radius.
+pub const ARRIVE_SLOW_RADIUS: f32 = 60.0;
+/// How much room a unit takes up, for avoidance.
+pub const BODY_RADIUS: f32 = 30.0;
+/// Bodies closer than this push each other apart.
+pub const SEPARATION_RADIUS: f32 = 40.0;
+/// Separation speed when two bodies are right on top of each other.
+const SEPARATION_STRENGTH: f32 = 150.0;
+/// How far ahead a moving unit looks for something to steer around.
+pub const AVOID_LOOKAHEAD: f32 = 90.0;
+/// Sideways speed used to steer around the nearest blocker ahead.
+const AVOID_STRENGTH: f32 = 120.0;
+
+/// Something a moving unit should steer around rather than walk through.
+#[derive(Clone, Copy)]
+pub struct Blocker {
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+/// Full speed toward `offset` (target minus position).
+pub fn seek(offset: Vec2, max_speed: f32) -> Vec2 {
+    offset.normalize_or_zero() * max_speed
+}
+
+/// Seek, easing off once within `slow_radius` of `stop_radius` and stopping
+/// inside it.
+pub fn arrive(offset: Vec2, max_speed: f32, stop_radius: f32, slow_radius: f32) -> Vec2 {
+    let gap = offset.length() - stop_radius;
+    if gap <= 0.0 {
+        return Vec2::ZERO;
+    }
+    seek(offset, max_speed) * (gap / slow_radius).min(1.0)
+}
+
+/// Push away from every neighbour inside SEPARATION_RADIUS, harder the
+/// closer they are.
+pub fn separation(position: Vec2, neighbours: impl Iterator<Item = Vec2>) -> Vec2 {
+    let mut push = Vec2::ZERO;
+    for other in neighbours {
+        let away = position - other;
+        let distance = away.length();
+        if distance >= SEPARATION_RADIUS {
+            continue;
+        }
+        // Exactly on top of each other: pick a direction rather than none.
+        let dir = if distance > 0.001 {
+            away / distance
+        } else {
+            Vec2::X
+        };
+        push += dir * (1.0 - distance / SEPARATION_RADIUS) * SEPARATION_STRENGTH;
+    }
+    push
+}
+
+/// Sideways nudge around the nearest blocker within `lookahead` on the path
+/// `heading` points along. Zero when nothing is in the way.
+pub fn avoid_blockers(position: Vec2, heading: Vec2, lookahead: f32, blockers: &[Blocker]) -> Vec2 {
+    let Some(dir) = heading.try_normalize() else {
+        return Vec2::ZERO;
+    };
+
+    let nearest = blockers
+        .iter()
+        .filter_map(|b| {
+            let to = b.position - position;
+            let ahead = to.dot(dir);
+            // perp_dot is the sideways distance from our path to the blocker.
+            let side = dir.perp_dot(to);
+            (ahead > 0.0 && ahead < lookahead && side.abs() < b.radius)
+                .then_some((ahead, side, b.radius))
+        })
+        .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let Some((ahead, side, radius)) = nearest else {
+        return Vec2::ZERO;
+    };
+    // Go round whichever side we're already on; straight at it, go left.
+    let away = if side > 0.0 { -dir.perp() } else { dir.perp() };
+    let urgency = (1.0 - ahead / lookahead) * (1.0 - side.abs() / radius);
+    away * AVOID_STRENGTH * urgency
+}
+
+/// Turn `velocity` toward `desired`, changing it by at most
+/// `acceleration * delta`, and cap the result at `max_speed`.
+pub fn steer(velocity: Vec2, desired: Vec2, acceleration: f32, max_speed: f32, delta: f32) -> Vec2 {
+    let change = (desired - velocity).clamp_length_max(acceleration * delta);
+    (velocity + change).clamp_length_max(max_speed)
+}
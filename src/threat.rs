@@ -0,0 +1,339 @@
+// Threat and taunt.
+//
+// Every unit keeps a Threat table: how much damage each enemy has done to it,
+// fed by DamagedEvent. HighestThreat targeting (pick_target.rs) goes after
+// whoever tops that table.
+//
+// Tanks also carry Taunt. Every TAUNT_INTERVAL seconds a tank forces nearby
+// enemies to target it for TAUNT_DURATION (they get Taunted, which
+// pick_target_system respects), and the taunt itself adds threat so the pull
+// lingers a little after it wears off.
+//
+// Press F3 during a fight for the threat overlay: a line from each unit to
+// everyone on its threat table (brighter = more threat), taunt radii around
+// tanks, orange lines from taunted units to their taunter, and each unit's
+// top threat value above its head.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::combat::FloatingText;
+use crate::health::{DamagedEvent, Dying, Health};
+use crate::movement::TargetEntity;
+use crate::pick_target::Team;
+use crate::setup_round::Inert;
+use crate::status::{CanBeTargeted, CanTarget};
+use crate::{CombatState, GameFont, GameState};
+
+pub struct ThreatPlugin;
+
+impl Plugin for ThreatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ThreatOverlay>()
+            .add_observer(on_damaged_add_threat)
+            .add_systems(
+                Update,
+                (taunt_system, taunted_system)
+                    .chain()
+                    .run_if(in_state(CombatState::DuringCombat)),
+            )
+            .add_systems(
+                Update,
+                (
+                    prune_threat_system,
+                    toggle_threat_overlay_system,
+                    threat_overlay_system,
+                    threat_label_system,
+                )
+                    .run_if(in_state(GameState::Combat)),
+            );
+    }
+}
+
+/// Threat added per point of damage dealt.
+const THREAT_PER_DAMAGE: f32 = 1.0;
+const TAUNT_INTERVAL: f32 = 6.0;
+const TAUNT_RADIUS: f32 = 200.0;
+const TAUNT_DURATION: f32 = 2.5;
+/// Threat a taunt adds on each enemy it catches.
+const TAUNT_THREAT: f32 = 5.0;
+
+const TAUNT_COLOR: Color = Color::srgb(1.0, 0.6, 0.1);
+
+/// How much each enemy has hurt this unit, keyed by the enemy.
+#[derive(Component, Default)]
+pub struct Threat(pub HashMap<Entity, f32>);
+
+impl Threat {
+    fn add(&mut self, source: Entity, amount: f32) {
+        *self.0.entry(source).or_default() += amount;
+    }
+
+    /// The biggest single entry, if any.
+    pub fn top(&self) -> Option<(Entity, f32)> {
+        self.0
+            .iter()
+            .map(|(&e, &t)| (e, t))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+    }
+}
+
+/// Periodically pulls nearby enemies onto this unit. Tanks spawn with it.
+#[derive(Component)]
+pub struct Taunt {
+    pub cooldown: Timer,
+    pub radius: f32,
+    pub duration: f32,
+}
+
+impl Default for Taunt {
+    fn default() -> Self {
+        Self {
+            cooldown: Timer::from_seconds(TAUNT_INTERVAL, TimerMode::Repeating),
+            radius: TAUNT_RADIUS,
+            duration: TAUNT_DURATION,
+        }
+    }
+}
+
+/// This unit must target `by` until the timer runs out.
+#[derive(Component)]
+pub struct Taunted {
+    pub by: Entity,
+    pub timer: Timer,
+}
+
+/// Resource — whether the F3 threat overlay is showing.
+#[derive(Resource, Default)]
+pub struct ThreatOverlay(pub bool);
+
+/// Overlay text floating above `0`.
+#[derive(Component)]
+struct ThreatLabel(Entity);
+
+/// Credits damage to whoever dealt it. Hits from attacking children (the
+/// wizard's spear) count for the parent, since the spear can't be targeted.
+fn on_damaged_add_threat(
+    trigger: On<DamagedEvent>,
+    mut commands: Commands,
+    mut tables: Query<Option<&mut Threat>, With<Health>>,
+    parents: Query<&ChildOf, Without<Health>>,
+) {
+    let Some(source) = trigger.source else {
+        return;
+    };
+    let source = parents.get(source).map_or(source, |p| p.parent());
+    let amount = trigger.amount as f32 * THREAT_PER_DAMAGE;
+
+    match tables.get_mut(trigger.entity) {
+        Ok(Some(mut threat)) => threat.add(source, amount),
+        Ok(None) => {
+            let mut threat = Threat::default();
+            threat.add(source, amount);
+            commands.entity(trigger.entity).insert(threat);
+        }
+        Err(_) => {}
+    }
+}
+
+fn taunt_system(
+    mut commands: Commands,
+    mut taunters: Query<
+        (Entity, &Team, &GlobalTransform, &mut Taunt),
+        (Without<Dying>, Without<Inert>),
+    >,
+    mut enemies: Query<
+        (Entity, &Team, &GlobalTransform, Option<&mut Threat>),
+        (With<CanTarget>, With<Health>),
+    >,
+    game_font: Res<GameFont>,
+    time: Res<Time>,
+) {
+    for (taunter, team, transform, mut taunt) in &mut taunters {
+        taunt.cooldown.tick(time.delta());
+        if !taunt.cooldown.just_finished() {
+            continue;
+        }
+
+        let pos = transform.translation();
+        let mut caught = 0;
+        for (enemy, enemy_team, enemy_transform, threat) in &mut enemies {
+            if enemy_team == team || enemy_transform.translation().distance(pos) > taunt.radius {
+                continue;
+            }
+            caught += 1;
+            commands.entity(enemy).insert((
+                TargetEntity(taunter),
+                Taunted {
+                    by: taunter,
+                    timer: Timer::from_seconds(taunt.duration, TimerMode::Once),
+                },
+            ));
+            match threat {
+                Some(mut threat) => threat.add(taunter, TAUNT_THREAT),
+                None => {
+                    let mut threat = Threat::default();
+                    threat.add(taunter, TAUNT_THREAT);
+                    commands.entity(enemy).insert(threat);
+                }
+            }
+        }
+
+        if caught > 0 {
+            commands.spawn((
+                FloatingText(Timer::from_seconds(0.8, TimerMode::Once)),
+                Text2d::new("TAUNT!"),
+                TextFont {
+                    font: game_font.0.clone(),
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(TAUNT_COLOR),
+                Transform::from_xyz(pos.x, pos.y + 50.0, 10.0),
+            ));
+        }
+    }
+}
+
+/// Ends taunts when they run out or the taunter can no longer be targeted.
+fn taunted_system(
+    mut commands: Commands,
+    mut taunted: Query<(Entity, &mut Taunted)>,
+    targetable: Query<(), With<CanBeTargeted>>,
+    time: Res<Time>,
+) {
+    for (entity, mut taunt) in &mut taunted {
+        taunt.timer.tick(time.delta());
+        if taunt.timer.is_finished() || targetable.get(taunt.by).is_err() {
+            commands.entity(entity).remove::<Taunted>();
+        }
+    }
+}
+
+/// Drops threat entries for units that are dead or dying.
+fn prune_threat_system(mut tables: Query<&mut Threat>, targetable: Query<(), With<CanBeTargeted>>) {
+    for mut threat in &mut tables {
+        if threat.0.keys().any(|e| targetable.get(*e).is_err()) {
+            threat.0.retain(|e, _| targetable.get(*e).is_ok());
+        }
+    }
+}
+
+fn toggle_threat_overlay_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut overlay: ResMut<ThreatOverlay>,
+) {
+    if keyboard.just_pressed(KeyCode::F3) {
+        overlay.0 = !overlay.0;
+    }
+}
+
+fn threat_overlay_system(
+    mut gizmos: Gizmos,
+    overlay: Res<ThreatOverlay>,
+    tables: Query<(&GlobalTransform, &Threat)>,
+    taunters: Query<(&GlobalTransform, &Taunt), Without<Dying>>,
+    taunted: Query<(&GlobalTransform, &Taunted)>,
+    positions: Query<&GlobalTransform>,
+) {
+    if !overlay.0 {
+        return;
+    }
+
+    for (transform, threat) in &tables {
+        let Some((_, max)) = threat.top() else {
+            continue;
+        };
+        let pos = transform.translation().truncate();
+        for (source, amount) in &threat.0 {
+            let Ok(source_transform) = positions.get(*source) else {
+                continue;
+            };
+            let alpha = 0.15 + 0.85 * (amount / max);
+            gizmos.line_2d(
+                pos,
+                source_transform.translation().truncate(),
+                Color::srgba(1.0, 0.2, 0.2, alpha),
+            );
+        }
+    }
+
+    for (transform, taunt) in &taunters {
+        // Brightens as the next taunt comes up.
+        let alpha = 0.2 + 0.6 * taunt.cooldown.fraction();
+        gizmos.circle_2d(
+            transform.translation().truncate(),
+            taunt.radius,
+            TAUNT_COLOR.with_alpha(alpha),
+        );
+    }
+
+    for (transform, taunt) in &taunted {
+        let Ok(taunter) = positions.get(taunt.by) else {
+            continue;
+        };
+        gizmos.line_2d(
+            transform.translation().truncate(),
+            taunter.translation().truncate(),
+            TAUNT_COLOR,
+        );
+    }
+}
+
+/// Keeps one label above every unit with a threat table while the overlay
+/// is on, and clears them all when it's off.
+fn threat_label_system(
+    mut commands: Commands,
+    overlay: Res<ThreatOverlay>,
+    units: Query<(Entity, &GlobalTransform, &Threat, Option<&Taunted>)>,
+    mut labels: Query<(Entity, &ThreatLabel, &mut Transform, &mut Text2d)>,
+    game_font: Res<GameFont>,
+) {
+    let mut labelled = Vec::new();
+    for (label_entity, label, mut transform, mut text) in &mut labels {
+        let Some((_, unit_transform, threat, taunted)) =
+            units.get(label.0).ok().filter(|_| overlay.0)
+        else {
+            commands.entity(label_entity).despawn();
+            continue;
+        };
+        let pos = unit_transform.translation();
+        transform.translation = Vec3::new(pos.x, pos.y + 45.0, 20.0);
+        **text = threat_label(threat, taunted);
+        labelled.push(label.0);
+    }
+
+    if !overlay.0 {
+        return;
+    }
+    for (entity, transform, threat, taunted) in &units {
+        if labelled.contains(&entity) {
+            continue;
+        }
+        let pos = transform.translation();
+        commands.spawn((
+            ThreatLabel(entity),
+            Text2d::new(threat_label(threat, taunted)),
+            TextFont {
+                font: game_font.0.clone(),
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(Color::srgb(1.0, 0.5, 0.5)),
+            Transform::from_xyz(pos.x, pos.y + 45.0, 20.0),
+            DespawnOnExit(GameState::Combat),
+        ));
+    }
+}
+
+fn threat_label(threat: &Threat, taunted: Option<&Taunted>) -> String {
+    let top = threat.top().map_or(0.0, |(_, t)| t);
+    match taunted {
+        Some(taunted) => format!(
+            "threat {top:.0} | taunted {:.1}s",
+            taunted.timer.remaining_secs()
+        ),
+        None => format!("threat {top:.0}"),
+    }
+}
@@ -0,0 +1,261 @@
+// Unit stat upgrades bought on the Home screen.
+//
+// The Army in SaveData holds each unit type's *base* stats. Upgrades are
+// stored separately as levels, and `Upgrades::apply` folds them into a copy
+// of the army right before it's handed to `setup_slime_spawn`. Keeping the
+// two apart means the base stats never drift, and tuning the table below
+// takes effect for existing saves without any migration.
+//
+// Every upgradeable stat is one row in UPGRADES: how much one level adds,
+// what the first level costs, how fast the price grows, and the level cap.
+// Rows without a slime type (merge chance and radius) apply to the whole
+// army and sit in the Home screen's merging row.
+
+use serde::{Deserialize, Serialize};
+
+use crate::armies::{Army, SlimeType};
+
+/// One purchasable stat. Each variant maps to a single field on the army.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UpgradeStat {
+    NormalHp,
+    TankHp,
+    TankBlockChance,
+    TankStunChance,
+    WizardHp,
+    WizardSpellRange,
+    WizardAoeDamage,
+    WizardSpearKnockback,
+    MergeChance,
+    MergeRadius,
+}
+
+pub struct UpgradeDef {
+    pub stat: UpgradeStat,
+    /// None for army-wide stats.
+    pub slime_type: Option<SlimeType>,
+    pub label: &'static str,
+    /// Added to the stat per level. Chances are fractions (0.05 = +5%).
+    pub per_level: f32,
+    /// Price of level 1, in goop.
+    pub base_cost: u32,
+    /// Each level costs this many times the previous one.
+    pub cost_growth: f32,
+    pub max_level: u32,
+}
+
+pub const UPGRADES: &[UpgradeDef] = &[
+    UpgradeDef {
+        stat: UpgradeStat::NormalHp,
+        slime_type: Some(SlimeType::Normal),
+        label: "HP",
+        per_level: 1.0,
+        base_cost: 5,
+        cost_growth: 1.5,
+        max_level: 10,
+    },
+    UpgradeDef {
+        stat: UpgradeStat::TankHp,
+        slime_type: Some(SlimeType::Tank),
+        label: "HP",
+        per_level: 2.0,
+        base_cost: 15,
+        cost_growth: 1.5,
+        max_level: 10,
+    },
+    UpgradeDef {
+        stat: UpgradeStat::TankBlockChance,
+        slime_type: Some(SlimeType::Tank),
+        label: "Block",
+        per_level: 0.05,
+        base_cost: 20,
+        cost_growth: 1.8,
+        max_level: 8,
+    },
+    UpgradeDef {
+        stat: UpgradeStat::TankStunChance,
+        slime_type: Some(SlimeType::Tank),
+        label: "Stun",
+        per_level: 0.05,
+        base_cost: 20,
+        cost_growth: 1.8,
+        max_level: 8,
+    },
+    UpgradeDef {
+        stat: UpgradeStat::WizardHp,
+        slime_type: Some(SlimeType::Wizard),
+        label: "HP",
+        per_level: 1.0,
+        base_cost: 15,
+        cost_growth: 1.5,
+        max_level: 10,
+    },
+    UpgradeDef {
+        stat: UpgradeStat::WizardSpellRange,
+        slime_type: Some(SlimeType::Wizard),
+        label: "Range",
+        per_level: 50.0,
+        base_cost: 15,
+        cost_growth: 1.6,
+        max_level: 6,
+    },
+    UpgradeDef {
+        stat: UpgradeStat::WizardAoeDamage,
+        slime_type: Some(SlimeType::Wizard),
+        label: "AoE",
+        per_level: 1.0,
+        base_cost: 30,
+        cost_growth: 2.0,
+        max_level: 5,
+    },
+    UpgradeDef {
+        stat: UpgradeStat::WizardSpearKnockback,
+        slime_type: Some(SlimeType::Wizard),
+        label: "Knock",
+        per_level: 50.0,
+        base_cost: 10,
+        cost_growth: 1.5,
+        max_level: 6,
+    },
+    UpgradeDef {
+        stat: UpgradeStat::MergeChance,
+        slime_type: None,
+        label: "Chance",
+        per_level: 0.0025,
+        base_cost: 20,
+        cost_growth: 1.7,
+        max_level: 6,
+    },
+    UpgradeDef {
+        stat: UpgradeStat::MergeRadius,
+        slime_type: None,
+        label: "Radius",
+        per_level: 25.0,
+        base_cost: 10,
+        cost_growth: 1.5,
+        max_level: 6,
+    },
+];
+
+impl UpgradeStat {
+    pub fn def(self) -> &'static UpgradeDef {
+        UPGRADES
+            .iter()
+            .find(|def| def.stat == self)
+            .expect("every UpgradeStat has a row in UPGRADES")
+    }
+
+    /// Human-readable bonus for a given level, e.g. "+3 HP" or "+15% Block".
+    pub fn describe_bonus(self, level: u32) -> String {
+        let def = self.def();
+        let bonus = def.per_level * level as f32;
+        match self {
+            UpgradeStat::TankBlockChance | UpgradeStat::TankStunChance => {
+                format!("+{}% {}", (bonus * 100.0).round() as i32, def.label)
+            }
+            // Merge chances are tiny, so they need the decimals.
+            UpgradeStat::MergeChance => format!("+{:.2}% {}", bonus * 100.0, def.label),
+            _ => format!("+{} {}", bonus as i32, def.label),
+        }
+    }
+}
+
+impl UpgradeDef {
+    /// Price of going from `level` to `level + 1`, or None at the cap.
+    pub fn cost_at(&self, level: u32) -> Option<u32> {
+        if level >= self.max_level {
+            return None;
+        }
+        Some((self.base_cost as f32 * self.cost_growth.powi(level as i32)).round() as u32)
+    }
+}
+
+/// Purchased upgrade levels. Saved alongside the army; missing fields (older
+/// saves) default to level 0.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Upgrades {
+    pub normal_hp: u32,
+    pub tank_hp: u32,
+    pub tank_block_chance: u32,
+    pub tank_stun_chance: u32,
+    pub wizard_hp: u32,
+    pub wizard_spell_range: u32,
+    pub wizard_aoe_damage: u32,
+    pub wizard_spear_knockback: u32,
+    pub merge_chance: u32,
+    pub merge_radius: u32,
+}
+
+impl Upgrades {
+    pub fn level(&self, stat: UpgradeStat) -> u32 {
+        match stat {
+            UpgradeStat::NormalHp => self.normal_hp,
+            UpgradeStat::TankHp => self.tank_hp,
+            UpgradeStat::TankBlockChance => self.tank_block_chance,
+            UpgradeStat::TankStunChance => self.tank_stun_chance,
+            UpgradeStat::WizardHp => self.wizard_hp,
+            UpgradeStat::WizardSpellRange => self.wizard_spell_range,
+            UpgradeStat::WizardAoeDamage => self.wizard_aoe_damage,
+            UpgradeStat::WizardSpearKnockback => self.wizard_spear_knockback,
+            UpgradeStat::MergeChance => self.merge_chance,
+            UpgradeStat::MergeRadius => self.merge_radius,
+        }
+    }
+
+    pub fn level_mut(&mut self, stat: UpgradeStat) -> &mut u32 {
+        match stat {
+            UpgradeStat::NormalHp => &mut self.normal_hp,
+            UpgradeStat::TankHp => &mut self.tank_hp,
+            UpgradeStat::TankBlockChance => &mut self.tank_block_chance,
+            UpgradeStat::TankStunChance => &mut self.tank_stun_chance,
+            UpgradeStat::WizardHp => &mut self.wizard_hp,
+            UpgradeStat::WizardSpellRange => &mut self.wizard_spell_range,
+            UpgradeStat::WizardAoeDamage => &mut self.wizard_aoe_damage,
+            UpgradeStat::WizardSpearKnockback => &mut self.wizard_spear_knockback,
+            UpgradeStat::MergeChance => &mut self.merge_chance,
+            UpgradeStat::MergeRadius => &mut self.merge_radius,
+        }
+    }
+
+    /// Price of the next level of `stat`, or None if it's maxed out.
+    pub fn next_cost(&self, stat: UpgradeStat) -> Option<u32> {
+        stat.def().cost_at(self.level(stat))
+    }
+
+    /// Total bonus currently added to `stat`.
+    pub fn bonus(&self, stat: UpgradeStat) -> f32 {
+        stat.def().per_level * self.level(stat) as f32
+    }
+
+    /// True if no level exceeds its cap — used to reject tampered share codes.
+    pub fn is_valid(&self) -> bool {
+        UPGRADES
+            .iter()
+            .all(|def| self.level(def.stat) <= def.max_level)
+    }
+
+    /// Returns a copy of `army` with every upgrade applied. Chances are
+    /// clamped so a generous table can't push them past certainty.
+    pub fn apply(&self, army: &Army) -> Army {
+        let mut army = army.clone();
+
+        army.normal.hp += self.bonus(UpgradeStat::NormalHp) as i32;
+
+        army.tanks.hp += self.bonus(UpgradeStat::TankHp) as i32;
+        army.tanks.block_chance =
+            (army.tanks.block_chance + self.bonus(UpgradeStat::TankBlockChance)).min(1.0);
+        army.tanks.stun_chance =
+            (army.tanks.stun_chance + self.bonus(UpgradeStat::TankStunChance)).min(1.0);
+
+        army.wizards.hp += self.bonus(UpgradeStat::WizardHp) as i32;
+        army.wizards.spell_range += self.bonus(UpgradeStat::WizardSpellRange);
+        army.wizards.aoe_damage += self.bonus(UpgradeStat::WizardAoeDamage) as i32;
+        army.wizards.spear_knockback += self.bonus(UpgradeStat::WizardSpearKnockback);
+
+        army.merging.chance = (army.merging.chance + self.bonus(UpgradeStat::MergeChance)).min(1.0);
+        army.merging.radius += self.bonus(UpgradeStat::MergeRadius);
+
+        army
+    }
+}